@@ -0,0 +1,834 @@
+use crate::character::{Ancestry, Background, Character, Class, Feat, VariantRules};
+use crate::content::{ContentKind, ContentLibrary};
+use crate::error::{Error, Result};
+use crate::item::Item;
+use crate::save::{load_character, save_character};
+use crate::spell::Spell;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SCHEMA_VERSION: u32 = 3;
+
+const SCHEMA: &str = "
+CREATE TABLE campaigns (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    rules TEXT NOT NULL DEFAULT '{}'
+);
+CREATE TABLE parties (
+    id INTEGER PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    name TEXT NOT NULL
+);
+CREATE TABLE characters (
+    id INTEGER PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    party_id INTEGER REFERENCES parties(id) ON DELETE SET NULL,
+    name TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX characters_by_level ON characters(campaign_id, level);
+CREATE TABLE content (
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL COLLATE NOCASE,
+    data TEXT NOT NULL,
+    PRIMARY KEY (kind, name)
+);
+CREATE TABLE encounters (
+    id INTEGER PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    notes TEXT NOT NULL
+);
+CREATE TABLE logs (
+    id INTEGER PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    timestamp INTEGER NOT NULL,
+    message TEXT NOT NULL
+);
+";
+
+/// `SCHEMA_MIGRATIONS[i]` upgrades a campaign file from schema version `i + 1` to version `i + 2`.
+const SCHEMA_MIGRATIONS: &[&str] = &[
+    "ALTER TABLE campaigns ADD COLUMN rules TEXT NOT NULL DEFAULT '{}';",
+    // content names ignore case like the library does, the last saved of several spellings wins
+    "CREATE TABLE content_nocase (
+         kind TEXT NOT NULL,
+         name TEXT NOT NULL COLLATE NOCASE,
+         data TEXT NOT NULL,
+         PRIMARY KEY (kind, name)
+     );
+     INSERT OR REPLACE INTO content_nocase SELECT kind, trim(name), data FROM content ORDER BY rowid;
+     DROP TABLE content;
+     ALTER TABLE content_nocase RENAME TO content;",
+];
+
+pub type CampaignId = i64;
+pub type PartyId = i64;
+pub type CharacterId = i64;
+pub type EncounterId = i64;
+
+/// Summary of a stored character, without resolving its content.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CharacterRecord {
+    id: CharacterId,
+    campaign: CampaignId,
+    party: Option<PartyId>,
+    name: String,
+    level: u32,
+}
+
+impl CharacterRecord {
+    pub fn id(&self) -> CharacterId {
+        self.id
+    }
+
+    pub fn campaign(&self) -> CampaignId {
+        self.campaign
+    }
+
+    pub fn party(&self) -> Option<PartyId> {
+        self.party
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Encounter {
+    id: EncounterId,
+    name: String,
+    notes: String,
+}
+
+impl Encounter {
+    pub fn id(&self) -> EncounterId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LogEntry {
+    timestamp: u64,
+    message: String,
+}
+
+impl LogEntry {
+    /// Seconds since the unix epoch.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// All campaign queries. Implemented by both the database and its transactions, so the same calls can be grouped
+/// into a transaction without changing them.
+pub trait CampaignStore {
+    fn connection(&self) -> &Connection;
+
+    // ============================= Campaigns =============================
+
+    fn create_campaign(&self, name: &str) -> Result<CampaignId> {
+        self.connection()
+            .execute("INSERT INTO campaigns (name) VALUES (?1)", params![name])?;
+        Ok(self.connection().last_insert_rowid())
+    }
+
+    fn campaign(&self, name: &str) -> Result<Option<CampaignId>> {
+        Ok(self
+            .connection()
+            .query_row(
+                "SELECT id FROM campaigns WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// The variant rules the campaign plays with.
+    fn campaign_rules(&self, campaign: CampaignId) -> Result<VariantRules> {
+        let rules: String = self
+            .connection()
+            .query_row(
+                "SELECT rules FROM campaigns WHERE id = ?1",
+                params![campaign],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or(Error::NotFound {
+                kind: "campaign",
+                id: campaign,
+            })?;
+        Ok(serde_json::from_str(&rules)?)
+    }
+
+    /// Changes the variant rules of the campaign. Its characters use them when they are loaded.
+    fn set_campaign_rules(&self, campaign: CampaignId, rules: &VariantRules) -> Result<()> {
+        let changed = self.connection().execute(
+            "UPDATE campaigns SET rules = ?2 WHERE id = ?1",
+            params![campaign, serde_json::to_string(rules)?],
+        )?;
+        if changed == 0 {
+            return Err(Error::NotFound {
+                kind: "campaign",
+                id: campaign,
+            });
+        }
+        Ok(())
+    }
+
+    /// Deletes the campaign together with its parties, characters, encounters and logs.
+    fn delete_campaign(&self, campaign: CampaignId) -> Result<()> {
+        self.connection()
+            .execute("DELETE FROM campaigns WHERE id = ?1", params![campaign])?;
+        Ok(())
+    }
+
+    fn create_party(&self, campaign: CampaignId, name: &str) -> Result<PartyId> {
+        self.connection().execute(
+            "INSERT INTO parties (campaign_id, name) VALUES (?1, ?2)",
+            params![campaign, name],
+        )?;
+        Ok(self.connection().last_insert_rowid())
+    }
+
+    // ============================= Characters =============================
+
+    fn insert_character(
+        &self,
+        campaign: CampaignId,
+        party: Option<PartyId>,
+        character: &Character,
+    ) -> Result<CharacterId> {
+        self.connection().execute(
+            "INSERT INTO characters (campaign_id, party_id, name, level, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                campaign,
+                party,
+                character.name(),
+                character.level(),
+                save_character(character)
+            ],
+        )?;
+        Ok(self.connection().last_insert_rowid())
+    }
+
+    /// Saves the character and moves it to `party`.
+    fn update_character(
+        &self,
+        id: CharacterId,
+        party: Option<PartyId>,
+        character: &Character,
+    ) -> Result<()> {
+        let changed = self.connection().execute(
+            "UPDATE characters SET party_id = ?2, name = ?3, level = ?4, data = ?5 WHERE id = ?1",
+            params![
+                id,
+                party,
+                character.name(),
+                character.level(),
+                save_character(character)
+            ],
+        )?;
+        if changed == 0 {
+            return Err(Error::NotFound {
+                kind: "character",
+                id,
+            });
+        }
+        Ok(())
+    }
+
+    /// Loads a character with the variant rules of its campaign.
+    fn load_character<'a>(
+        &self,
+        id: CharacterId,
+        library: &'a ContentLibrary,
+    ) -> Result<Character<'a, 'a, 'a>> {
+        let (data, rules): (String, String) = self
+            .connection()
+            .query_row(
+                "SELECT characters.data, campaigns.rules FROM characters
+                     JOIN campaigns ON campaigns.id = characters.campaign_id
+                     WHERE characters.id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or(Error::NotFound {
+                kind: "character",
+                id,
+            })?;
+        let mut character = load_character(&data, library)?;
+        character.set_variant_rules(serde_json::from_str(&rules)?);
+        Ok(character)
+    }
+
+    fn delete_character(&self, id: CharacterId) -> Result<()> {
+        self.connection()
+            .execute("DELETE FROM characters WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Lists the characters of a campaign that are at least `min_level`, ordered by name.
+    fn characters(&self, campaign: CampaignId, min_level: u32) -> Result<Vec<CharacterRecord>> {
+        let mut statement = self.connection().prepare(
+            "SELECT id, campaign_id, party_id, name, level FROM characters
+                 WHERE campaign_id = ?1 AND level >= ?2 ORDER BY name, id",
+        )?;
+        let rows = statement.query_map(params![campaign, min_level], |row| {
+            Ok(CharacterRecord {
+                id: row.get(0)?,
+                campaign: row.get(1)?,
+                party: row.get(2)?,
+                name: row.get(3)?,
+                level: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    fn party_members(&self, party: PartyId) -> Result<Vec<CharacterRecord>> {
+        let mut statement = self.connection().prepare(
+            "SELECT id, campaign_id, party_id, name, level FROM characters
+                 WHERE party_id = ?1 ORDER BY name, id",
+        )?;
+        let rows = statement.query_map(params![party], |row| {
+            Ok(CharacterRecord {
+                id: row.get(0)?,
+                campaign: row.get(1)?,
+                party: row.get(2)?,
+                name: row.get(3)?,
+                level: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    // ============================= Content =============================
+
+    fn save_ancestry(&self, ancestry: &Ancestry) -> Result<()> {
+        save_content(
+            self.connection(),
+            ContentKind::Ancestry,
+            ancestry.name(),
+            ancestry,
+        )
+    }
+
+    fn save_background(&self, background: &Background) -> Result<()> {
+        save_content(
+            self.connection(),
+            ContentKind::Background,
+            background.name(),
+            background,
+        )
+    }
+
+    fn save_class(&self, class: &Class) -> Result<()> {
+        save_content(self.connection(), ContentKind::Class, class.name(), class)
+    }
+
+    fn save_feat(&self, feat: &Feat) -> Result<()> {
+        save_content(self.connection(), ContentKind::Feat, feat.name(), feat)
+    }
+
+    fn save_item(&self, item: &Item) -> Result<()> {
+        save_content(self.connection(), ContentKind::Item, item.name(), item)
+    }
+
+    fn save_spell(&self, spell: &Spell) -> Result<()> {
+        save_content(self.connection(), ContentKind::Spell, spell.name(), spell)
+    }
+
+    /// Stores every entry of the library, replacing entries with the same name.
+    fn save_library(&self, library: &ContentLibrary) -> Result<()> {
+        for ancestry in library.ancestries() {
+            self.save_ancestry(ancestry)?;
+        }
+        for background in library.backgrounds() {
+            self.save_background(background)?;
+        }
+        for class in library.classes() {
+            self.save_class(class)?;
+        }
+        for feat in library.feats() {
+            self.save_feat(feat)?;
+        }
+        for item in library.items() {
+            self.save_item(item)?;
+        }
+        for spell in library.spells() {
+            self.save_spell(spell)?;
+        }
+        Ok(())
+    }
+
+    fn load_library(&self) -> Result<ContentLibrary> {
+        let mut statement = self
+            .connection()
+            .prepare("SELECT kind, name, data FROM content")?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut library = ContentLibrary::new();
+        for row in rows {
+            let (kind, name, data) = row?;
+            match kind.as_str() {
+                "ancestry" => library.add_ancestry(serde_json::from_str(&data)?),
+                "background" => library.add_background(serde_json::from_str(&data)?),
+                "class" => library.add_class(serde_json::from_str(&data)?),
+                "feat" => library.add_feat(serde_json::from_str(&data)?),
+                "item" => library.add_item(serde_json::from_str(&data)?),
+                "spell" => library.add_spell(serde_json::from_str(&data)?),
+                _ => {
+                    return Err(Error::InvalidFormat(format!(
+                        "{:?} has unknown content kind {:?}",
+                        name, kind
+                    )))
+                }
+            }
+        }
+        Ok(library)
+    }
+
+    fn delete_content(&self, kind: ContentKind, name: &str) -> Result<()> {
+        self.connection().execute(
+            "DELETE FROM content WHERE kind = ?1 AND name = ?2",
+            params![kind.key(), name.trim()],
+        )?;
+        Ok(())
+    }
+
+    // ============================= Encounters and logs =============================
+
+    fn insert_encounter(
+        &self,
+        campaign: CampaignId,
+        name: &str,
+        notes: &str,
+    ) -> Result<EncounterId> {
+        self.connection().execute(
+            "INSERT INTO encounters (campaign_id, name, notes) VALUES (?1, ?2, ?3)",
+            params![campaign, name, notes],
+        )?;
+        Ok(self.connection().last_insert_rowid())
+    }
+
+    fn encounters(&self, campaign: CampaignId) -> Result<Vec<Encounter>> {
+        let mut statement = self
+            .connection()
+            .prepare("SELECT id, name, notes FROM encounters WHERE campaign_id = ?1 ORDER BY id")?;
+        let rows = statement.query_map(params![campaign], |row| {
+            Ok(Encounter {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                notes: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    fn log(&self, campaign: CampaignId, message: &str) -> Result<()> {
+        self.connection().execute(
+            "INSERT INTO logs (campaign_id, timestamp, message) VALUES (?1, ?2, ?3)",
+            params![campaign, now() as i64, message],
+        )?;
+        Ok(())
+    }
+
+    fn logs(&self, campaign: CampaignId) -> Result<Vec<LogEntry>> {
+        let mut statement = self
+            .connection()
+            .prepare("SELECT timestamp, message FROM logs WHERE campaign_id = ?1 ORDER BY id")?;
+        let rows = statement.query_map(params![campaign], |row| {
+            Ok(LogEntry {
+                timestamp: row.get::<_, i64>(0)? as u64,
+                message: row.get(1)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+}
+
+fn save_content<T: serde::Serialize>(
+    connection: &Connection,
+    kind: ContentKind,
+    name: &str,
+    content: &T,
+) -> Result<()> {
+    let data = serde_json::to_string(content)?;
+    connection.execute(
+        "INSERT OR REPLACE INTO content (kind, name, data) VALUES (?1, ?2, ?3)",
+        params![kind.key(), name.trim(), data],
+    )?;
+    Ok(())
+}
+
+/// Runs the schema scripts and records the new version in one transaction, so a failing script leaves the campaign
+/// file as it was.
+fn upgrade(connection: &mut Connection, scripts: &[&str], version: u32) -> Result<()> {
+    let transaction = connection.transaction()?;
+    for script in scripts {
+        transaction.execute_batch(script)?;
+    }
+    transaction.pragma_update(None, "user_version", version)?;
+    transaction.commit()?;
+    Ok(())
+}
+
+/// A campaign file backed by an embedded SQLite database.
+pub struct CampaignDatabase {
+    connection: Connection,
+}
+
+impl CampaignDatabase {
+    /// Opens the campaign file at `path`, creating it if it doesn't exist yet.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<CampaignDatabase> {
+        CampaignDatabase::setup(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<CampaignDatabase> {
+        CampaignDatabase::setup(Connection::open_in_memory()?)
+    }
+
+    fn setup(mut connection: Connection) -> Result<CampaignDatabase> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        let version: u32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        let scripts = match version {
+            0 => &[SCHEMA][..],
+            1..=SCHEMA_VERSION => &SCHEMA_MIGRATIONS[version as usize - 1..],
+            _ => {
+                return Err(Error::UnsupportedVersion {
+                    version,
+                    newest: SCHEMA_VERSION,
+                })
+            }
+        };
+        upgrade(&mut connection, scripts, SCHEMA_VERSION)?;
+        Ok(CampaignDatabase { connection })
+    }
+
+    /// Runs `f` in a transaction. Nothing is written unless `f` succeeds.
+    pub fn transaction<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&CampaignTransaction) -> Result<T>,
+    {
+        let transaction = CampaignTransaction {
+            transaction: self.connection.transaction()?,
+        };
+        let result = f(&transaction)?;
+        transaction.transaction.commit()?;
+        Ok(result)
+    }
+}
+
+impl CampaignStore for CampaignDatabase {
+    fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+pub struct CampaignTransaction<'a> {
+    transaction: Transaction<'a>,
+}
+
+impl CampaignStore for CampaignTransaction<'_> {
+    fn connection(&self) -> &Connection {
+        &self.transaction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{AbilityBoostChoice, AbilityScoreType, Size};
+    use crate::item::Bulk;
+
+    fn library() -> ContentLibrary {
+        let mut library = ContentLibrary::new();
+        library.add_class(Class::new(
+            "Fighter".to_string(),
+            AbilityScoreType::Strength,
+            10,
+        ));
+        library.add_ancestry(Ancestry::new(
+            "Human".to_string(),
+            8,
+            Size::Medium,
+            25,
+            vec![AbilityBoostChoice::free(), AbilityBoostChoice::free()],
+        ));
+        library.add_background(Background::new(
+            "Warrior".to_string(),
+            "".to_string(),
+            vec![],
+            vec![],
+            None,
+        ));
+        library.add_item(Item::new("Rope", 0, Bulk::light()));
+        library.add_spell(Spell::new("Shield", 0, 1));
+        library
+    }
+
+    fn character<'a>(library: &'a ContentLibrary, name: &str, level: u32) -> Character<'a, 'a, 'a> {
+        let mut character = Character::new(
+            name,
+            library.class("Fighter").unwrap(),
+            library.ancestry("Human").unwrap(),
+            &[AbilityScoreType::Strength, AbilityScoreType::Dexterity],
+            library.background("Warrior").unwrap(),
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        for _ in 1..level {
+            character.level_up();
+        }
+        character
+    }
+
+    #[test]
+    fn character_crud() {
+        let library = library();
+        let db = CampaignDatabase::open_in_memory().unwrap();
+        let campaign = db.create_campaign("Age of Ashes").unwrap();
+
+        let id = db
+            .insert_character(campaign, None, &character(&library, "Bob", 1))
+            .unwrap();
+        assert_eq!(db.load_character(id, &library).unwrap().level(), 1);
+
+        let party = db.create_party(campaign, "Heroes").unwrap();
+        db.update_character(id, Some(party), &character(&library, "Bob", 3))
+            .unwrap();
+        assert_eq!(db.load_character(id, &library).unwrap().level(), 3);
+        assert_eq!(db.party_members(party).unwrap()[0].id(), id);
+
+        db.delete_character(id).unwrap();
+        assert!(db.load_character(id, &library).is_err());
+    }
+
+    #[test]
+    fn characters_filters_by_campaign_and_level() {
+        let library = library();
+        let db = CampaignDatabase::open_in_memory().unwrap();
+        let ashes = db.create_campaign("Age of Ashes").unwrap();
+        let other = db.create_campaign("Extinction Curse").unwrap();
+        let party = db.create_party(ashes, "Heroes").unwrap();
+
+        db.insert_character(ashes, Some(party), &character(&library, "Bob", 5))
+            .unwrap();
+        db.insert_character(ashes, Some(party), &character(&library, "Alice", 2))
+            .unwrap();
+        db.insert_character(ashes, None, &character(&library, "Carl", 7))
+            .unwrap();
+        db.insert_character(other, None, &character(&library, "Dora", 9))
+            .unwrap();
+
+        let names: Vec<_> = db
+            .characters(ashes, 5)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["Bob", "Carl"]);
+        assert_eq!(db.party_members(party).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn failed_transaction_writes_nothing() {
+        let library = library();
+        let mut db = CampaignDatabase::open_in_memory().unwrap();
+        let campaign = db.create_campaign("Age of Ashes").unwrap();
+        let bob = db
+            .insert_character(campaign, None, &character(&library, "Bob", 1))
+            .unwrap();
+
+        let result: Result<()> = db.transaction(|tx| {
+            tx.update_character(bob, None, &character(&library, "Bob", 2))?;
+            tx.update_character(bob + 1, None, &character(&library, "Nobody", 2))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(db.load_character(bob, &library).unwrap().level(), 1);
+    }
+
+    #[test]
+    fn characters_use_campaign_rules() {
+        let library = library();
+        let db = CampaignDatabase::open_in_memory().unwrap();
+        let campaign = db.create_campaign("Age of Ashes").unwrap();
+        let id = db
+            .insert_character(campaign, None, &character(&library, "Bob", 1))
+            .unwrap();
+        assert_eq!(db.campaign_rules(campaign).unwrap(), VariantRules::new());
+
+        let mut rules = VariantRules::new();
+        rules.set_automatic_bonus_progression(true);
+        db.set_campaign_rules(campaign, &rules).unwrap();
+
+        assert_eq!(db.campaign_rules(campaign).unwrap(), rules);
+        assert_eq!(
+            *db.load_character(id, &library).unwrap().variant_rules(),
+            rules
+        );
+        assert!(db.set_campaign_rules(campaign + 1, &rules).is_err());
+    }
+
+    #[test]
+    fn content_roundtrip() {
+        let db = CampaignDatabase::open_in_memory().unwrap();
+        db.save_library(&library()).unwrap();
+        db.delete_content(ContentKind::Background, "Warrior")
+            .unwrap();
+
+        let library = db.load_library().unwrap();
+
+        assert_eq!(library.class("Fighter").unwrap().hp_increment(), 10);
+        assert_eq!(library.ancestry("Human").unwrap().ability_boosts().len(), 2);
+        assert!(library.background("Warrior").is_none());
+        assert_eq!(library.item("rope").unwrap().bulk(), Bulk::light());
+        assert!(library.spell("Shield").unwrap().is_cantrip());
+    }
+
+    #[test]
+    fn content_names_ignore_case() {
+        let db = CampaignDatabase::open_in_memory().unwrap();
+        db.save_class(&Class::new(
+            "Fighter".to_string(),
+            AbilityScoreType::Strength,
+            10,
+        ))
+        .unwrap();
+        db.save_class(&Class::new(
+            "fighter ".to_string(),
+            AbilityScoreType::Strength,
+            8,
+        ))
+        .unwrap();
+
+        let count: u32 = db
+            .connection()
+            .query_row("SELECT count(*) FROM content", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            db.load_library()
+                .unwrap()
+                .class("FIGHTER")
+                .unwrap()
+                .hp_increment(),
+            8
+        );
+        db.delete_content(ContentKind::Class, "FIGHTER").unwrap();
+        assert!(db.load_library().unwrap().class("Fighter").is_none());
+    }
+
+    #[test]
+    fn older_schemas_are_migrated() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE campaigns (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE);
+                 CREATE TABLE content (
+                     kind TEXT NOT NULL, name TEXT NOT NULL, data TEXT NOT NULL,
+                     PRIMARY KEY (kind, name)
+                 );
+                 PRAGMA user_version = 1;",
+            )
+            .unwrap();
+        let class = |hp| {
+            serde_json::to_string(&Class::new(
+                "Fighter".to_string(),
+                AbilityScoreType::Strength,
+                hp,
+            ))
+            .unwrap()
+        };
+        connection
+            .execute(
+                "INSERT INTO content VALUES ('class', 'Fighter', ?1), ('class', 'fighter', ?2)",
+                params![class(10), class(8)],
+            )
+            .unwrap();
+
+        let db = CampaignDatabase::setup(connection).unwrap();
+
+        let library = db.load_library().unwrap();
+        assert_eq!(library.classes().count(), 1);
+        assert_eq!(library.class("Fighter").unwrap().hp_increment(), 8);
+    }
+
+    #[test]
+    fn failed_migrations_change_nothing() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE campaigns (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE);
+                 PRAGMA user_version = 1;",
+            )
+            .unwrap();
+
+        // the content table is missing, so the second migration fails after creating its new table
+        assert!(upgrade(&mut connection, SCHEMA_MIGRATIONS, SCHEMA_VERSION).is_err());
+
+        let version: u32 = connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 1);
+        assert!(connection.prepare("SELECT rules FROM campaigns").is_err());
+        assert!(connection.prepare("SELECT * FROM content_nocase").is_err());
+    }
+
+    #[test]
+    fn logs_and_encounters() {
+        let db = CampaignDatabase::open_in_memory().unwrap();
+        let campaign = db.create_campaign("Age of Ashes").unwrap();
+
+        db.insert_encounter(campaign, "Goblin ambush", "4 goblins")
+            .unwrap();
+        db.log(campaign, "The party rests.").unwrap();
+
+        assert_eq!(db.encounters(campaign).unwrap()[0].name(), "Goblin ambush");
+        assert_eq!(db.logs(campaign).unwrap()[0].message(), "The party rests.");
+    }
+}
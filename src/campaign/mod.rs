@@ -0,0 +1,12 @@
+mod database;
+
+pub use database::CampaignDatabase;
+pub use database::CampaignId;
+pub use database::CampaignStore;
+pub use database::CampaignTransaction;
+pub use database::CharacterId;
+pub use database::CharacterRecord;
+pub use database::Encounter;
+pub use database::EncounterId;
+pub use database::LogEntry;
+pub use database::PartyId;
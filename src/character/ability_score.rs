@@ -1,4 +1,7 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct AbilityScore {
@@ -14,6 +17,10 @@ impl AbilityScore {
         self.value
     }
 
+    pub fn modifier(&self) -> i32 {
+        (self.value as i32 - 10).div_euclid(2)
+    }
+
     pub fn boost(&mut self) {
         if self.value < 18 {
             self.value += 2
@@ -23,7 +30,7 @@ impl AbilityScore {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum AbilityScoreType {
     Strength,
     Dexterity,
@@ -33,7 +40,61 @@ pub enum AbilityScoreType {
     Charisma,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+impl AbilityScoreType {
+    pub fn all() -> [AbilityScoreType; 6] {
+        [
+            AbilityScoreType::Strength,
+            AbilityScoreType::Dexterity,
+            AbilityScoreType::Constitution,
+            AbilityScoreType::Intelligence,
+            AbilityScoreType::Wisdom,
+            AbilityScoreType::Charisma,
+        ]
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            AbilityScoreType::Strength => "str",
+            AbilityScoreType::Dexterity => "dex",
+            AbilityScoreType::Constitution => "con",
+            AbilityScoreType::Intelligence => "int",
+            AbilityScoreType::Wisdom => "wis",
+            AbilityScoreType::Charisma => "cha",
+        }
+    }
+
+    /// Parses the usual three letter abbreviation (e.g. `str` or `Dex`), ignoring case.
+    pub fn from_abbreviation(abbreviation: &str) -> Option<AbilityScoreType> {
+        AbilityScoreType::all()
+            .iter()
+            .find(|score| score.abbreviation().eq_ignore_ascii_case(abbreviation))
+            .copied()
+    }
+}
+
+/// Which set of ability boosts a choice belongs to, so errors can be attributed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BoostSource {
+    Ancestry,
+    Background,
+    /// The free boosts every character gets at level 1.
+    Free,
+    /// The free boosts gained at the given level.
+    Level(u32),
+}
+
+impl fmt::Display for BoostSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoostSource::Ancestry => write!(f, "ancestry"),
+            BoostSource::Background => write!(f, "background"),
+            BoostSource::Free => write!(f, "free"),
+            BoostSource::Level(level) => write!(f, "level {}", level),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct AbilityBoostFree {}
 
 impl AbilityBoostFree {
@@ -42,25 +103,31 @@ impl AbilityBoostFree {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct AbilityBoostRestricted {
     options: Vec<AbilityScoreType>,
 }
 
 impl AbilityBoostRestricted {
-    pub fn choose(&self, boost: AbilityScoreType) -> Result<AbilityScoreType, String> {
+    pub fn options(&self) -> &Vec<AbilityScoreType> {
+        &self.options
+    }
+
+    pub fn choose(&self, boost: AbilityScoreType) -> Result<AbilityScoreType> {
         if self.options.contains(&boost) {
             Ok(boost)
         } else {
-            Err(format!(
-                "Boost to {:?} is not a valid choice! Options: {:?}",
-                boost, self.options
-            ))
+            Err(Error::InvalidBoostChoice {
+                boost_source: None,
+                index: 0,
+                choice: boost,
+                options: self.options.clone(),
+            })
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AbilityBoostChoice {
     Free(AbilityBoostFree),
     Restricted(AbilityBoostRestricted),
@@ -84,45 +151,56 @@ impl AbilityBoostChoice {
 }
 
 pub trait AbilityBoostChoiceSet {
-    fn apply_choices(
-        &self,
-        choices: &Vec<AbilityScoreType>,
-    ) -> Result<HashSet<AbilityScoreType>, String>;
+    /// Resolves the boosts using one choice per free or restricted boost. Errors report the position of the
+    /// offending boost within the set, `Error::with_boost_source` adds which set it was.
+    fn apply_choices(&self, choices: &[AbilityScoreType]) -> Result<HashSet<AbilityScoreType>>;
 }
 impl AbilityBoostChoiceSet for Vec<AbilityBoostChoice> {
-    fn apply_choices(
-        &self,
-        choices: &Vec<AbilityScoreType>,
-    ) -> Result<HashSet<AbilityScoreType>, String> {
+    fn apply_choices(&self, choices: &[AbilityScoreType]) -> Result<HashSet<AbilityScoreType>> {
+        let expected = self
+            .iter()
+            .filter(|boost| !matches!(boost, AbilityBoostChoice::Predetermined(_)))
+            .count();
         let mut choices_iter = choices.iter();
         let mut result = HashSet::with_capacity(self.len());
-        for boost in self.iter() {
+        for (index, boost) in self.iter().enumerate() {
             let choice = match boost {
                 AbilityBoostChoice::Predetermined(boost) => *boost,
                 AbilityBoostChoice::Free(_) | AbilityBoostChoice::Restricted(_) => {
                     if let Some(choice) = choices_iter.next() {
                         match boost {
                             AbilityBoostChoice::Free(_) => *choice,
-                            AbilityBoostChoice::Restricted(options) => options.choose(*choice)?,
+                            AbilityBoostChoice::Restricted(options) => options
+                                .choose(*choice)
+                                .map_err(|error| error.at_index(index))?,
                             _ => panic!("should never happen"),
                         }
                     } else {
-                        return Err("Too few boost choices!".to_string());
+                        return Err(Error::TooFewBoostChoices {
+                            boost_source: None,
+                            expected,
+                            provided: choices.len(),
+                        });
                     }
                 }
             };
             if result.contains(&choice) {
-                return Err(format!(
-                    "Duplicte boost choice! {:?} was already chosen!",
-                    choice
-                ));
+                return Err(Error::DuplicateBoost {
+                    boost_source: None,
+                    index,
+                    choice,
+                });
             } else {
                 result.insert(choice);
             }
         }
 
-        if let Some(_) = choices_iter.next() {
-            Err("Too many boost choices!".to_string())
+        if choices_iter.next().is_some() {
+            Err(Error::TooManyBoostChoices {
+                boost_source: None,
+                expected,
+                provided: choices.len(),
+            })
         } else {
             Ok(result)
         }
@@ -172,6 +250,17 @@ impl AbilityScoreSet {
         }
     }
 
+    pub fn set(&mut self, ability_score: AbilityScoreType, value: AbilityScore) {
+        match ability_score {
+            AbilityScoreType::Strength => self.strength = value,
+            AbilityScoreType::Dexterity => self.dexterity = value,
+            AbilityScoreType::Constitution => self.constitution = value,
+            AbilityScoreType::Intelligence => self.intelligence = value,
+            AbilityScoreType::Wisdom => self.wisdom = value,
+            AbilityScoreType::Charisma => self.charisma = value,
+        }
+    }
+
     pub fn get(&self, ability_score: AbilityScoreType) -> AbilityScore {
         match ability_score {
             AbilityScoreType::Strength => self.strength,
@@ -205,6 +294,14 @@ mod tests {
         assert_eq!(score.value(), 19);
     }
 
+    #[test]
+    fn ability_score_modifier() {
+        assert_eq!(AbilityScore::new(10).modifier(), 0);
+        assert_eq!(AbilityScore::new(19).modifier(), 4);
+        assert_eq!(AbilityScore::new(8).modifier(), -1);
+        assert_eq!(AbilityScore::new(7).modifier(), -2);
+    }
+
     #[test]
     fn ability_score_set_boost() {
         let mut set = AbilityScoreSet::new();
@@ -297,9 +394,7 @@ mod ability_boost_choice_tests {
             AbilityBoostChoice::free(),
         ];
 
-        let boosts = set
-            .apply_choices(&vec![AbilityScoreType::Dexterity])
-            .unwrap();
+        let boosts = set.apply_choices(&[AbilityScoreType::Dexterity]).unwrap();
 
         assert_eq!(
             boosts,
@@ -320,11 +415,8 @@ mod ability_boost_choice_tests {
             AbilityBoostChoice::free(),
         ];
 
-        set.apply_choices(&vec![
-            AbilityScoreType::Dexterity,
-            AbilityScoreType::Strength,
-        ])
-        .unwrap();
+        set.apply_choices(&[AbilityScoreType::Dexterity, AbilityScoreType::Strength])
+            .unwrap();
     }
 
     #[test]
@@ -336,7 +428,7 @@ mod ability_boost_choice_tests {
             AbilityBoostChoice::free(),
         ];
 
-        set.apply_choices(&vec![]).unwrap();
+        set.apply_choices(&[]).unwrap();
     }
 
     #[test]
@@ -348,7 +440,44 @@ mod ability_boost_choice_tests {
             AbilityBoostChoice::free(),
         ];
 
-        set.apply_choices(&vec![AbilityScoreType::Strength])
-            .unwrap();
+        set.apply_choices(&[AbilityScoreType::Strength]).unwrap();
+    }
+
+    #[test]
+    fn ability_boost_set_errors_are_structured() {
+        let set = vec![
+            AbilityBoostChoice::predetermined(AbilityScoreType::Strength),
+            AbilityBoostChoice::restricted(hashset![
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Constitution
+            ]),
+            AbilityBoostChoice::free(),
+        ];
+
+        match set.apply_choices(&[AbilityScoreType::Wisdom, AbilityScoreType::Charisma]) {
+            Err(Error::InvalidBoostChoice {
+                index: 1,
+                choice: AbilityScoreType::Wisdom,
+                options,
+                ..
+            }) => assert_eq!(options.len(), 2),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(matches!(
+            set.apply_choices(&[AbilityScoreType::Dexterity, AbilityScoreType::Strength]),
+            Err(Error::DuplicateBoost {
+                index: 2,
+                choice: AbilityScoreType::Strength,
+                ..
+            })
+        ));
+        assert!(matches!(
+            set.apply_choices(&[AbilityScoreType::Dexterity]),
+            Err(Error::TooFewBoostChoices {
+                expected: 2,
+                provided: 1,
+                ..
+            })
+        ));
     }
 }
@@ -1,13 +1,19 @@
 use crate::character::Size;
 use crate::character::{AbilityBoostChoice, AbilityBoostChoiceSet, AbilityScoreType};
+use crate::content::Rarity;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Ancestry {
     name: String,
     base_hp: u32,
     size: Size,
     speed: u32,
     ability_boosts: Vec<AbilityBoostChoice>,
+    #[serde(default)]
+    rarity: Rarity,
+    #[serde(default)]
+    access: Option<String>,
 }
 
 impl Ancestry {
@@ -24,6 +30,8 @@ impl Ancestry {
             size,
             speed,
             ability_boosts,
+            rarity: Rarity::Common,
+            access: None,
         }
     }
 
@@ -46,4 +54,21 @@ impl Ancestry {
     pub fn ability_boosts(&self) -> &Vec<AbilityBoostChoice> {
         &self.ability_boosts
     }
+
+    pub fn rarity(&self) -> Rarity {
+        self.rarity
+    }
+
+    pub fn set_rarity(&mut self, rarity: Rarity) {
+        self.rarity = rarity;
+    }
+
+    /// What grants access if it isn't common.
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+
+    pub fn set_access(&mut self, access: Option<&str>) {
+        self.access = access.map(str::to_string);
+    }
 }
@@ -1,17 +1,36 @@
-use crate::character::AbilityBoostChoice;
+use crate::character::{AbilityBoostChoice, Feat, SkillTrainingChoice};
+use crate::content::Rarity;
+use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Background {
     name: String,
     description: String,
     ability_boosts: Vec<AbilityBoostChoice>,
+    skill_trainings: Vec<SkillTrainingChoice>,
+    skill_feat: Option<Feat>,
+    #[serde(default)]
+    rarity: Rarity,
+    #[serde(default)]
+    access: Option<String>,
 }
 
 impl Background {
-    pub fn new(name: String, description: String, ability_boosts: Vec<AbilityBoostChoice>) -> Self {
+    pub fn new(
+        name: String,
+        description: String,
+        ability_boosts: Vec<AbilityBoostChoice>,
+        skill_trainings: Vec<SkillTrainingChoice>,
+        skill_feat: Option<Feat>,
+    ) -> Self {
         Self {
             name,
             description,
             ability_boosts,
+            skill_trainings,
+            skill_feat,
+            rarity: Rarity::Common,
+            access: None,
         }
     }
 
@@ -26,4 +45,30 @@ impl Background {
     pub fn ability_boosts(&self) -> &Vec<AbilityBoostChoice> {
         &self.ability_boosts
     }
+
+    /// The skills trained by this background, including its Lore skill.
+    pub fn skill_trainings(&self) -> &Vec<SkillTrainingChoice> {
+        &self.skill_trainings
+    }
+
+    pub fn skill_feat(&self) -> Option<&Feat> {
+        self.skill_feat.as_ref()
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        self.rarity
+    }
+
+    pub fn set_rarity(&mut self, rarity: Rarity) {
+        self.rarity = rarity;
+    }
+
+    /// What grants access if it isn't common.
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+
+    pub fn set_access(&mut self, access: Option<&str>) {
+        self.access = access.map(str::to_string);
+    }
 }
@@ -1,9 +1,27 @@
+use crate::combat::DamageFormula;
+use crate::dice::{Check, DiceExpression, DiceRng, Visibility};
+use crate::downtime::{CraftingProject, FormulaBook};
+use crate::error::{Error, Result};
+use crate::item::{
+    starting_wealth, Armor, ArmorCategory, ArmorTrait, ConsumableOutcome, Inventory, Item, ItemId,
+    ItemState, Price, Purse, Transaction, TransactionKind, Weapon, WeaponCategory, WeaponTrait,
+};
+use crate::spell::SpellcastingEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::character::{
+    attack_potency, defense_potency, devastating_dice, perception_potency, save_potency,
+    skill_potency_slots, VariantRules,
+};
 use crate::character::{
     AbilityBoostChoice, AbilityBoostChoiceSet, AbilityScore, AbilityScoreSet, AbilityScoreType,
-    Ancestry, Background, Class, Health,
+    Ancestry, Background, BoostSource, Class, Feat, Health, Modifier, ModifierBreakdown,
+    ModifierType, Proficiency, Selector, Skill, SkillSet, SkillTrainingChoiceSet, Statistic,
+    StatisticCheck, StatisticRoll,
 };
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Size {
     Tiny,
     Small,
@@ -13,30 +31,88 @@ pub enum Size {
     Gargantuan,
 }
 
+/// How weighed down a character is by the Bulk they carry.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum Encumbrance {
+    Unencumbered,
+    /// Clumsy 1 and a -10 foot penalty to Speed.
+    Encumbered,
+    /// Carrying more than the maximum Bulk. Has the same effects as being encumbered.
+    Overloaded,
+}
+
 pub struct Character<'class, 'ancestry, 'background> {
     name: String,
     description: String,
-    class: &'class Class,
-    ancestry: &'ancestry Ancestry,
-    background: &'background Background,
-    level: u32,
+    pub(super) class: &'class Class,
+    pub(super) ancestry: &'ancestry Ancestry,
+    pub(super) background: &'background Background,
+    pub(super) level: u32,
     hp: Health,
     speed: u32,
     size: Size,
 
-    ability_scores: AbilityScoreSet,
+    pub(super) ability_scores: AbilityScoreSet,
+    skills: SkillSet,
+    /// Proficiencies in Perception and saves, skills are kept in `skills`.
+    proficiencies: HashMap<Statistic, Proficiency>,
+    weapon_proficiencies: HashMap<WeaponCategory, Proficiency>,
+    armor_proficiencies: HashMap<ArmorCategory, Proficiency>,
+    pub(super) feats: Vec<Feat>,
+    /// Active bonuses and penalties.
+    pub(super) modifiers: Vec<Modifier>,
+    pub(super) inventory: Inventory,
+    pub(super) wealth: Purse,
+    /// Every purchase and sale, oldest first.
+    pub(super) transactions: Vec<Transaction>,
+    pub(super) formula_book: FormulaBook,
+    /// Items being crafted, oldest first.
+    pub(super) crafting_projects: Vec<CraftingProject>,
+    variant_rules: VariantRules,
+    /// Skill potency bonuses chosen for automatic bonus progression.
+    skill_potencies: BTreeMap<Skill, u32>,
+    /// Names of content the GM granted access to, in lowercase.
+    pub(super) access: BTreeSet<String>,
+    pub(super) spellcasting: Vec<SpellcastingEntry>,
+}
+
+/// Every class is at least trained in Perception and all saves.
+fn default_proficiencies() -> HashMap<Statistic, Proficiency> {
+    hashmap! {
+        Statistic::Perception => Proficiency::Trained,
+        Statistic::Fortitude => Proficiency::Trained,
+        Statistic::Reflex => Proficiency::Trained,
+        Statistic::Will => Proficiency::Trained,
+    }
+}
+
+/// Every class is at least trained in unarmed attacks and simple weapons.
+fn default_weapon_proficiencies() -> HashMap<WeaponCategory, Proficiency> {
+    hashmap! {
+        WeaponCategory::Unarmed => Proficiency::Trained,
+        WeaponCategory::Simple => Proficiency::Trained,
+    }
+}
+
+/// Every class is at least trained in unarmored defense.
+fn default_armor_proficiencies() -> HashMap<ArmorCategory, Proficiency> {
+    hashmap! {
+        ArmorCategory::Unarmored => Proficiency::Trained,
+    }
 }
 
 impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: &str,
         class: &'class Class,
         ancestry: &'ancestry Ancestry,
-        ancestry_boost_choices: &Vec<AbilityScoreType>,
+        ancestry_boost_choices: &[AbilityScoreType],
         background: &'background Background,
-        background_boost_choices: &Vec<AbilityScoreType>,
-        extra_boost_choices: &Vec<AbilityScoreType>,
-    ) -> Result<Character<'class, 'ancestry, 'background>, String> {
+        background_boost_choices: &[AbilityScoreType],
+        background_skill_choices: &[Skill],
+        extra_boost_choices: &[AbilityScoreType],
+    ) -> Result<Character<'class, 'ancestry, 'background>> {
         let extra_boosts: Vec<AbilityBoostChoice> = vec![
             AbilityBoostChoice::free(),
             AbilityBoostChoice::free(),
@@ -44,6 +120,28 @@ impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
             AbilityBoostChoice::free(),
         ];
 
+        let mut skills = SkillSet::new();
+        skills.train(
+            &background
+                .skill_trainings()
+                .apply_choices(background_skill_choices)?,
+        );
+        let feats = background.skill_feat().into_iter().cloned().collect();
+        let spellcasting = class
+            .spellcasting()
+            .map(|(tradition, casting_type)| {
+                let mut entry = SpellcastingEntry::new(
+                    class.name(),
+                    tradition,
+                    casting_type,
+                    class.key_ability(),
+                );
+                entry.set_slot_progression(class.slot_progression().clone());
+                entry
+            })
+            .into_iter()
+            .collect();
+
         Ok(Character {
             name: String::from(name),
             description: "".to_string(),
@@ -59,15 +157,79 @@ impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
                 &hashset![class.key_ability()],
                 &ancestry
                     .ability_boosts()
-                    .apply_choices(ancestry_boost_choices)?,
+                    .apply_choices(ancestry_boost_choices)
+                    .map_err(|error| error.with_boost_source(BoostSource::Ancestry))?,
                 &background
                     .ability_boosts()
-                    .apply_choices(background_boost_choices)?,
-                &extra_boosts.apply_choices(extra_boost_choices)?,
+                    .apply_choices(background_boost_choices)
+                    .map_err(|error| error.with_boost_source(BoostSource::Background))?,
+                &extra_boosts
+                    .apply_choices(extra_boost_choices)
+                    .map_err(|error| error.with_boost_source(BoostSource::Free))?,
             ]),
+            skills,
+            proficiencies: default_proficiencies(),
+            weapon_proficiencies: default_weapon_proficiencies(),
+            armor_proficiencies: default_armor_proficiencies(),
+            feats,
+            modifiers: vec![],
+            inventory: Inventory::new(),
+            wealth: Purse::from(starting_wealth(1)),
+            transactions: vec![],
+            formula_book: FormulaBook::new(),
+            crafting_projects: vec![],
+            variant_rules: VariantRules::new(),
+            skill_potencies: BTreeMap::new(),
+            access: BTreeSet::new(),
+            spellcasting,
         })
     }
 
+    /// Rebuilds a character from previously computed state, e.g. when loading a save.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn restore(
+        name: String,
+        description: String,
+        class: &'class Class,
+        ancestry: &'ancestry Ancestry,
+        background: &'background Background,
+        level: u32,
+        hp: Health,
+        speed: u32,
+        size: Size,
+        ability_scores: AbilityScoreSet,
+        skills: SkillSet,
+        feats: Vec<Feat>,
+    ) -> Character<'class, 'ancestry, 'background> {
+        Character {
+            name,
+            description,
+            class,
+            ancestry,
+            background,
+            level,
+            hp,
+            speed,
+            size,
+            ability_scores,
+            skills,
+            proficiencies: default_proficiencies(),
+            weapon_proficiencies: default_weapon_proficiencies(),
+            armor_proficiencies: default_armor_proficiencies(),
+            feats,
+            modifiers: vec![],
+            inventory: Inventory::new(),
+            wealth: Purse::new(),
+            transactions: vec![],
+            formula_book: FormulaBook::new(),
+            crafting_projects: vec![],
+            variant_rules: VariantRules::new(),
+            skill_potencies: BTreeMap::new(),
+            access: BTreeSet::new(),
+            spellcasting: vec![],
+        }
+    }
+
     // ============================= Accessors =============================
 
     pub fn name(&self) -> &str {
@@ -83,15 +245,15 @@ impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
     }
 
     pub fn class(&self) -> &Class {
-        &self.class
+        self.class
     }
 
     pub fn ancestry(&self) -> &Ancestry {
-        &self.ancestry
+        self.ancestry
     }
 
     pub fn background(&self) -> &Background {
-        &self.background
+        self.background
     }
 
     pub fn level(&self) -> u32 {
@@ -102,7 +264,27 @@ impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
         &self.hp
     }
 
+    pub fn hp_mut(&mut self) -> &mut Health {
+        &mut self.hp
+    }
+
+    /// The Speed after penalties from armor and being encumbered. Penalties can't reduce it below 5 feet.
     pub fn speed(&self) -> u32 {
+        let mut penalty = self
+            .worn_armor()
+            .map_or(0, |armor| armor.speed_penalty_for(self.strength().value()));
+        if self.encumbrance() != Encumbrance::Unencumbered {
+            penalty += 10;
+        }
+        if penalty == 0 {
+            self.speed
+        } else {
+            self.speed.saturating_sub(penalty).max(5).min(self.speed)
+        }
+    }
+
+    /// The Speed without any penalties.
+    pub fn base_speed(&self) -> u32 {
         self.speed
     }
 
@@ -138,33 +320,671 @@ impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
         self.ability_scores.get(AbilityScoreType::Charisma)
     }
 
+    pub fn skills(&self) -> &SkillSet {
+        &self.skills
+    }
+
+    pub fn proficiency(&self, skill: &Skill) -> Proficiency {
+        self.skills.get(skill)
+    }
+
+    /// The total modifier for checks with the given skill, including active modifiers.
+    pub fn skill_modifier(&self, skill: &Skill) -> i32 {
+        self.statistic_modifier(&Statistic::Skill(skill.clone()))
+    }
+
+    pub fn set_proficiency(&mut self, skill: Skill, proficiency: Proficiency) {
+        self.skills.set(skill, proficiency);
+    }
+
+    pub fn statistic_proficiency(&self, statistic: &Statistic) -> Proficiency {
+        match statistic {
+            Statistic::Skill(skill) => self.proficiency(skill),
+            _ => self.proficiencies[statistic],
+        }
+    }
+
+    pub fn set_statistic_proficiency(&mut self, statistic: Statistic, proficiency: Proficiency) {
+        match statistic {
+            Statistic::Skill(skill) => self.set_proficiency(skill, proficiency),
+            _ => {
+                self.proficiencies.insert(statistic, proficiency);
+            }
+        }
+    }
+
+    pub fn variant_rules(&self) -> &VariantRules {
+        &self.variant_rules
+    }
+
+    pub fn set_variant_rules(&mut self, rules: VariantRules) {
+        self.variant_rules = rules;
+    }
+
+    /// The skill potency bonus chosen for the skill, only used with automatic bonus progression.
+    pub fn skill_potency(&self, skill: &Skill) -> u32 {
+        self.skill_potencies.get(skill).copied().unwrap_or(0)
+    }
+
+    pub fn skill_potencies(&self) -> &BTreeMap<Skill, u32> {
+        &self.skill_potencies
+    }
+
+    /// Assigns a skill potency bonus, 0 removes it. Fails if the bonuses no longer fit the character's level.
+    pub fn set_skill_potency(&mut self, skill: Skill, bonus: u32) -> Result<()> {
+        let mut potencies = self.skill_potencies.clone();
+        if bonus == 0 {
+            potencies.remove(&skill);
+        } else {
+            potencies.insert(skill, bonus);
+        }
+        let mut bonuses: Vec<u32> = potencies.values().copied().collect();
+        bonuses.sort_unstable_by(|a, b| b.cmp(a));
+        let slots = skill_potency_slots(self.level);
+        if bonuses.len() > slots.len()
+            || bonuses.iter().zip(slots).any(|(bonus, slot)| bonus > slot)
+        {
+            return Err(Error::SkillPotencyLimit { level: self.level });
+        }
+        self.skill_potencies = potencies;
+        Ok(())
+    }
+
+    pub fn weapon_proficiency(&self, category: WeaponCategory) -> Proficiency {
+        self.weapon_proficiencies
+            .get(&category)
+            .copied()
+            .unwrap_or(Proficiency::Untrained)
+    }
+
+    pub fn set_weapon_proficiency(&mut self, category: WeaponCategory, proficiency: Proficiency) {
+        self.weapon_proficiencies.insert(category, proficiency);
+    }
+
+    pub fn armor_proficiency(&self, category: ArmorCategory) -> Proficiency {
+        self.armor_proficiencies
+            .get(&category)
+            .copied()
+            .unwrap_or(Proficiency::Untrained)
+    }
+
+    pub fn set_armor_proficiency(&mut self, category: ArmorCategory, proficiency: Proficiency) {
+        self.armor_proficiencies.insert(category, proficiency);
+    }
+
+    /// The total modifier for checks with the given statistic, including active modifiers.
+    pub fn statistic_modifier(&self, statistic: &Statistic) -> i32 {
+        self.statistic_breakdown(statistic, &[]).total()
+    }
+
+    /// Collects the ability modifier, proficiency bonus, active modifiers and the given situational modifiers of a
+    /// statistic. Situational modifiers always apply, regardless of their selector.
+    pub fn statistic_breakdown(
+        &self,
+        statistic: &Statistic,
+        situational: &[Modifier],
+    ) -> ModifierBreakdown {
+        let ability = statistic.key_ability();
+        let proficiency = self.statistic_proficiency(statistic);
+        let mut modifiers = vec![
+            Modifier::new(
+                &format!("{:?}", ability),
+                ModifierType::Ability,
+                self.ability_scores.get(ability).modifier(),
+                Selector::Ability(ability),
+            ),
+            Modifier::new(
+                &format!("{:?}", proficiency),
+                ModifierType::Proficiency,
+                proficiency.bonus(self.level),
+                Selector::Statistic(statistic.clone()),
+            ),
+        ];
+        modifiers.extend(
+            self.modifiers
+                .iter()
+                .chain(self.derived_modifiers().iter())
+                .filter(|modifier| modifier.selector().matches(statistic))
+                .cloned(),
+        );
+        modifiers.extend(situational.iter().cloned());
+        ModifierBreakdown::new(modifiers)
+    }
+
+    /// Collects the modifiers to AC: Dexterity up to the armor's cap, the armor proficiency and the armor's item
+    /// bonus. AC is 10 plus the total.
+    pub fn armor_class_breakdown(&self, situational: &[Modifier]) -> ModifierBreakdown {
+        let armor = self.worn_armor();
+        let proficiency = self
+            .armor_proficiency(armor.map_or(ArmorCategory::Unarmored, |armor| armor.category()));
+        let dexterity = self.dexterity().modifier();
+        let mut modifiers = vec![
+            Modifier::new(
+                "Dexterity",
+                ModifierType::Ability,
+                match armor.and_then(|armor| armor.dex_cap()) {
+                    Some(cap) => dexterity.min(cap as i32),
+                    None => dexterity,
+                },
+                Selector::Ability(AbilityScoreType::Dexterity),
+            ),
+            Modifier::new(
+                &format!("{:?}", proficiency),
+                ModifierType::Proficiency,
+                proficiency.bonus(self.level),
+                Selector::ArmorClass,
+            ),
+        ];
+        let item_bonus = if self.variant_rules.automatic_bonus_progression() {
+            armor.map_or(0, |armor| armor.ac_bonus()) + defense_potency(self.level)
+        } else {
+            armor.map_or(0, |armor| armor.item_bonus())
+        };
+        if item_bonus > 0 {
+            modifiers.push(Modifier::new(
+                "Armor",
+                ModifierType::Item,
+                item_bonus as i32,
+                Selector::ArmorClass,
+            ));
+        }
+        modifiers.extend(
+            self.modifiers
+                .iter()
+                .chain(self.derived_modifiers().iter())
+                .filter(|modifier| modifier.selector().matches_armor_class())
+                .cloned(),
+        );
+        modifiers.extend(situational.iter().cloned());
+        ModifierBreakdown::new(modifiers)
+    }
+
+    pub fn armor_class(&self) -> i32 {
+        10 + self.armor_class_breakdown(&[]).total()
+    }
+
+    /// The ability used for attack rolls: Dexterity for ranged weapons and Strength for melee weapons, unless a finesse
+    /// weapon gets a better total with Dexterity, e.g. because the character is enfeebled.
+    pub fn attack_ability(&self, weapon: &Weapon) -> AbilityScoreType {
+        let finesse_dexterity = weapon.has_trait(&WeaponTrait::Finesse)
+            && self
+                .attack_breakdown_with(weapon, AbilityScoreType::Dexterity, &[])
+                .total()
+                > self
+                    .attack_breakdown_with(weapon, AbilityScoreType::Strength, &[])
+                    .total();
+        if weapon.is_ranged() || finesse_dexterity {
+            AbilityScoreType::Dexterity
+        } else {
+            AbilityScoreType::Strength
+        }
+    }
+
+    /// Collects the modifiers of an attack roll with the weapon, see `statistic_breakdown`.
+    pub fn attack_breakdown(&self, weapon: &Weapon, situational: &[Modifier]) -> ModifierBreakdown {
+        self.attack_breakdown_with(weapon, self.attack_ability(weapon), situational)
+    }
+
+    fn attack_breakdown_with(
+        &self,
+        weapon: &Weapon,
+        ability: AbilityScoreType,
+        situational: &[Modifier],
+    ) -> ModifierBreakdown {
+        let proficiency = self.weapon_proficiency(weapon.category());
+        let mut modifiers = vec![
+            Modifier::new(
+                &format!("{:?}", ability),
+                ModifierType::Ability,
+                self.ability_scores.get(ability).modifier(),
+                Selector::Ability(ability),
+            ),
+            Modifier::new(
+                &format!("{:?}", proficiency),
+                ModifierType::Proficiency,
+                proficiency.bonus(self.level),
+                Selector::Attacks,
+            ),
+        ];
+        let potency = if self.variant_rules.automatic_bonus_progression() {
+            attack_potency(self.level)
+        } else {
+            weapon.runes().potency()
+        };
+        if potency > 0 {
+            modifiers.push(Modifier::new(
+                "Potency",
+                ModifierType::Item,
+                potency as i32,
+                Selector::Attacks,
+            ));
+        }
+        modifiers.extend(
+            self.modifiers
+                .iter()
+                .chain(self.derived_modifiers().iter())
+                .filter(|modifier| modifier.selector().matches_attack(ability))
+                .cloned(),
+        );
+        modifiers.extend(situational.iter().cloned());
+        ModifierBreakdown::new(modifiers)
+    }
+
+    /// The damage of a Strike with the weapon. Melee and thrown weapons add the Strength modifier, propulsive weapons
+    /// half of it, or all of it if it's a penalty. With automatic bonus progression the number of dice depends on the
+    /// level instead of striking runes.
+    pub fn weapon_damage(&self, weapon: &Weapon) -> DamageFormula {
+        let strength = self.strength().modifier();
+        let thrown = weapon
+            .traits()
+            .iter()
+            .any(|t| matches!(t, WeaponTrait::Thrown(_)));
+        let modifier = if !weapon.is_ranged() || thrown {
+            strength
+        } else if weapon.has_trait(&WeaponTrait::Propulsive) {
+            if strength > 0 {
+                strength / 2
+            } else {
+                strength
+            }
+        } else {
+            0
+        };
+
+        let dice = if self.variant_rules.automatic_bonus_progression() {
+            devastating_dice(self.level)
+        } else {
+            weapon.damage_dice()
+        };
+        let mut formula =
+            DamageFormula::new(dice, weapon.damage_die(), modifier, weapon.damage_type());
+        if let Some(die) = weapon.deadly() {
+            formula.set_deadly(die);
+        }
+        if let Some(die) = weapon.fatal() {
+            formula.set_fatal(die);
+        }
+        for rune in weapon.runes().property() {
+            for part in rune.damage() {
+                formula.add_part(part.clone());
+            }
+        }
+        formula
+    }
+
+    /// Looks up a statistic by name or id. Lore skills match the character's own lore skills regardless of case.
+    pub fn find_statistic(&self, name: &str) -> Result<Statistic> {
+        let statistic: Statistic = name.parse()?;
+        if let Statistic::Skill(Skill::Lore(_)) = statistic {
+            if let Some((skill, _)) = self
+                .skills
+                .trained()
+                .into_iter()
+                .find(|(skill, _)| skill.name().eq_ignore_ascii_case(&statistic.name()))
+            {
+                return Ok(Statistic::Skill(skill.clone()));
+            }
+        }
+        Ok(statistic)
+    }
+
+    pub fn modifiers(&self) -> &Vec<Modifier> {
+        &self.modifiers
+    }
+
+    /// Modifiers that follow from the character's state, e.g. clumsy from being encumbered, the worn armor's check
+    /// penalty or the effects of active items.
+    pub(super) fn derived_modifiers(&self) -> Vec<Modifier> {
+        let mut modifiers: Vec<Modifier> = self
+            .inventory
+            .entries()
+            .iter()
+            .filter(|entry| entry.is_active())
+            .flat_map(|entry| entry.item().effects().iter().cloned())
+            .collect();
+        if self.encumbrance() != Encumbrance::Unencumbered {
+            modifiers.push(Modifier::new(
+                "Clumsy",
+                ModifierType::Status,
+                -1,
+                Selector::Ability(AbilityScoreType::Dexterity),
+            ));
+        }
+        if let Some(armor) = self.worn_armor() {
+            let strength = self.strength().value();
+            let penalty = armor.check_penalty_for(strength) as i32;
+            let flexible = armor.has_trait(&ArmorTrait::Flexible);
+            for skill in &[
+                Skill::Acrobatics,
+                Skill::Athletics,
+                Skill::Stealth,
+                Skill::Thievery,
+            ] {
+                let penalty = match skill {
+                    Skill::Acrobatics | Skill::Athletics if flexible => 0,
+                    Skill::Stealth if armor.has_trait(&ArmorTrait::Noisy) => {
+                        armor.check_penalty() as i32
+                    }
+                    _ => penalty,
+                };
+                if penalty > 0 {
+                    modifiers.push(Modifier::new(
+                        "Armor check penalty",
+                        ModifierType::Untyped,
+                        -penalty,
+                        Selector::Statistic(Statistic::Skill(skill.clone())),
+                    ));
+                }
+            }
+            if armor.runes().resilient() > 0 && !self.variant_rules.automatic_bonus_progression() {
+                modifiers.push(Modifier::new(
+                    "Resilient",
+                    ModifierType::Item,
+                    armor.runes().resilient() as i32,
+                    Selector::Saves,
+                ));
+            }
+        }
+        if self.variant_rules.automatic_bonus_progression() {
+            modifiers.extend(self.potency_modifiers());
+        }
+        modifiers
+    }
+
+    /// The save, Perception and skill potency bonuses of automatic bonus progression.
+    fn potency_modifiers(&self) -> Vec<Modifier> {
+        let mut modifiers = vec![];
+        let saves = save_potency(self.level);
+        if saves > 0 {
+            modifiers.push(Modifier::new(
+                "Save potency",
+                ModifierType::Item,
+                saves as i32,
+                Selector::Saves,
+            ));
+        }
+        let perception = perception_potency(self.level);
+        if perception > 0 {
+            modifiers.push(Modifier::new(
+                "Perception potency",
+                ModifierType::Item,
+                perception as i32,
+                Selector::Statistic(Statistic::Perception),
+            ));
+        }
+        for (skill, bonus) in &self.skill_potencies {
+            modifiers.push(Modifier::new(
+                "Skill potency",
+                ModifierType::Item,
+                *bonus as i32,
+                Selector::Statistic(Statistic::Skill(skill.clone())),
+            ));
+        }
+        modifiers
+    }
+
+    pub fn add_modifier(&mut self, modifier: Modifier) {
+        self.modifiers.push(modifier);
+    }
+
+    /// Removes all active modifiers with the given name and returns them.
+    pub fn remove_modifiers(&mut self, name: &str) -> Vec<Modifier> {
+        let (removed, kept) = self
+            .modifiers
+            .drain(..)
+            .partition(|modifier| modifier.name() == name);
+        self.modifiers = kept;
+        removed
+    }
+
+    pub fn feats(&self) -> &Vec<Feat> {
+        &self.feats
+    }
+
+    pub fn add_feat(&mut self, feat: Feat) {
+        self.feats.push(feat);
+    }
+
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    pub fn inventory_mut(&mut self) -> &mut Inventory {
+        &mut self.inventory
+    }
+
+    pub fn wealth(&self) -> &Purse {
+        &self.wealth
+    }
+
+    pub fn wealth_mut(&mut self) -> &mut Purse {
+        &mut self.wealth
+    }
+
+    /// Replaces the coins with the starting gold for the current level, for characters built above level 1.
+    pub fn set_starting_wealth(&mut self) {
+        self.wealth = Purse::from(starting_wealth(self.level));
+    }
+
+    pub fn transactions(&self) -> &Vec<Transaction> {
+        &self.transactions
+    }
+
+    pub(crate) fn set_transactions(&mut self, transactions: Vec<Transaction>) {
+        self.transactions = transactions;
+    }
+
+    /// The armor the character is wearing, if any.
+    pub fn worn_armor(&self) -> Option<&Armor> {
+        self.inventory
+            .entries()
+            .iter()
+            .filter(|entry| entry.state() == ItemState::Worn)
+            .find_map(|entry| entry.item().armor())
+    }
+
+    pub fn formula_book(&self) -> &FormulaBook {
+        &self.formula_book
+    }
+
+    pub fn formula_book_mut(&mut self) -> &mut FormulaBook {
+        &mut self.formula_book
+    }
+
+    pub fn crafting_projects(&self) -> &Vec<CraftingProject> {
+        &self.crafting_projects
+    }
+
+    pub(crate) fn set_crafting_projects(&mut self, projects: Vec<CraftingProject>) {
+        self.crafting_projects = projects;
+    }
+
+    /// The Bulk at which the character becomes encumbered and the maximum Bulk they can carry.
+    pub fn bulk_limits(&self) -> (i32, i32) {
+        let strength = self.strength().modifier();
+        (5 + strength, 10 + strength)
+    }
+
+    pub fn encumbrance(&self) -> Encumbrance {
+        let bulk = self.inventory.total_bulk().bulk() as i32;
+        let (encumbered, max) = self.bulk_limits();
+        if bulk > max {
+            Encumbrance::Overloaded
+        } else if bulk > encumbered {
+            Encumbrance::Encumbered
+        } else {
+            Encumbrance::Unencumbered
+        }
+    }
+
     // ============================= Logic =============================
 
+    /// Rolls a d20 with the named statistic, e.g. `"Athletics"` or `"fortitude"`. Rolls with the secret trait are
+    /// GM-only.
+    pub fn roll_statistic<R: DiceRng + ?Sized>(
+        &self,
+        name: &str,
+        traits: &[&str],
+        situational: &[Modifier],
+        rng: &mut R,
+    ) -> Result<StatisticRoll> {
+        let statistic = self.find_statistic(name)?;
+        let breakdown = self.statistic_breakdown(&statistic, situational);
+        let roll = DiceExpression::parse(&format!("1d20{:+}", breakdown.total()))?.roll(rng)?;
+        Ok(StatisticRoll::new(
+            statistic,
+            breakdown,
+            roll,
+            Visibility::for_traits(traits),
+        ))
+    }
+
+    /// Rolls a check with the named statistic against a DC.
+    pub fn check_statistic<R: DiceRng + ?Sized>(
+        &self,
+        name: &str,
+        dc: i32,
+        traits: &[&str],
+        situational: &[Modifier],
+        rng: &mut R,
+    ) -> Result<StatisticCheck> {
+        let statistic = self.find_statistic(name)?;
+        let breakdown = self.statistic_breakdown(&statistic, situational);
+        let mut check = Check::new(breakdown.total(), dc);
+        for name in traits {
+            check.add_trait(name);
+        }
+        let result = check.roll(rng)?;
+        Ok(StatisticCheck::new(statistic, breakdown, result))
+    }
+
+    /// Pays for `quantity` of an item and puts them in the inventory. Nothing changes if either fails, or if the
+    /// item is above the character's level or needs access they don't have.
+    pub fn buy(&mut self, item: Item, quantity: u32, state: ItemState) -> Result<ItemId> {
+        if let Some(issue) = self.item_issues(&item).into_iter().next() {
+            return Err(Error::Illegal(issue));
+        }
+        let price = item.price() * quantity;
+        let name = item.name().to_string();
+        let mut wealth = self.wealth;
+        wealth.pay(price)?;
+        let id = self.inventory.add(item, quantity, state)?;
+        self.wealth = wealth;
+        self.transactions.push(Transaction::new(
+            TransactionKind::Buy,
+            &name,
+            quantity,
+            price,
+        ));
+        Ok(id)
+    }
+
+    /// Sells `quantity` of an item for half its price and returns what was received.
+    pub fn sell(&mut self, id: ItemId, quantity: u32) -> Result<Price> {
+        let entry = self.inventory.entry(id)?;
+        if quantity > entry.quantity() {
+            return Err(Error::NotEnoughItems {
+                id,
+                available: entry.quantity(),
+            });
+        }
+        if quantity == entry.quantity() && !self.inventory.contents(id).is_empty() {
+            return Err(Error::ContainerNotEmpty { id });
+        }
+        let price = (entry.item().price() * quantity).half();
+        let name = entry.item().name().to_string();
+        let remaining = entry.quantity() - quantity;
+        self.inventory.set_quantity(id, remaining)?;
+        self.wealth.receive(price);
+        self.transactions.push(Transaction::new(
+            TransactionKind::Sell,
+            &name,
+            quantity,
+            price,
+        ));
+        Ok(price)
+    }
+
+    /// Uses one of a consumable on the character itself, e.g. drinking a healing potion. The consumable is only used
+    /// up if its effect could be applied.
+    pub fn use_consumable<R: DiceRng + ?Sized>(
+        &mut self,
+        id: ItemId,
+        rng: &mut R,
+    ) -> Result<ConsumableOutcome> {
+        let consumable = self.inventory.consumable(id)?.clone();
+        let outcome = consumable.apply(self, rng)?;
+        self.inventory.consume(id)?;
+        Ok(outcome)
+    }
+
+    /// Uses one of a consumable on someone else, e.g. feeding an ally a healing potion.
+    pub fn use_consumable_on<R: DiceRng + ?Sized>(
+        &mut self,
+        id: ItemId,
+        target: &mut Character,
+        rng: &mut R,
+    ) -> Result<ConsumableOutcome> {
+        let outcome = self.inventory.consumable(id)?.apply(target, rng)?;
+        self.inventory.consume(id)?;
+        Ok(outcome)
+    }
+
+    /// Prepares for a new day: every item activation can be used again, wands recharge and spell slots are
+    /// regained. Prepared casters keep their prepared spells unless they clear them.
+    pub fn daily_preparations(&mut self) {
+        self.inventory.reset_daily();
+        for entry in &mut self.spellcasting {
+            entry.reset();
+        }
+    }
+
     pub fn level_up(&mut self) {
         self.level += 1;
         self.hp.increase_max(self.class.hp_increment());
     }
+
+    /// Applies the four free ability boosts gained at levels 5, 10, 15 and 20. The importers call it once per boost
+    /// level the character reached, it doesn't check the level itself.
+    pub(crate) fn apply_level_boosts(&mut self, choices: &[AbilityScoreType]) -> Result<()> {
+        let level_boosts: Vec<AbilityBoostChoice> = vec![
+            AbilityBoostChoice::free(),
+            AbilityBoostChoice::free(),
+            AbilityBoostChoice::free(),
+            AbilityBoostChoice::free(),
+        ];
+
+        let boosts = level_boosts
+            .apply_choices(choices)
+            .map_err(|error| error.with_boost_source(BoostSource::Level(self.level)))?;
+        self.ability_scores.boost(&boosts);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::character::AbilityBoostChoice;
+    use crate::character::{AbilityBoostChoice, FeatType, SkillTrainingChoice};
+    use crate::combat::DamageType;
+    use crate::dice::SeededRng;
+    use crate::item::{Activation, Bulk, Consumable, ConsumableEffect, Frequency, WeaponGroup};
 
     #[test]
     fn class_stats() {
         let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
         let ancestry = Ancestry::new("Bob".to_string(), 0, Size::Medium, 30, vec![]);
-        let background = Background::new("Bob".to_string(), "".to_string(), vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
         let character = Character::new(
             "Bob",
             &class,
             &ancestry,
-            &vec![],
+            &[],
             &background,
-            &vec![],
-            &vec![
+            &[],
+            &[],
+            &[
                 AbilityScoreType::Strength,
                 AbilityScoreType::Constitution,
                 AbilityScoreType::Dexterity,
@@ -198,15 +1018,16 @@ mod tests {
                 AbilityBoostChoice::free(),
             ],
         );
-        let background = Background::new("Bob".to_string(), "".to_string(), vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
         let character = Character::new(
             "Bob",
             &class,
             &ancestry,
-            &vec![AbilityScoreType::Dexterity],
+            &[AbilityScoreType::Dexterity],
             &background,
-            &vec![],
-            &vec![
+            &[],
+            &[],
+            &[
                 AbilityScoreType::Strength,
                 AbilityScoreType::Constitution,
                 AbilityScoreType::Dexterity,
@@ -249,15 +1070,18 @@ mod tests {
                 ]),
                 AbilityBoostChoice::free(),
             ],
+            vec![],
+            None,
         );
         let character = Character::new(
             "Bob",
             &class,
             &ancestry,
-            &vec![AbilityScoreType::Dexterity],
+            &[AbilityScoreType::Dexterity],
             &background,
-            &vec![AbilityScoreType::Strength, AbilityScoreType::Constitution],
-            &vec![
+            &[AbilityScoreType::Strength, AbilityScoreType::Constitution],
+            &[],
+            &[
                 AbilityScoreType::Strength,
                 AbilityScoreType::Constitution,
                 AbilityScoreType::Dexterity,
@@ -274,6 +1098,126 @@ mod tests {
         assert_eq!(character.charisma().value(), 10);
     }
 
+    #[test]
+    fn boost_errors_name_their_set() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new(
+            "Bob".to_string(),
+            8,
+            Size::Medium,
+            30,
+            vec![AbilityBoostChoice::free()],
+        );
+        let background = Background::new(
+            "Bob".to_string(),
+            "".to_string(),
+            vec![AbilityBoostChoice::free(), AbilityBoostChoice::free()],
+            vec![],
+            None,
+        );
+        let free = [
+            AbilityScoreType::Strength,
+            AbilityScoreType::Constitution,
+            AbilityScoreType::Dexterity,
+            AbilityScoreType::Wisdom,
+        ];
+
+        let result = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[AbilityScoreType::Dexterity],
+            &background,
+            &[AbilityScoreType::Strength, AbilityScoreType::Strength],
+            &[],
+            &free,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::DuplicateBoost {
+                boost_source: Some(BoostSource::Background),
+                index: 1,
+                ..
+            })
+        ));
+
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[AbilityScoreType::Dexterity],
+            &background,
+            &[AbilityScoreType::Strength, AbilityScoreType::Wisdom],
+            &[],
+            &free,
+        )
+        .unwrap();
+        for _ in 1..5 {
+            character.level_up();
+        }
+        assert!(matches!(
+            character.apply_level_boosts(&free[..2]),
+            Err(Error::TooFewBoostChoices {
+                boost_source: Some(BoostSource::Level(5)),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn background_grants() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 30, vec![]);
+        let background = Background::new(
+            "Bob".to_string(),
+            "".to_string(),
+            vec![],
+            vec![
+                SkillTrainingChoice::restricted(hashset![Skill::Athletics, Skill::Intimidation]),
+                SkillTrainingChoice::predetermined(Skill::lore("Warfare")),
+            ],
+            Some(Feat::new(
+                "Intimidating Glare".to_string(),
+                "".to_string(),
+                FeatType::Skill,
+                1,
+            )),
+        );
+        let character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[Skill::Intimidation],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            character.proficiency(&Skill::Intimidation),
+            Proficiency::Trained
+        );
+        assert_eq!(
+            character.proficiency(&Skill::lore("Warfare")),
+            Proficiency::Trained
+        );
+        assert_eq!(
+            character.proficiency(&Skill::Athletics),
+            Proficiency::Untrained
+        );
+        assert_eq!(character.skill_modifier(&Skill::Intimidation), 3);
+        assert_eq!(character.skill_modifier(&Skill::Athletics), 2);
+        assert_eq!(character.feats().len(), 1);
+        assert_eq!(character.feats()[0].name(), "Intimidating Glare");
+    }
+
     #[test]
     fn level_up() {
         let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
@@ -298,15 +1242,18 @@ mod tests {
                 ]),
                 AbilityBoostChoice::free(),
             ],
+            vec![],
+            None,
         );
         let mut character = Character::new(
             "Bob",
             &class,
             &ancestry,
-            &vec![AbilityScoreType::Dexterity],
+            &[AbilityScoreType::Dexterity],
             &background,
-            &vec![AbilityScoreType::Strength, AbilityScoreType::Constitution],
-            &vec![
+            &[AbilityScoreType::Strength, AbilityScoreType::Constitution],
+            &[],
+            &[
                 AbilityScoreType::Strength,
                 AbilityScoreType::Constitution,
                 AbilityScoreType::Dexterity,
@@ -320,4 +1267,469 @@ mod tests {
         assert_eq!(character.level(), 2);
         assert_eq!(character.hp().max(), 28);
     }
+
+    #[test]
+    fn statistic_checks_include_modifiers() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        character.set_proficiency(Skill::Athletics, Proficiency::Trained);
+        character.add_modifier(Modifier::new(
+            "Heroism",
+            ModifierType::Status,
+            1,
+            Selector::All,
+        ));
+        character.add_modifier(Modifier::new(
+            "Enfeebled",
+            ModifierType::Status,
+            -1,
+            Selector::Ability(AbilityScoreType::Strength),
+        ));
+
+        // +2 Strength, +3 Trained, +1 Heroism, -1 Enfeebled
+        assert_eq!(character.skill_modifier(&Skill::Athletics), 5);
+        // +1 Constitution, +3 Trained, +1 Heroism
+        assert_eq!(character.statistic_modifier(&Statistic::Fortitude), 5);
+
+        let cover = Modifier::new("Cover", ModifierType::Circumstance, 2, Selector::All);
+        let check = character
+            .check_statistic("fortitude", 20, &[], &[cover], &mut SeededRng::new(3))
+            .unwrap();
+        assert_eq!(check.breakdown().total(), 7);
+        assert_eq!(check.breakdown().modifiers().len(), 4);
+        assert_eq!(
+            check.result().total(),
+            check.result().roll().dice().next().unwrap().value() as i32 + 7
+        );
+
+        assert_eq!(character.remove_modifiers("Heroism").len(), 1);
+        assert_eq!(character.skill_modifier(&Skill::Athletics), 4);
+        assert!(character
+            .roll_statistic("Juggling", &[], &[], &mut SeededRng::new(3))
+            .is_err());
+        let seek = character
+            .roll_statistic("Perception", &["secret"], &[], &mut SeededRng::new(3))
+            .unwrap();
+        assert_eq!(seek.visibility(), Visibility::GmOnly);
+    }
+
+    #[test]
+    fn heavy_loads_encumber() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        let anvil = character
+            .inventory_mut()
+            .add(Item::new("Anvil", 0, Bulk::new(4)), 1, ItemState::Held)
+            .unwrap();
+
+        assert_eq!(character.bulk_limits(), (7, 12));
+        assert_eq!(character.encumbrance(), Encumbrance::Unencumbered);
+        assert_eq!(character.statistic_modifier(&Statistic::Reflex), 4);
+
+        character.inventory_mut().set_quantity(anvil, 2).unwrap();
+
+        assert_eq!(character.encumbrance(), Encumbrance::Encumbered);
+        assert_eq!(character.speed(), 15);
+        // clumsy 1
+        assert_eq!(character.statistic_modifier(&Statistic::Reflex), 3);
+
+        character.inventory_mut().set_quantity(anvil, 4).unwrap();
+        assert_eq!(character.encumbrance(), Encumbrance::Overloaded);
+    }
+
+    #[test]
+    fn buy_and_sell() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        assert_eq!(character.wealth().total(), Price::gp(15));
+
+        let mut sword = Item::new("Longsword", 0, Bulk::new(1));
+        sword.set_price(Price::gp(1));
+        let mut arrows = Item::new("Arrows", 0, Bulk::light());
+        arrows.set_price(Price::sp(1));
+        let sword = character.buy(sword, 1, ItemState::Held).unwrap();
+        let arrows = character.buy(arrows, 10, ItemState::Worn).unwrap();
+        assert_eq!(character.wealth().total(), Price::gp(13));
+
+        // a failed purchase neither takes money nor adds the item
+        let mut bag = Item::new("Bag", 0, Bulk::light());
+        bag.set_price(Price::gp(1));
+        assert!(character.buy(bag, 1, ItemState::Stowed(sword)).is_err());
+        assert_eq!(character.wealth().total(), Price::gp(13));
+        assert_eq!(character.inventory().entries().len(), 2);
+
+        assert_eq!(character.sell(arrows, 5).unwrap(), Price::cp(25));
+        assert!(matches!(
+            character.sell(arrows, 6),
+            Err(Error::NotEnoughItems { available: 5, .. })
+        ));
+        assert_eq!(character.wealth().total(), Price::cp(1325));
+        assert_eq!(character.transactions().len(), 3);
+        assert_eq!(character.transactions()[2].kind(), TransactionKind::Sell);
+    }
+
+    #[test]
+    fn weapon_attacks_and_damage() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        let mut longsword = Weapon::new(
+            WeaponCategory::Martial,
+            WeaponGroup::Sword,
+            8,
+            DamageType::Slashing,
+        );
+
+        // +2 Strength, untrained
+        assert_eq!(character.attack_breakdown(&longsword, &[]).total(), 2);
+
+        character.set_weapon_proficiency(WeaponCategory::Martial, Proficiency::Trained);
+        longsword.runes_mut().set_potency(1).unwrap();
+        longsword.runes_mut().set_striking(1).unwrap();
+        character.add_modifier(Modifier::new(
+            "Clumsy",
+            ModifierType::Status,
+            -1,
+            Selector::Ability(AbilityScoreType::Dexterity),
+        ));
+        // +2 Strength, +3 Trained, +1 potency
+        assert_eq!(character.attack_breakdown(&longsword, &[]).total(), 6);
+        let damage = character.weapon_damage(&longsword);
+        assert_eq!(
+            (damage.dice(), damage.die_size(), damage.modifier()),
+            (2, 8, 2)
+        );
+
+        let mut shortbow = Weapon::new(
+            WeaponCategory::Martial,
+            WeaponGroup::Bow,
+            6,
+            DamageType::Piercing,
+        );
+        shortbow.set_range(Some(60));
+        shortbow.set_reload(Some(0));
+        // +1 Dexterity, +3 Trained, -1 Clumsy
+        assert_eq!(character.attack_breakdown(&shortbow, &[]).total(), 3);
+        assert_eq!(character.weapon_damage(&shortbow).modifier(), 0);
+        shortbow.add_trait(WeaponTrait::Propulsive);
+        assert_eq!(character.weapon_damage(&shortbow).modifier(), 1);
+
+        let mut dagger = Weapon::new(
+            WeaponCategory::Simple,
+            WeaponGroup::Knife,
+            4,
+            DamageType::Piercing,
+        );
+        dagger.add_trait(WeaponTrait::Finesse);
+        character.remove_modifiers("Clumsy");
+        character.add_modifier(Modifier::new(
+            "Enfeebled",
+            ModifierType::Status,
+            -2,
+            Selector::Ability(AbilityScoreType::Strength),
+        ));
+        // finesse switches to Dexterity once enfeebled makes Strength worse, +1 Dexterity, +3 Trained
+        assert_eq!(
+            character.attack_ability(&dagger),
+            AbilityScoreType::Dexterity
+        );
+        assert_eq!(character.attack_breakdown(&dagger, &[]).total(), 4);
+    }
+
+    #[test]
+    fn armor_penalties_and_ac() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        // +1 Dexterity, +3 Trained in unarmored defense
+        assert_eq!(character.armor_class(), 14);
+
+        let mut full_plate = Armor::new(ArmorCategory::Heavy, 6, Some(0));
+        full_plate.set_check_penalty(3);
+        full_plate.set_speed_penalty(10);
+        full_plate.set_strength(Some(18));
+        let mut item = Item::new("Full Plate", 2, Bulk::new(4));
+        item.set_armor(Some(full_plate));
+        let plate = character
+            .inventory_mut()
+            .add(item, 1, ItemState::Worn)
+            .unwrap();
+
+        // Dexterity capped at +0, untrained, +6 item
+        assert_eq!(character.armor_class(), 16);
+        assert_eq!(character.speed(), 15);
+        assert_eq!(character.skill_modifier(&Skill::Athletics), -1);
+
+        character.inventory_mut().remove(plate).unwrap();
+        let mut hide = Armor::new(ArmorCategory::Medium, 3, Some(2));
+        hide.set_check_penalty(2);
+        hide.set_speed_penalty(5);
+        hide.set_strength(Some(14));
+        hide.add_trait(ArmorTrait::Noisy);
+        hide.runes_mut().set_resilient(1).unwrap();
+        let mut item = Item::new("Hide Armor", 0, Bulk::new(2));
+        item.set_armor(Some(hide));
+        character
+            .inventory_mut()
+            .add(item, 1, ItemState::Worn)
+            .unwrap();
+
+        // meeting the Strength threshold removes the penalties, except for Stealth as the armor is noisy
+        assert_eq!(character.speed(), 25);
+        assert_eq!(character.skill_modifier(&Skill::Acrobatics), 1);
+        assert_eq!(character.skill_modifier(&Skill::Stealth), -1);
+        // +1 Constitution, +3 Trained, +1 resilient
+        assert_eq!(character.statistic_modifier(&Statistic::Fortitude), 5);
+    }
+
+    #[test]
+    fn active_item_effects_apply() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        let mut boots = Item::new("Boots of Bounding", 7, Bulk::light());
+        boots.add_trait("invested");
+        boots.add_effect(Modifier::new(
+            "Boots of Bounding",
+            ModifierType::Item,
+            1,
+            Selector::Statistic(Statistic::Skill(Skill::Athletics)),
+        ));
+        boots.add_activation(Activation::new("Bound", 1, Frequency::PerDay(1)));
+        let boots = character
+            .inventory_mut()
+            .add(boots, 1, ItemState::Worn)
+            .unwrap();
+
+        // +2 Strength, untrained
+        assert_eq!(character.skill_modifier(&Skill::Athletics), 2);
+        character.inventory_mut().invest(boots).unwrap();
+        assert_eq!(character.skill_modifier(&Skill::Athletics), 3);
+
+        character.inventory_mut().activate(boots, "Bound").unwrap();
+        assert!(character.inventory_mut().activate(boots, "Bound").is_err());
+        character.daily_preparations();
+        assert!(character.inventory_mut().activate(boots, "Bound").is_ok());
+    }
+
+    #[test]
+    fn healing_potions_heal() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        let mut potion = Item::new("Minor Healing Potion", 1, Bulk::light());
+        potion.set_consumable(Some(Consumable::new(
+            1,
+            ConsumableEffect::Heal(DiceExpression::parse("1d8+20").unwrap()),
+        )));
+        let potions = character
+            .inventory_mut()
+            .add(potion, 2, ItemState::Held)
+            .unwrap();
+        character.hp_mut().damage(10);
+
+        assert_eq!(
+            character
+                .use_consumable(potions, &mut SeededRng::new(1))
+                .unwrap(),
+            ConsumableOutcome::Healed(10)
+        );
+        assert_eq!(character.hp().current(), character.hp().max());
+        assert_eq!(character.inventory().get(potions).unwrap().quantity(), 1);
+
+        let mut scroll = Item::new("Scroll of Fear", 1, Bulk::light());
+        scroll.set_consumable(Some(Consumable::scroll("Fear", 1, 2)));
+        let scroll = character
+            .inventory_mut()
+            .add(scroll, 1, ItemState::Held)
+            .unwrap();
+        assert!(matches!(
+            character.use_consumable(scroll, &mut SeededRng::new(1)),
+            Ok(ConsumableOutcome::SpellCast { rank: 1, .. })
+        ));
+        assert!(character.inventory().get(scroll).is_none());
+    }
+
+    #[test]
+    fn automatic_bonus_progression() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        for _ in 1..8 {
+            character.level_up();
+        }
+        let mut longsword = Weapon::new(
+            WeaponCategory::Martial,
+            WeaponGroup::Sword,
+            8,
+            DamageType::Slashing,
+        );
+        longsword.runes_mut().set_potency(2).unwrap();
+        longsword.runes_mut().set_striking(2).unwrap();
+        character.set_skill_potency(Skill::Athletics, 1).unwrap();
+
+        let attack = character.attack_breakdown(&longsword, &[]).total();
+        let ac = character.armor_class();
+        let fortitude = character.statistic_modifier(&Statistic::Fortitude);
+        let perception = character.statistic_modifier(&Statistic::Perception);
+        let athletics = character.skill_modifier(&Skill::Athletics);
+        assert_eq!(character.weapon_damage(&longsword).dice(), 3);
+
+        let mut rules = VariantRules::new();
+        rules.set_automatic_bonus_progression(true);
+        character.set_variant_rules(rules);
+        // level 8: +1 attack potency instead of the +2 rune, two dice instead of three, +1 to AC, saves and Perception
+        assert_eq!(
+            character.attack_breakdown(&longsword, &[]).total(),
+            attack - 1
+        );
+        assert_eq!(character.weapon_damage(&longsword).dice(), 2);
+        assert_eq!(character.armor_class(), ac + 1);
+        assert_eq!(
+            character.statistic_modifier(&Statistic::Fortitude),
+            fortitude + 1
+        );
+        assert_eq!(
+            character.statistic_modifier(&Statistic::Perception),
+            perception + 1
+        );
+        assert_eq!(character.skill_modifier(&Skill::Athletics), athletics + 1);
+
+        // two +1 skill potencies at level 8
+        character.set_skill_potency(Skill::Crafting, 1).unwrap();
+        assert!(character.set_skill_potency(Skill::Stealth, 1).is_err());
+        assert!(character.set_skill_potency(Skill::Crafting, 2).is_err());
+        assert_eq!(character.skill_potency(&Skill::Crafting), 1);
+    }
 }
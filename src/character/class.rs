@@ -1,10 +1,23 @@
 use crate::character::AbilityScoreType;
+use crate::content::Rarity;
+use crate::spell::{CastingType, SlotProgression, Tradition};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Class {
     name: String,
     key_ability: AbilityScoreType,
     hp_increment: u32,
+    /// The tradition and casting type of spellcasting classes.
+    #[serde(default)]
+    spellcasting: Option<(Tradition, CastingType)>,
+    /// The spell slots of its spellcasting entry.
+    #[serde(default)]
+    slot_progression: SlotProgression,
+    #[serde(default)]
+    rarity: Rarity,
+    #[serde(default)]
+    access: Option<String>,
 }
 
 impl Class {
@@ -13,6 +26,10 @@ impl Class {
             name,
             key_ability,
             hp_increment,
+            spellcasting: None,
+            slot_progression: SlotProgression::Full,
+            rarity: Rarity::Common,
+            access: None,
         }
     }
 
@@ -27,4 +44,40 @@ impl Class {
     pub fn hp_increment(&self) -> u32 {
         self.hp_increment
     }
+
+    pub fn spellcasting(&self) -> Option<(Tradition, CastingType)> {
+        self.spellcasting
+    }
+
+    /// Makes the class a caster. Its characters start with a spellcasting entry using the key ability and the
+    /// class's slot progression.
+    pub fn set_spellcasting(&mut self, spellcasting: Option<(Tradition, CastingType)>) {
+        self.spellcasting = spellcasting;
+    }
+
+    pub fn slot_progression(&self) -> &SlotProgression {
+        &self.slot_progression
+    }
+
+    /// Changes the spell slots of the class, full casters are the default.
+    pub fn set_slot_progression(&mut self, slot_progression: SlotProgression) {
+        self.slot_progression = slot_progression;
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        self.rarity
+    }
+
+    pub fn set_rarity(&mut self, rarity: Rarity) {
+        self.rarity = rarity;
+    }
+
+    /// What grants access if it isn't common.
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+
+    pub fn set_access(&mut self, access: Option<&str>) {
+        self.access = access.map(str::to_string);
+    }
 }
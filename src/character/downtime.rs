@@ -0,0 +1,250 @@
+use crate::character::{Character, Proficiency, Skill};
+use crate::dice::{level_dc, DegreeOfSuccess, DiceRng};
+use crate::downtime::{crafting_requirement, income_per_day, CraftingProject, EarnIncome};
+use crate::error::{Error, Result};
+use crate::item::{ItemId, ItemState, Price, Transaction, TransactionKind};
+
+/// Crafting and Earning Income.
+impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
+    /// Starts crafting `quantity` of an item from the formula book and rolls the Crafting check against the item's
+    /// level DC. A success pays half the price for raw materials. On a failure the materials are salvaged at full
+    /// value, so nothing is paid, while a critical failure loses 10% of them. The project is added to the character's
+    /// projects either way, failed ones can only be abandoned.
+    pub fn start_crafting<R: DiceRng + ?Sized>(
+        &mut self,
+        name: &str,
+        quantity: u32,
+        rng: &mut R,
+    ) -> Result<&CraftingProject> {
+        let item = self
+            .formula_book
+            .get(name)
+            .ok_or_else(|| Error::MissingFormula(name.to_string()))?
+            .clone();
+        if item.level() > self.level {
+            return Err(Error::ItemLevelTooHigh {
+                level: item.level(),
+                max: self.level,
+            });
+        }
+        let required = crafting_requirement(item.level());
+        let actual = self.proficiency(&Skill::Crafting);
+        if actual < required {
+            return Err(Error::InsufficientProficiency { required, actual });
+        }
+        let materials = (item.price() * quantity).half();
+        if !self.wealth.can_afford(materials) {
+            return Err(Error::InsufficientFunds {
+                price: materials,
+                available: self.wealth.total(),
+            });
+        }
+
+        let check = self.check_statistic("Crafting", level_dc(item.level()), &[], &[], rng)?;
+        let paid = match check.result().degree() {
+            DegreeOfSuccess::CriticalSuccess | DegreeOfSuccess::Success => materials,
+            DegreeOfSuccess::Failure => Price::free(),
+            DegreeOfSuccess::CriticalFailure => Price::cp(materials.copper() / 10),
+        };
+        self.wealth.pay(paid)?;
+        self.transactions.push(Transaction::new(
+            TransactionKind::Craft,
+            item.name(),
+            quantity,
+            paid,
+        ));
+        self.crafting_projects
+            .push(CraftingProject::new(item, quantity, check, paid));
+        Ok(self.crafting_projects.last().unwrap())
+    }
+
+    fn crafting_project(&self, index: usize) -> Result<&CraftingProject> {
+        self.crafting_projects
+            .get(index)
+            .ok_or(Error::UnknownCraftingProject(index))
+    }
+
+    /// Works on a project for more days, each lowering the remaining cost by the income of the character's level at
+    /// their Crafting proficiency, or the level above on a critical success. Returns the new remaining cost.
+    pub fn spend_crafting_days(&mut self, index: usize, days: u32) -> Result<Price> {
+        let project = self.crafting_project(index)?;
+        if project.is_failed() {
+            return Err(Error::CraftingFailed(project.item().name().to_string()));
+        }
+        let per_day = income_per_day(
+            self.level,
+            self.proficiency(&Skill::Crafting),
+            project.degree(),
+        );
+        let project = &mut self.crafting_projects[index];
+        project.add_days(days, per_day);
+        Ok(project.remaining_cost())
+    }
+
+    /// Pays the remaining cost of a project and puts the crafted items in the inventory. Nothing changes if either
+    /// fails.
+    pub fn finish_crafting(&mut self, index: usize, state: ItemState) -> Result<ItemId> {
+        let project = self.crafting_project(index)?;
+        if project.is_failed() {
+            return Err(Error::CraftingFailed(project.item().name().to_string()));
+        }
+        let cost = project.remaining_cost();
+        let (item, quantity) = project.clone().into_item();
+        let name = item.name().to_string();
+        let mut wealth = self.wealth;
+        wealth.pay(cost)?;
+        let id = self.inventory.add(item, quantity, state)?;
+        self.wealth = wealth;
+        self.crafting_projects.remove(index);
+        self.transactions.push(Transaction::new(
+            TransactionKind::Craft,
+            &name,
+            quantity,
+            cost,
+        ));
+        Ok(id)
+    }
+
+    /// Stops working on a project. Nothing paid for raw materials is refunded.
+    pub fn abandon_crafting(&mut self, index: usize) -> Result<CraftingProject> {
+        self.crafting_project(index)?;
+        Ok(self.crafting_projects.remove(index))
+    }
+
+    /// Earns Income with a skill for a number of days against the task level's DC, adding the coins to the
+    /// character's wealth. The skill has to be trained and the task can't be above the character's level.
+    pub fn earn_income<R: DiceRng + ?Sized>(
+        &mut self,
+        skill: &str,
+        task_level: u32,
+        days: u32,
+        rng: &mut R,
+    ) -> Result<EarnIncome> {
+        if task_level > self.level {
+            return Err(Error::TaskLevelTooHigh {
+                level: task_level,
+                max: self.level,
+            });
+        }
+        let statistic = self.find_statistic(skill)?;
+        let proficiency = self.statistic_proficiency(&statistic);
+        if proficiency == Proficiency::Untrained {
+            return Err(Error::InsufficientProficiency {
+                required: Proficiency::Trained,
+                actual: proficiency,
+            });
+        }
+        let check = self.check_statistic(
+            &statistic.name(),
+            level_dc(task_level),
+            &["downtime"],
+            &[],
+            rng,
+        )?;
+        let per_day = income_per_day(task_level, proficiency, check.result().degree());
+        let income = EarnIncome::new(check, task_level, days, per_day);
+        self.wealth.receive(income.total());
+        self.transactions.push(Transaction::new(
+            TransactionKind::Income,
+            &format!("Earn Income ({})", statistic.name()),
+            days,
+            income.total(),
+        ));
+        Ok(income)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::character::{AbilityScoreType, Ancestry, Background, Class, Size};
+    use crate::downtime::SETUP_DAYS;
+    use crate::item::{Bulk, Item};
+
+    /// Always rolls the same number on every die.
+    struct Fixed(u64);
+
+    impl DiceRng for Fixed {
+        fn next_u64(&mut self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn crafting_and_earning_income() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        let mut potion = Item::new("Minor Healing Potion", 1, Bulk::light());
+        potion.set_price(Price::gp(4));
+        let natural_20 = &mut Fixed(19);
+        let natural_1 = &mut Fixed(0);
+
+        assert!(matches!(
+            character.start_crafting("Minor Healing Potion", 1, natural_20),
+            Err(Error::MissingFormula(_))
+        ));
+        character.formula_book_mut().add(potion);
+        assert!(matches!(
+            character.start_crafting("minor healing potion", 1, natural_20),
+            Err(Error::InsufficientProficiency { .. })
+        ));
+        character.set_proficiency(Skill::Crafting, Proficiency::Trained);
+
+        // a critical success pays half the price and lowers the rest by the income of the next level
+        character
+            .start_crafting("Minor Healing Potion", 1, natural_20)
+            .unwrap();
+        assert_eq!(character.wealth().total(), Price::gp(13));
+        assert_eq!(character.spend_crafting_days(0, 2).unwrap(), Price::sp(14));
+        assert_eq!(character.crafting_projects()[0].days(), SETUP_DAYS + 2);
+        let potions = character.finish_crafting(0, ItemState::Worn).unwrap();
+        assert!(character.crafting_projects().is_empty());
+        assert_eq!(character.wealth().total(), Price::sp(116));
+        assert_eq!(
+            character.inventory().get(potions).unwrap().item().name(),
+            "Minor Healing Potion"
+        );
+
+        // a critical failure loses a tenth of the materials
+        character
+            .start_crafting("Minor Healing Potion", 1, natural_1)
+            .unwrap();
+        assert_eq!(character.wealth().total(), Price::sp(114));
+        assert!(character.spend_crafting_days(0, 1).is_err());
+        assert!(character.finish_crafting(0, ItemState::Worn).is_err());
+        assert!(character.abandon_crafting(0).unwrap().is_failed());
+        assert!(matches!(
+            character.finish_crafting(0, ItemState::Worn),
+            Err(Error::UnknownCraftingProject(0))
+        ));
+
+        assert!(character.earn_income("Arcana", 1, 3, natural_20).is_err());
+        assert!(matches!(
+            character.earn_income("Crafting", 2, 3, natural_20),
+            Err(Error::TaskLevelTooHigh { level: 2, max: 1 })
+        ));
+        let income = character.earn_income("Crafting", 1, 3, natural_20).unwrap();
+        assert_eq!(income.total(), Price::sp(9));
+        assert_eq!(character.wealth().total(), Price::sp(123));
+        assert_eq!(character.transactions().len(), 4);
+        assert_eq!(character.transactions()[3].kind(), TransactionKind::Income);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::content::Rarity;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum FeatType {
+    Ancestry,
+    Class,
+    General,
+    Skill,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Feat {
+    name: String,
+    description: String,
+    feat_type: FeatType,
+    level: u32,
+    #[serde(default)]
+    rarity: Rarity,
+    #[serde(default)]
+    access: Option<String>,
+}
+
+impl Feat {
+    pub fn new(name: String, description: String, feat_type: FeatType, level: u32) -> Feat {
+        Feat {
+            name,
+            description,
+            feat_type,
+            level,
+            rarity: Rarity::Common,
+            access: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn feat_type(&self) -> &FeatType {
+        &self.feat_type
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        self.rarity
+    }
+
+    pub fn set_rarity(&mut self, rarity: Rarity) {
+        self.rarity = rarity;
+    }
+
+    /// What grants access if it isn't common.
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+
+    pub fn set_access(&mut self, access: Option<&str>) {
+        self.access = access.map(str::to_string);
+    }
+}
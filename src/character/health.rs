@@ -1,3 +1,4 @@
+use crate::combat::{Damage, Defenses};
 use std::cmp::min;
 
 pub struct Health {
@@ -15,6 +16,11 @@ impl Health {
         }
     }
 
+    /// Restores a previously saved state.
+    pub(crate) fn restore(max: u32, current: u32, temp: u32) -> Health {
+        Health { max, current, temp }
+    }
+
     pub fn max(&self) -> u32 {
         self.max
     }
@@ -60,11 +66,19 @@ impl Health {
             self.current
         }
     }
+
+    /// Applies typed damage after the target's immunities, resistances and weaknesses. Returns the damage taken.
+    pub fn apply_damage(&mut self, damage: &Damage, defenses: &Defenses) -> u32 {
+        let taken = defenses.apply(damage);
+        self.damage(taken);
+        taken
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::combat::DamageType;
 
     #[test]
     fn new_produces_correct_state() {
@@ -96,6 +110,18 @@ mod tests {
         assert_eq!(hp.current(), 0);
     }
 
+    #[test]
+    fn apply_damage_uses_defenses() {
+        let mut hp = Health::new(20);
+        let mut defenses = Defenses::new();
+        defenses.set_resistance(DamageType::Fire, 5);
+
+        let taken = hp.apply_damage(&Damage::of(DamageType::Fire, 8), &defenses);
+
+        assert_eq!(taken, 3);
+        assert_eq!(hp.current(), 17);
+    }
+
     #[test]
     fn damage_takes_from_temp_hp_first() {
         let mut hp = Health::new(10);
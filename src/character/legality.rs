@@ -0,0 +1,224 @@
+use crate::character::Character;
+use crate::content::Rarity;
+use crate::item::Item;
+use crate::spell::KnownSpell;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Something a character has that the rules only allow with the GM's permission.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LegalityIssue {
+    /// An item or feat above the character's level.
+    LevelTooHigh { name: String, level: u32, max: u32 },
+    /// Uncommon, rare or unique content the character wasn't granted access to.
+    NoAccess {
+        name: String,
+        rarity: Rarity,
+        access: Option<String>,
+    },
+}
+
+impl LegalityIssue {
+    /// The name of the item or content the issue is about.
+    pub fn name(&self) -> &str {
+        match self {
+            LegalityIssue::LevelTooHigh { name, .. } | LegalityIssue::NoAccess { name, .. } => name,
+        }
+    }
+}
+
+impl fmt::Display for LegalityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LegalityIssue::LevelTooHigh { name, level, max } => write!(
+                f,
+                "{} is level {}, above the character's level of {}",
+                name, level, max
+            ),
+            LegalityIssue::NoAccess {
+                name,
+                rarity,
+                access: Some(access),
+            } => write!(f, "{} is {} and needs access: {}", name, rarity, access),
+            LegalityIssue::NoAccess { name, rarity, .. } => {
+                write!(f, "{} is {} and needs access", name, rarity)
+            }
+        }
+    }
+}
+
+/// Access to uncommon content and the checks for content the character isn't allowed to have.
+impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
+    pub fn access(&self) -> &BTreeSet<String> {
+        &self.access
+    }
+
+    /// Lets the character have the named item or content regardless of its rarity and level.
+    pub fn grant_access(&mut self, name: &str) {
+        self.access.insert(name.trim().to_lowercase());
+    }
+
+    pub fn revoke_access(&mut self, name: &str) {
+        self.access.remove(&name.trim().to_lowercase());
+    }
+
+    pub fn has_access(&self, name: &str) -> bool {
+        self.access.contains(&name.trim().to_lowercase())
+    }
+
+    fn content_issues(
+        &self,
+        name: &str,
+        level: Option<u32>,
+        rarity: Rarity,
+        access: Option<&str>,
+    ) -> Vec<LegalityIssue> {
+        if self.has_access(name) {
+            return vec![];
+        }
+        let mut issues = vec![];
+        if let Some(level) = level.filter(|level| *level > self.level) {
+            issues.push(LegalityIssue::LevelTooHigh {
+                name: name.to_string(),
+                level,
+                max: self.level,
+            });
+        }
+        if rarity != Rarity::Common {
+            issues.push(LegalityIssue::NoAccess {
+                name: name.to_string(),
+                rarity,
+                access: access.map(str::to_string),
+            });
+        }
+        issues
+    }
+
+    /// What keeps the character from having the item.
+    pub fn item_issues(&self, item: &Item) -> Vec<LegalityIssue> {
+        self.content_issues(
+            item.name(),
+            Some(item.level()),
+            item.rarity(),
+            item.access(),
+        )
+    }
+
+    /// Checks the character's class, ancestry, background, feats and items for content above their level or that
+    /// needs access they weren't granted.
+    pub fn legality_issues(&self) -> Vec<LegalityIssue> {
+        let mut issues = self.content_issues(
+            self.class.name(),
+            None,
+            self.class.rarity(),
+            self.class.access(),
+        );
+        issues.extend(self.content_issues(
+            self.ancestry.name(),
+            None,
+            self.ancestry.rarity(),
+            self.ancestry.access(),
+        ));
+        issues.extend(self.content_issues(
+            self.background.name(),
+            None,
+            self.background.rarity(),
+            self.background.access(),
+        ));
+        for feat in &self.feats {
+            issues.extend(self.content_issues(
+                feat.name(),
+                Some(feat.level()),
+                feat.rarity(),
+                feat.access(),
+            ));
+        }
+        for entry in self.inventory.entries() {
+            issues.extend(self.item_issues(entry.item()));
+        }
+        for spell in self
+            .spellcasting
+            .iter()
+            .flat_map(|entry| entry.spells())
+            .map(KnownSpell::spell)
+        {
+            issues.extend(self.content_issues(spell.name(), None, spell.rarity(), spell.access()));
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::character::{AbilityScoreType, Ancestry, Background, Class, Size};
+    use crate::character::{Feat, FeatType};
+    use crate::error::Error;
+    use crate::item::{Bulk, ItemState};
+
+    #[test]
+    fn legality_checks_level_and_rarity() {
+        let class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        let mut cloak = Item::new("Cloak of Illusions", 1, Bulk::light());
+        cloak.set_rarity(Rarity::Uncommon);
+        cloak.set_access(Some("Member of the Magaambya"));
+        let boots = Item::new("Boots of Bounding", 7, Bulk::light());
+        let mut feat = Feat::new(
+            "Secret Lore".to_string(),
+            "".to_string(),
+            FeatType::General,
+            1,
+        );
+        feat.set_rarity(Rarity::Rare);
+        character.add_feat(feat);
+
+        assert!(matches!(
+            character.buy(cloak.clone(), 1, ItemState::Worn),
+            Err(Error::Illegal(LegalityIssue::NoAccess {
+                rarity: Rarity::Uncommon,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            character.buy(boots.clone(), 1, ItemState::Worn),
+            Err(Error::Illegal(LegalityIssue::LevelTooHigh {
+                level: 7,
+                max: 1,
+                ..
+            }))
+        ));
+        assert!(character.transactions().is_empty());
+
+        character.grant_access("cloak of illusions");
+        character.buy(cloak, 1, ItemState::Worn).unwrap();
+        character
+            .inventory_mut()
+            .add(boots, 1, ItemState::Worn)
+            .unwrap();
+        let issues: Vec<_> = character
+            .legality_issues()
+            .iter()
+            .map(|issue| issue.name().to_string())
+            .collect();
+        assert_eq!(issues, vec!["Secret Lore", "Boots of Bounding"]);
+    }
+}
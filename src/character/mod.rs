@@ -1,18 +1,51 @@
 mod ability_score;
 mod ancestry;
 mod background;
+#[allow(clippy::module_inception)]
 mod character;
 mod class;
+mod downtime;
+mod feat;
 mod health;
+mod legality;
+mod modifier;
+mod skill;
+mod spellcasting;
+mod statistic;
+mod variant;
 
 pub use ability_score::AbilityBoostChoice;
 pub use ability_score::AbilityBoostChoiceSet;
 pub use ability_score::AbilityScore;
 pub use ability_score::AbilityScoreSet;
 pub use ability_score::AbilityScoreType;
+pub use ability_score::BoostSource;
 pub use ancestry::Ancestry;
 pub use background::Background;
 pub use character::Character;
+pub use character::Encumbrance;
 pub use character::Size;
 pub use class::Class;
+pub use feat::Feat;
+pub use feat::FeatType;
 pub use health::Health;
+pub use legality::LegalityIssue;
+pub use modifier::Modifier;
+pub use modifier::ModifierBreakdown;
+pub use modifier::ModifierType;
+pub use modifier::Selector;
+pub use skill::Proficiency;
+pub use skill::Skill;
+pub use skill::SkillSet;
+pub use skill::SkillTrainingChoice;
+pub use skill::SkillTrainingChoiceSet;
+pub use statistic::Statistic;
+pub use statistic::StatisticCheck;
+pub use statistic::StatisticRoll;
+pub use variant::attack_potency;
+pub use variant::defense_potency;
+pub use variant::devastating_dice;
+pub use variant::perception_potency;
+pub use variant::save_potency;
+pub use variant::skill_potency_slots;
+pub use variant::VariantRules;
@@ -0,0 +1,208 @@
+use crate::character::{AbilityScoreType, Statistic};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Bonuses and penalties of the same type don't stack, only untyped ones do.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ModifierType {
+    Ability,
+    Proficiency,
+    Circumstance,
+    Item,
+    Status,
+    Untyped,
+}
+
+/// Which statistics a modifier applies to.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Selector {
+    All,
+    Saves,
+    Skills,
+    /// Attack rolls with weapons and unarmed attacks.
+    Attacks,
+    ArmorClass,
+    /// Spell attack rolls and spell DCs.
+    Spellcasting,
+    /// Every statistic based on the given ability, e.g. clumsy applies to all Dexterity-based checks.
+    Ability(AbilityScoreType),
+    Statistic(Statistic),
+}
+
+impl Selector {
+    pub fn matches(&self, statistic: &Statistic) -> bool {
+        match self {
+            Selector::All => true,
+            Selector::Saves => statistic.is_save(),
+            Selector::Skills => matches!(statistic, Statistic::Skill(_)),
+            Selector::Attacks | Selector::ArmorClass | Selector::Spellcasting => false,
+            Selector::Ability(ability) => statistic.key_ability() == *ability,
+            Selector::Statistic(selected) => selected == statistic,
+        }
+    }
+
+    /// Whether the modifier applies to an attack roll using the given ability.
+    pub fn matches_attack(&self, ability: AbilityScoreType) -> bool {
+        match self {
+            Selector::All | Selector::Attacks => true,
+            Selector::Ability(selected) => *selected == ability,
+            _ => false,
+        }
+    }
+
+    /// Whether the modifier applies to spell attack rolls and DCs of spells cast with the given ability.
+    pub fn matches_spellcasting(&self, ability: AbilityScoreType) -> bool {
+        match self {
+            Selector::All | Selector::Spellcasting => true,
+            Selector::Ability(selected) => *selected == ability,
+            _ => false,
+        }
+    }
+
+    pub fn matches_armor_class(&self) -> bool {
+        matches!(
+            self,
+            Selector::All | Selector::ArmorClass | Selector::Ability(AbilityScoreType::Dexterity)
+        )
+    }
+}
+
+/// A named bonus or penalty, e.g. a +1 status bonus to saves from Heroism.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Modifier {
+    name: String,
+    modifier_type: ModifierType,
+    value: i32,
+    selector: Selector,
+}
+
+impl Modifier {
+    pub fn new(
+        name: &str,
+        modifier_type: ModifierType,
+        value: i32,
+        selector: Selector,
+    ) -> Modifier {
+        Modifier {
+            name: name.to_string(),
+            modifier_type,
+            value,
+            selector,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn modifier_type(&self) -> ModifierType {
+        self.modifier_type
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn selector(&self) -> &Selector {
+        &self.selector
+    }
+}
+
+/// All modifiers that went into a statistic and which of them survived the stacking rules.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ModifierBreakdown {
+    modifiers: Vec<(Modifier, bool)>,
+    total: i32,
+}
+
+impl ModifierBreakdown {
+    /// Applies the stacking rules: of each type only the highest bonus and the worst penalty count, untyped
+    /// modifiers always count.
+    pub fn new(modifiers: Vec<Modifier>) -> ModifierBreakdown {
+        let mut applied = vec![false; modifiers.len()];
+        for (i, modifier) in modifiers.iter().enumerate() {
+            applied[i] = modifier.modifier_type == ModifierType::Untyped
+                || !modifiers.iter().enumerate().any(|(j, other)| {
+                    other.modifier_type == modifier.modifier_type
+                        && (other.value < 0) == (modifier.value < 0)
+                        && (other.value.abs() > modifier.value.abs()
+                            || (other.value == modifier.value && j < i))
+                });
+        }
+
+        let total = modifiers
+            .iter()
+            .zip(applied.iter())
+            .filter(|(_, &applied)| applied)
+            .map(|(modifier, _)| modifier.value)
+            .sum();
+        ModifierBreakdown {
+            modifiers: modifiers.into_iter().zip(applied).collect(),
+            total,
+        }
+    }
+
+    /// Every modifier together with whether it applies.
+    pub fn modifiers(&self) -> &Vec<(Modifier, bool)> {
+        &self.modifiers
+    }
+
+    pub fn applied(&self) -> impl Iterator<Item = &Modifier> {
+        self.modifiers
+            .iter()
+            .filter(|(_, applied)| *applied)
+            .map(|(modifier, _)| modifier)
+    }
+
+    pub fn total(&self) -> i32 {
+        self.total
+    }
+}
+
+impl fmt::Display for ModifierBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+}", self.total)?;
+        for (modifier, applied) in self.modifiers.iter() {
+            write!(f, ", {:+} {}", modifier.value, modifier.name)?;
+            if !applied {
+                write!(f, " (doesn't stack)")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_modifiers_dont_stack() {
+        let breakdown = ModifierBreakdown::new(vec![
+            Modifier::new("Heroism", ModifierType::Status, 1, Selector::All),
+            Modifier::new("Inspire Courage", ModifierType::Status, 1, Selector::All),
+            Modifier::new("Frightened", ModifierType::Status, -2, Selector::All),
+            Modifier::new("Sickened", ModifierType::Status, -1, Selector::All),
+            Modifier::new("Cover", ModifierType::Circumstance, 2, Selector::All),
+            Modifier::new("Multiple Attack", ModifierType::Untyped, -5, Selector::All),
+            Modifier::new("Range", ModifierType::Untyped, -2, Selector::All),
+        ]);
+
+        assert_eq!(breakdown.total(), 1 - 2 + 2 - 5 - 2);
+        let applied: Vec<_> = breakdown.applied().map(Modifier::name).collect();
+        assert_eq!(
+            applied,
+            vec!["Heroism", "Frightened", "Cover", "Multiple Attack", "Range"]
+        );
+    }
+
+    #[test]
+    fn selector_matches() {
+        let athletics = Statistic::Skill(crate::character::Skill::Athletics);
+
+        assert!(Selector::Skills.matches(&athletics));
+        assert!(Selector::Ability(AbilityScoreType::Strength).matches(&athletics));
+        assert!(!Selector::Saves.matches(&athletics));
+        assert!(Selector::Saves.matches(&Statistic::Will));
+    }
+}
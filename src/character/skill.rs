@@ -0,0 +1,338 @@
+use crate::character::AbilityScoreType;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Skill {
+    Acrobatics,
+    Arcana,
+    Athletics,
+    Crafting,
+    Deception,
+    Diplomacy,
+    Intimidation,
+    Lore(String),
+    Medicine,
+    Nature,
+    Occultism,
+    Performance,
+    Religion,
+    Society,
+    Stealth,
+    Survival,
+    Thievery,
+}
+
+impl Skill {
+    pub fn lore(topic: &str) -> Skill {
+        Skill::Lore(topic.to_string())
+    }
+
+    pub fn all() -> [Skill; 16] {
+        [
+            Skill::Acrobatics,
+            Skill::Arcana,
+            Skill::Athletics,
+            Skill::Crafting,
+            Skill::Deception,
+            Skill::Diplomacy,
+            Skill::Intimidation,
+            Skill::Medicine,
+            Skill::Nature,
+            Skill::Occultism,
+            Skill::Performance,
+            Skill::Religion,
+            Skill::Society,
+            Skill::Stealth,
+            Skill::Survival,
+            Skill::Thievery,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Skill::Lore(topic) => format!("{} Lore", topic),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    /// Parses the name of a skill, ignoring case. Lore skills are written as `<topic> Lore`.
+    pub fn from_name(name: &str) -> Option<Skill> {
+        let name = name.trim();
+        if let Some(topic) = name
+            .strip_suffix(" Lore")
+            .or_else(|| name.strip_suffix(" lore"))
+        {
+            return Some(Skill::lore(topic));
+        }
+        Skill::all()
+            .iter()
+            .find(|skill| skill.name().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    pub fn key_ability(&self) -> AbilityScoreType {
+        match self {
+            Skill::Athletics => AbilityScoreType::Strength,
+            Skill::Acrobatics | Skill::Stealth | Skill::Thievery => AbilityScoreType::Dexterity,
+            Skill::Arcana
+            | Skill::Crafting
+            | Skill::Lore(_)
+            | Skill::Occultism
+            | Skill::Society => AbilityScoreType::Intelligence,
+            Skill::Medicine | Skill::Nature | Skill::Religion | Skill::Survival => {
+                AbilityScoreType::Wisdom
+            }
+            Skill::Deception | Skill::Diplomacy | Skill::Intimidation | Skill::Performance => {
+                AbilityScoreType::Charisma
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Proficiency {
+    Untrained,
+    Trained,
+    Expert,
+    Master,
+    Legendary,
+}
+
+impl Proficiency {
+    /// The proficiency bonus at the given level. Untrained characters don't add their level.
+    pub fn bonus(&self, level: u32) -> i32 {
+        let level = level as i32;
+        match self {
+            Proficiency::Untrained => 0,
+            Proficiency::Trained => level + 2,
+            Proficiency::Expert => level + 4,
+            Proficiency::Master => level + 6,
+            Proficiency::Legendary => level + 8,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct SkillTrainingFree {}
+
+impl SkillTrainingFree {
+    pub fn choose(&self, skill: Skill) -> Skill {
+        skill
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct SkillTrainingRestricted {
+    options: Vec<Skill>,
+}
+
+impl SkillTrainingRestricted {
+    pub fn options(&self) -> &Vec<Skill> {
+        &self.options
+    }
+
+    pub fn choose(&self, skill: Skill) -> Result<Skill> {
+        if self.options.contains(&skill) {
+            Ok(skill)
+        } else {
+            Err(Error::InvalidSkillChoice {
+                index: 0,
+                choice: skill,
+                options: self.options.clone(),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SkillTrainingChoice {
+    Free(SkillTrainingFree),
+    Restricted(SkillTrainingRestricted),
+    Predetermined(Skill),
+}
+
+impl SkillTrainingChoice {
+    pub fn free() -> SkillTrainingChoice {
+        SkillTrainingChoice::Free(SkillTrainingFree {})
+    }
+
+    /// The options are sorted, so the first one is the same on every run.
+    pub fn restricted(options: HashSet<Skill>) -> SkillTrainingChoice {
+        let mut options: Vec<Skill> = options.into_iter().collect();
+        options.sort();
+        SkillTrainingChoice::Restricted(SkillTrainingRestricted { options })
+    }
+
+    pub fn predetermined(skill: Skill) -> SkillTrainingChoice {
+        SkillTrainingChoice::Predetermined(skill)
+    }
+}
+
+pub trait SkillTrainingChoiceSet {
+    /// Resolves the trainings like `AbilityBoostChoiceSet::apply_choices` resolves boosts.
+    fn apply_choices(&self, choices: &[Skill]) -> Result<HashSet<Skill>>;
+}
+
+impl SkillTrainingChoiceSet for Vec<SkillTrainingChoice> {
+    fn apply_choices(&self, choices: &[Skill]) -> Result<HashSet<Skill>> {
+        let expected = self
+            .iter()
+            .filter(|training| !matches!(training, SkillTrainingChoice::Predetermined(_)))
+            .count();
+        let mut choices_iter = choices.iter();
+        let mut result = HashSet::with_capacity(self.len());
+        for (index, training) in self.iter().enumerate() {
+            let choice = match training {
+                SkillTrainingChoice::Predetermined(skill) => skill.clone(),
+                SkillTrainingChoice::Free(_) | SkillTrainingChoice::Restricted(_) => {
+                    if let Some(choice) = choices_iter.next() {
+                        match training {
+                            SkillTrainingChoice::Free(free) => free.choose(choice.clone()),
+                            SkillTrainingChoice::Restricted(options) => options
+                                .choose(choice.clone())
+                                .map_err(|error| error.at_index(index))?,
+                            _ => panic!("should never happen"),
+                        }
+                    } else {
+                        return Err(Error::TooFewSkillChoices {
+                            expected,
+                            provided: choices.len(),
+                        });
+                    }
+                }
+            };
+            if result.contains(&choice) {
+                return Err(Error::DuplicateSkill { index, choice });
+            } else {
+                result.insert(choice);
+            }
+        }
+
+        if choices_iter.next().is_some() {
+            Err(Error::TooManySkillChoices {
+                expected,
+                provided: choices.len(),
+            })
+        } else {
+            Ok(result)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SkillSet {
+    proficiencies: HashMap<Skill, Proficiency>,
+}
+
+impl SkillSet {
+    pub fn new() -> SkillSet {
+        SkillSet {
+            proficiencies: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, skill: &Skill) -> Proficiency {
+        *self
+            .proficiencies
+            .get(skill)
+            .unwrap_or(&Proficiency::Untrained)
+    }
+
+    pub fn set(&mut self, skill: Skill, proficiency: Proficiency) {
+        self.proficiencies.insert(skill, proficiency);
+    }
+
+    /// Makes the character trained in the given skills. Skills that are already trained or better are left alone.
+    pub fn train(&mut self, skills: &HashSet<Skill>) {
+        for skill in skills.iter() {
+            if self.get(skill) < Proficiency::Trained {
+                self.set(skill.clone(), Proficiency::Trained);
+            }
+        }
+    }
+
+    /// Lists all skills the character is at least trained in.
+    pub fn trained(&self) -> Vec<(&Skill, Proficiency)> {
+        let mut trained: Vec<_> = self
+            .proficiencies
+            .iter()
+            .filter(|(_, &proficiency)| proficiency > Proficiency::Untrained)
+            .map(|(skill, &proficiency)| (skill, proficiency))
+            .collect();
+        trained.sort();
+        trained
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proficiency_bonus() {
+        assert_eq!(Proficiency::Untrained.bonus(5), 0);
+        assert_eq!(Proficiency::Trained.bonus(1), 3);
+        assert_eq!(Proficiency::Legendary.bonus(20), 28);
+    }
+
+    #[test]
+    fn skill_from_name() {
+        assert_eq!(Skill::from_name("athletics"), Some(Skill::Athletics));
+        assert_eq!(
+            Skill::from_name("Warfare Lore"),
+            Some(Skill::lore("Warfare"))
+        );
+        assert_eq!(Skill::from_name("Basket Weaving"), None);
+    }
+
+    #[test]
+    fn skill_set_train_does_not_downgrade() {
+        let mut set = SkillSet::new();
+        set.set(Skill::Athletics, Proficiency::Expert);
+
+        set.train(&hashset![Skill::Athletics, Skill::lore("Warfare")]);
+
+        assert_eq!(set.get(&Skill::Athletics), Proficiency::Expert);
+        assert_eq!(set.get(&Skill::lore("Warfare")), Proficiency::Trained);
+        assert_eq!(set.get(&Skill::Arcana), Proficiency::Untrained);
+    }
+
+    #[test]
+    fn skill_training_set_works() {
+        let set = vec![
+            SkillTrainingChoice::restricted(hashset![Skill::Athletics, Skill::Acrobatics]),
+            SkillTrainingChoice::predetermined(Skill::lore("Warfare")),
+        ];
+
+        let skills = set.apply_choices(&[Skill::Acrobatics]).unwrap();
+
+        assert_eq!(skills, hashset![Skill::Acrobatics, Skill::lore("Warfare")]);
+        match &set[0] {
+            SkillTrainingChoice::Restricted(choice) => {
+                assert_eq!(choice.options(), &vec![Skill::Acrobatics, Skill::Athletics])
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn skill_training_set_incorrect_restricted_choice() {
+        let set = vec![SkillTrainingChoice::restricted(hashset![
+            Skill::Athletics,
+            Skill::Acrobatics
+        ])];
+
+        set.apply_choices(&[Skill::Arcana]).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn skill_training_set_too_few_choices() {
+        let set = vec![SkillTrainingChoice::free()];
+
+        set.apply_choices(&[]).unwrap();
+    }
+}
@@ -0,0 +1,168 @@
+use crate::character::{Character, Modifier, ModifierBreakdown, ModifierType, Selector};
+use crate::error::{Error, Result};
+use crate::spell::{SpellCast, SpellcastingEntry};
+
+/// Spellcasting entries, spell attacks and DCs, and casting.
+impl<'class, 'ancestry, 'background> Character<'class, 'ancestry, 'background> {
+    pub fn spellcasting(&self) -> &Vec<SpellcastingEntry> {
+        &self.spellcasting
+    }
+
+    /// Adds a spellcasting entry, replacing an entry with the same name.
+    pub fn add_spellcasting(&mut self, entry: SpellcastingEntry) {
+        self.spellcasting
+            .retain(|existing| !existing.name().eq_ignore_ascii_case(entry.name()));
+        self.spellcasting.push(entry);
+    }
+
+    pub fn spellcasting_entry(&self, name: &str) -> Result<&SpellcastingEntry> {
+        self.spellcasting
+            .iter()
+            .find(|entry| entry.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::UnknownSpellcasting(name.to_string()))
+    }
+
+    pub fn spellcasting_entry_mut(&mut self, name: &str) -> Result<&mut SpellcastingEntry> {
+        self.spellcasting
+            .iter_mut()
+            .find(|entry| entry.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::UnknownSpellcasting(name.to_string()))
+    }
+
+    /// Collects the modifiers of spell attack rolls with the entry: its ability, its proficiency and active
+    /// modifiers. Spell DCs use the same modifiers.
+    pub fn spell_attack_breakdown(
+        &self,
+        entry: &str,
+        situational: &[Modifier],
+    ) -> Result<ModifierBreakdown> {
+        let entry = self.spellcasting_entry(entry)?;
+        let ability = entry.ability();
+        let mut modifiers = vec![
+            Modifier::new(
+                &format!("{:?}", ability),
+                ModifierType::Ability,
+                self.ability_scores.get(ability).modifier(),
+                Selector::Ability(ability),
+            ),
+            Modifier::new(
+                &format!("{:?}", entry.proficiency()),
+                ModifierType::Proficiency,
+                entry.proficiency().bonus(self.level),
+                Selector::Spellcasting,
+            ),
+        ];
+        modifiers.extend(
+            self.modifiers
+                .iter()
+                .chain(self.derived_modifiers().iter())
+                .filter(|modifier| modifier.selector().matches_spellcasting(ability))
+                .cloned(),
+        );
+        modifiers.extend(situational.iter().cloned());
+        Ok(ModifierBreakdown::new(modifiers))
+    }
+
+    pub fn spell_attack(&self, entry: &str) -> Result<i32> {
+        Ok(self.spell_attack_breakdown(entry, &[])?.total())
+    }
+
+    pub fn spell_dc(&self, entry: &str) -> Result<i32> {
+        Ok(10 + self.spell_attack(entry)?)
+    }
+
+    /// Prepares a spell from the entry's spellbook in a slot of the given rank.
+    pub fn prepare_spell(&mut self, entry: &str, spell: &str, rank: u32) -> Result<()> {
+        let level = self.level;
+        self.spellcasting_entry_mut(entry)?
+            .prepare(spell, rank, level)
+    }
+
+    /// Casts a spell at the given rank, expending the prepared spell or a slot. Cantrips are cast at half the
+    /// character's level and use neither.
+    pub fn cast_spell(&mut self, entry: &str, spell: &str, rank: u32) -> Result<SpellCast> {
+        let attack = self.spell_attack(entry)?;
+        let level = self.level;
+        let (spell, rank) = self
+            .spellcasting_entry_mut(entry)?
+            .cast(spell, rank, level)?;
+        Ok(SpellCast::new(spell, rank, attack, 10 + attack))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::character::{AbilityScoreType, Ancestry, Background, Class, Size};
+    use crate::spell::{CastingType, SlotProgression, Spell, Tradition};
+
+    #[test]
+    fn spellcasting_uses_the_key_ability() {
+        let mut class = Class::new("Bob".to_string(), AbilityScoreType::Strength, 10);
+        class.set_spellcasting(Some((Tradition::Primal, CastingType::Spontaneous)));
+        class.set_slot_progression(SlotProgression::Table(vec![vec![2]]));
+        let ancestry = Ancestry::new("Bob".to_string(), 8, Size::Medium, 25, vec![]);
+        let background = Background::new("Bob".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        let mut tangle_vine = Spell::new("Tangle Vine", 0, 2);
+        tangle_vine.add_tradition(Tradition::Primal);
+        let mut heal = Spell::new("Heal", 1, 2);
+        heal.add_tradition(Tradition::Primal);
+        let entry = character.spellcasting_entry_mut("bob").unwrap();
+        entry.add_spell(tangle_vine).unwrap();
+        entry.add_spell(heal).unwrap();
+        character.add_modifier(Modifier::new(
+            "Heroism",
+            ModifierType::Status,
+            1,
+            Selector::Spellcasting,
+        ));
+
+        // +2 Strength, +3 Trained, +1 Heroism
+        assert_eq!(character.spell_attack("Bob").unwrap(), 6);
+        assert_eq!(character.spell_dc("Bob").unwrap(), 16);
+
+        let cast = character.cast_spell("Bob", "Tangle Vine", 0).unwrap();
+        assert_eq!((cast.rank(), cast.dc()), (1, 16));
+        // the class's table grants two 1st-rank slots instead of the full caster's three
+        for _ in 0..2 {
+            character.cast_spell("Bob", "Heal", 1).unwrap();
+        }
+        assert_eq!(
+            character
+                .spellcasting_entry("Bob")
+                .unwrap()
+                .remaining_slots(1, 1),
+            0
+        );
+        assert!(character.cast_spell("Bob", "Heal", 1).is_err());
+        character.cast_spell("Bob", "Tangle Vine", 0).unwrap();
+        assert!(character.cast_spell("Wizard", "Heal", 1).is_err());
+
+        character.daily_preparations();
+        let level = character.level();
+        assert_eq!(
+            character
+                .spellcasting_entry("Bob")
+                .unwrap()
+                .remaining_slots(level, 1),
+            2
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::character::{AbilityScoreType, ModifierBreakdown, Skill};
+use crate::dice::{CheckResult, Roll, Visibility};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Anything a character can roll a check with.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Statistic {
+    Perception,
+    Fortitude,
+    Reflex,
+    Will,
+    Skill(Skill),
+}
+
+impl Statistic {
+    pub fn saves() -> [Statistic; 3] {
+        [Statistic::Fortitude, Statistic::Reflex, Statistic::Will]
+    }
+
+    pub fn is_save(&self) -> bool {
+        matches!(
+            self,
+            Statistic::Fortitude | Statistic::Reflex | Statistic::Will
+        )
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Statistic::Skill(skill) => skill.name(),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    /// A lowercase identifier like `athletics` or `warfare-lore`.
+    pub fn id(&self) -> String {
+        self.name().to_lowercase().replace(' ', "-")
+    }
+
+    /// Parses a name or id, ignoring case.
+    pub fn from_name(name: &str) -> Option<Statistic> {
+        let name = name.trim();
+        let parse = |name: &str| match name.to_lowercase().as_str() {
+            "perception" => Some(Statistic::Perception),
+            "fortitude" => Some(Statistic::Fortitude),
+            "reflex" => Some(Statistic::Reflex),
+            "will" => Some(Statistic::Will),
+            _ => Skill::from_name(name).map(Statistic::Skill),
+        };
+        parse(name).or_else(|| parse(&name.replace('-', " ")))
+    }
+
+    pub fn key_ability(&self) -> AbilityScoreType {
+        match self {
+            Statistic::Perception | Statistic::Will => AbilityScoreType::Wisdom,
+            Statistic::Fortitude => AbilityScoreType::Constitution,
+            Statistic::Reflex => AbilityScoreType::Dexterity,
+            Statistic::Skill(skill) => skill.key_ability(),
+        }
+    }
+}
+
+impl fmt::Display for Statistic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Statistic {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Statistic> {
+        Statistic::from_name(name).ok_or_else(|| Error::UnknownStatistic(name.to_string()))
+    }
+}
+
+/// A roll of a character's statistic without a DC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatisticRoll {
+    statistic: Statistic,
+    breakdown: ModifierBreakdown,
+    roll: Roll,
+    visibility: Visibility,
+}
+
+impl StatisticRoll {
+    pub(crate) fn new(
+        statistic: Statistic,
+        breakdown: ModifierBreakdown,
+        roll: Roll,
+        visibility: Visibility,
+    ) -> StatisticRoll {
+        StatisticRoll {
+            statistic,
+            breakdown,
+            roll,
+            visibility,
+        }
+    }
+
+    pub fn statistic(&self) -> &Statistic {
+        &self.statistic
+    }
+
+    /// How the modifier added to the d20 was put together.
+    pub fn breakdown(&self) -> &ModifierBreakdown {
+        &self.breakdown
+    }
+
+    pub fn roll(&self) -> &Roll {
+        &self.roll
+    }
+
+    pub fn total(&self) -> i32 {
+        self.roll.total()
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+/// A check of a character's statistic against a DC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatisticCheck {
+    statistic: Statistic,
+    breakdown: ModifierBreakdown,
+    result: CheckResult,
+}
+
+impl StatisticCheck {
+    pub(crate) fn new(
+        statistic: Statistic,
+        breakdown: ModifierBreakdown,
+        result: CheckResult,
+    ) -> StatisticCheck {
+        StatisticCheck {
+            statistic,
+            breakdown,
+            result,
+        }
+    }
+
+    pub fn statistic(&self) -> &Statistic {
+        &self.statistic
+    }
+
+    pub fn breakdown(&self) -> &ModifierBreakdown {
+        &self.breakdown
+    }
+
+    pub fn result(&self) -> &CheckResult {
+        &self.result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name_accepts_names_and_ids() {
+        assert_eq!(
+            Statistic::from_name("Fortitude"),
+            Some(Statistic::Fortitude)
+        );
+        assert_eq!(
+            Statistic::from_name("athletics"),
+            Some(Statistic::Skill(Skill::Athletics))
+        );
+        assert_eq!(
+            "warfare-lore".parse::<Statistic>().unwrap(),
+            Statistic::Skill(Skill::lore("warfare"))
+        );
+        assert_eq!(
+            Statistic::Skill(Skill::lore("Warfare")).id(),
+            "warfare-lore"
+        );
+        assert!("Fort Knox".parse::<Statistic>().is_err());
+    }
+}
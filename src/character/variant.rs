@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// Optional rules a campaign plays with.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VariantRules {
+    automatic_bonus_progression: bool,
+}
+
+impl VariantRules {
+    pub fn new() -> VariantRules {
+        VariantRules::default()
+    }
+
+    /// Potency, striking and resilient bonuses come from the character's level instead of runes.
+    pub fn automatic_bonus_progression(&self) -> bool {
+        self.automatic_bonus_progression
+    }
+
+    pub fn set_automatic_bonus_progression(&mut self, enabled: bool) {
+        self.automatic_bonus_progression = enabled;
+    }
+}
+
+/// Counts how many of the `thresholds` levels have been reached.
+fn reached(level: u32, thresholds: [u32; 3]) -> u32 {
+    thresholds
+        .iter()
+        .filter(|&&threshold| level >= threshold)
+        .count() as u32
+}
+
+/// With automatic bonus progression, the item bonus to attack rolls that replaces weapon potency runes.
+pub fn attack_potency(level: u32) -> u32 {
+    reached(level, [2, 10, 16])
+}
+
+/// With automatic bonus progression, the number of weapon damage dice that replaces striking runes.
+pub fn devastating_dice(level: u32) -> u32 {
+    1 + reached(level, [4, 12, 19])
+}
+
+/// With automatic bonus progression, the item bonus to AC that replaces armor potency runes.
+pub fn defense_potency(level: u32) -> u32 {
+    reached(level, [5, 11, 18])
+}
+
+/// With automatic bonus progression, the item bonus to saves that replaces resilient runes.
+pub fn save_potency(level: u32) -> u32 {
+    reached(level, [8, 14, 20])
+}
+
+/// With automatic bonus progression, the item bonus to Perception.
+pub fn perception_potency(level: u32) -> u32 {
+    reached(level, [7, 13, 19])
+}
+
+/// With automatic bonus progression, the skill potency bonuses a character can assign at the given level, highest
+/// first. Each bonus goes to a different skill.
+pub fn skill_potency_slots(level: u32) -> &'static [u32] {
+    match level {
+        0..=2 => &[],
+        3..=5 => &[1],
+        6..=8 => &[1, 1],
+        9..=12 => &[2, 1],
+        13..=14 => &[2, 2, 1],
+        15..=16 => &[3, 2, 1, 1],
+        17..=19 => &[3, 3, 2, 1],
+        _ => &[3, 3, 3, 2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bonuses_grow_with_level() {
+        assert_eq!(attack_potency(1), 0);
+        assert_eq!(attack_potency(10), 2);
+        assert_eq!(devastating_dice(3), 1);
+        assert_eq!(devastating_dice(19), 4);
+        assert_eq!(defense_potency(11), 2);
+        assert_eq!(save_potency(20), 3);
+        assert_eq!(perception_potency(7), 1);
+        assert_eq!(skill_potency_slots(9), &[2, 1]);
+    }
+}
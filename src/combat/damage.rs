@@ -0,0 +1,460 @@
+use crate::dice::{DegreeOfSuccess, DiceExpression, DiceRng, Roll};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum DamageType {
+    Bludgeoning,
+    Piercing,
+    Slashing,
+    Acid,
+    Cold,
+    Electricity,
+    Fire,
+    Sonic,
+    Vitality,
+    Void,
+    Force,
+    Mental,
+    Poison,
+    Bleed,
+    Spirit,
+}
+
+impl DamageType {
+    pub fn all() -> [DamageType; 15] {
+        [
+            DamageType::Bludgeoning,
+            DamageType::Piercing,
+            DamageType::Slashing,
+            DamageType::Acid,
+            DamageType::Cold,
+            DamageType::Electricity,
+            DamageType::Fire,
+            DamageType::Sonic,
+            DamageType::Vitality,
+            DamageType::Void,
+            DamageType::Force,
+            DamageType::Mental,
+            DamageType::Poison,
+            DamageType::Bleed,
+            DamageType::Spirit,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        format!("{:?}", self).to_lowercase()
+    }
+
+    /// Parses a damage type, ignoring case. The pre-remaster names positive and negative are accepted as well.
+    pub fn from_name(name: &str) -> Option<DamageType> {
+        let name = name.trim().to_lowercase();
+        match name.as_str() {
+            "positive" => Some(DamageType::Vitality),
+            "negative" => Some(DamageType::Void),
+            _ => DamageType::all()
+                .iter()
+                .find(|damage_type| damage_type.name() == name)
+                .copied(),
+        }
+    }
+
+    pub fn is_physical(&self) -> bool {
+        matches!(
+            self,
+            DamageType::Bludgeoning | DamageType::Piercing | DamageType::Slashing
+        )
+    }
+}
+
+/// Precision and splash damage follow special rules, everything else is normal damage.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum DamageCategory {
+    Normal,
+    /// Doubled on a critical hit, ignored by creatures immune to precision damage.
+    Precision,
+    /// Dealt even on a failure and never doubled.
+    Splash,
+}
+
+/// Extra damage on top of a weapon's own, e.g. `1d6` fire from a flaming rune or `2d6` precision from Sneak Attack.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DamagePart {
+    expression: DiceExpression,
+    damage_type: DamageType,
+    category: DamageCategory,
+}
+
+impl DamagePart {
+    pub fn new(
+        expression: DiceExpression,
+        damage_type: DamageType,
+        category: DamageCategory,
+    ) -> DamagePart {
+        DamagePart {
+            expression,
+            damage_type,
+            category,
+        }
+    }
+
+    pub fn expression(&self) -> &DiceExpression {
+        &self.expression
+    }
+
+    pub fn damage_type(&self) -> DamageType {
+        self.damage_type
+    }
+
+    pub fn category(&self) -> DamageCategory {
+        self.category
+    }
+}
+
+/// Everything needed to roll the damage of a Strike: the weapon damage dice and modifier, the deadly and fatal
+/// traits and any additional damage.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DamageFormula {
+    dice: u32,
+    die_size: u32,
+    modifier: i32,
+    damage_type: DamageType,
+    deadly: Option<u32>,
+    fatal: Option<u32>,
+    parts: Vec<DamagePart>,
+}
+
+impl DamageFormula {
+    pub fn new(dice: u32, die_size: u32, modifier: i32, damage_type: DamageType) -> DamageFormula {
+        DamageFormula {
+            dice,
+            die_size,
+            modifier,
+            damage_type,
+            deadly: None,
+            fatal: None,
+            parts: vec![],
+        }
+    }
+
+    pub fn dice(&self) -> u32 {
+        self.dice
+    }
+
+    pub fn die_size(&self) -> u32 {
+        self.die_size
+    }
+
+    pub fn modifier(&self) -> i32 {
+        self.modifier
+    }
+
+    pub fn damage_type(&self) -> DamageType {
+        self.damage_type
+    }
+
+    pub fn deadly(&self) -> Option<u32> {
+        self.deadly
+    }
+
+    pub fn fatal(&self) -> Option<u32> {
+        self.fatal
+    }
+
+    pub fn parts(&self) -> &Vec<DamagePart> {
+        &self.parts
+    }
+
+    /// Deadly adds dice of the given size on a critical hit: one die, two with three weapon dice and three with four.
+    pub fn set_deadly(&mut self, die_size: u32) {
+        self.deadly = Some(die_size);
+    }
+
+    /// Fatal upgrades the weapon dice to the given size on a critical hit and adds one more die of that size.
+    pub fn set_fatal(&mut self, die_size: u32) {
+        self.fatal = Some(die_size);
+    }
+
+    pub fn add_modifier(&mut self, modifier: i32) {
+        self.modifier += modifier;
+    }
+
+    pub fn add_part(&mut self, part: DamagePart) {
+        self.parts.push(part);
+    }
+
+    /// Rolls damage for a Strike with the given outcome. Critical failures deal no damage and failures only deal
+    /// splash damage.
+    pub fn roll<R: DiceRng + ?Sized>(
+        &self,
+        degree: DegreeOfSuccess,
+        rng: &mut R,
+    ) -> Result<Damage> {
+        let mut damage = Damage::default();
+        if degree == DegreeOfSuccess::CriticalFailure {
+            return Ok(damage);
+        }
+        let hit = degree.is_success();
+        let critical = degree == DegreeOfSuccess::CriticalSuccess;
+        let multiplier = if critical { 2 } else { 1 };
+
+        if hit {
+            let die_size = match self.fatal {
+                Some(fatal) if critical => fatal,
+                _ => self.die_size,
+            };
+            let weapon =
+                DiceExpression::parse(&format!("{}d{}{:+}", self.dice, die_size, self.modifier))?
+                    .roll(rng)?;
+            // penalties can't reduce the damage of a hit below 1
+            damage.add(
+                self.damage_type,
+                DamageCategory::Normal,
+                weapon.total().max(1) * multiplier,
+            );
+            damage.rolls.push(weapon);
+
+            if critical {
+                let mut extra = Vec::new();
+                if let Some(deadly) = self.deadly {
+                    let count = match self.dice {
+                        0..=2 => 1,
+                        3 => 2,
+                        _ => 3,
+                    };
+                    extra.push(format!("{}d{}", count, deadly));
+                }
+                if let Some(fatal) = self.fatal {
+                    extra.push(format!("1d{}", fatal));
+                }
+                for expression in extra {
+                    let roll = DiceExpression::parse(&expression)?.roll(rng)?;
+                    damage.add(self.damage_type, DamageCategory::Normal, roll.total());
+                    damage.rolls.push(roll);
+                }
+            }
+        }
+
+        for part in self.parts.iter() {
+            let factor = match part.category {
+                DamageCategory::Splash => 1,
+                _ if hit => multiplier,
+                _ => continue,
+            };
+            let roll = part.expression.roll(rng)?;
+            damage.add(
+                part.damage_type,
+                part.category,
+                roll.total().max(0) * factor,
+            );
+            damage.rolls.push(roll);
+        }
+        Ok(damage)
+    }
+}
+
+/// Rolled damage, split into typed components.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Damage {
+    components: BTreeMap<(DamageType, DamageCategory), u32>,
+    rolls: Vec<Roll>,
+}
+
+impl Damage {
+    /// Creates damage of a single type without rolling, e.g. from a fixed hazard.
+    pub fn of(damage_type: DamageType, amount: u32) -> Damage {
+        let mut damage = Damage::default();
+        damage.add(damage_type, DamageCategory::Normal, amount as i32);
+        damage
+    }
+
+    fn add(&mut self, damage_type: DamageType, category: DamageCategory, amount: i32) {
+        if amount > 0 {
+            *self.components.entry((damage_type, category)).or_insert(0) += amount as u32;
+        }
+    }
+
+    /// The amount of each type and category of damage.
+    pub fn components(&self) -> &BTreeMap<(DamageType, DamageCategory), u32> {
+        &self.components
+    }
+
+    /// The dice rolled for this damage, in the order they were rolled.
+    pub fn rolls(&self) -> &Vec<Roll> {
+        &self.rolls
+    }
+
+    pub fn total(&self) -> u32 {
+        self.components.values().sum()
+    }
+
+    /// Sums the damage by type, as resistances and weaknesses apply once per type.
+    pub fn by_type(&self) -> BTreeMap<DamageType, u32> {
+        let mut totals = BTreeMap::new();
+        for ((damage_type, _), amount) in self.components.iter() {
+            *totals.entry(*damage_type).or_insert(0) += amount;
+        }
+        totals
+    }
+}
+
+/// A creature's immunities, resistances and weaknesses against damage.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Defenses {
+    immunities: HashSet<DamageType>,
+    precision_immunity: bool,
+    resistances: HashMap<DamageType, u32>,
+    weaknesses: HashMap<DamageType, u32>,
+}
+
+impl Defenses {
+    pub fn new() -> Defenses {
+        Defenses::default()
+    }
+
+    pub fn add_immunity(&mut self, damage_type: DamageType) {
+        self.immunities.insert(damage_type);
+    }
+
+    pub fn set_precision_immunity(&mut self, immune: bool) {
+        self.precision_immunity = immune;
+    }
+
+    pub fn set_resistance(&mut self, damage_type: DamageType, value: u32) {
+        self.resistances.insert(damage_type, value);
+    }
+
+    pub fn set_weakness(&mut self, damage_type: DamageType, value: u32) {
+        self.weaknesses.insert(damage_type, value);
+    }
+
+    pub fn immune(&self, damage_type: DamageType) -> bool {
+        self.immunities.contains(&damage_type)
+    }
+
+    pub fn resistance(&self, damage_type: DamageType) -> u32 {
+        self.resistances.get(&damage_type).copied().unwrap_or(0)
+    }
+
+    pub fn weakness(&self, damage_type: DamageType) -> u32 {
+        self.weaknesses.get(&damage_type).copied().unwrap_or(0)
+    }
+
+    /// How much of the damage gets through: immunities remove a type, then weaknesses add to and resistances
+    /// subtract from the total of each type.
+    pub fn apply(&self, damage: &Damage) -> u32 {
+        let mut totals = BTreeMap::new();
+        for ((damage_type, category), amount) in damage.components.iter() {
+            if *category == DamageCategory::Precision && self.precision_immunity {
+                continue;
+            }
+            *totals.entry(*damage_type).or_insert(0) += amount;
+        }
+        totals
+            .into_iter()
+            .filter(|(damage_type, amount)| !self.immune(*damage_type) && *amount > 0)
+            .map(|(damage_type, amount)| {
+                (amount + self.weakness(damage_type)).saturating_sub(self.resistance(damage_type))
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rolls a 1 on every die.
+    struct Ones;
+
+    impl DiceRng for Ones {
+        fn next_u64(&mut self) -> u64 {
+            0
+        }
+    }
+
+    fn part(expression: &str, damage_type: DamageType, category: DamageCategory) -> DamagePart {
+        DamagePart::new(
+            DiceExpression::parse(expression).unwrap(),
+            damage_type,
+            category,
+        )
+    }
+
+    #[test]
+    fn critical_hits_double_and_add_deadly_dice() {
+        let mut formula = DamageFormula::new(2, 8, 3, DamageType::Piercing);
+        formula.set_deadly(10);
+        formula.add_part(part("1d6", DamageType::Fire, DamageCategory::Normal));
+
+        let hit = formula.roll(DegreeOfSuccess::Success, &mut Ones).unwrap();
+        let critical = formula
+            .roll(DegreeOfSuccess::CriticalSuccess, &mut Ones)
+            .unwrap();
+
+        assert_eq!(hit.by_type()[&DamageType::Piercing], 5);
+        assert_eq!(hit.total(), 6);
+        assert_eq!(critical.by_type()[&DamageType::Piercing], 11);
+        assert_eq!(critical.by_type()[&DamageType::Fire], 2);
+    }
+
+    #[test]
+    fn fatal_upgrades_dice_on_critical_hits() {
+        let mut formula = DamageFormula::new(1, 8, 0, DamageType::Slashing);
+        formula.set_fatal(12);
+
+        let damage = formula
+            .roll(DegreeOfSuccess::CriticalSuccess, &mut Ones)
+            .unwrap();
+
+        assert_eq!(damage.total(), 3);
+        assert!(damage
+            .rolls()
+            .iter()
+            .flat_map(|roll| roll.dice())
+            .all(|die| die.sides() == 12));
+    }
+
+    #[test]
+    fn precision_splash_and_minimum_damage() {
+        let mut formula = DamageFormula::new(1, 4, -3, DamageType::Bludgeoning);
+        formula.add_part(part(
+            "2d6",
+            DamageType::Bludgeoning,
+            DamageCategory::Precision,
+        ));
+        formula.add_part(part("1", DamageType::Fire, DamageCategory::Splash));
+
+        let critical = formula
+            .roll(DegreeOfSuccess::CriticalSuccess, &mut Ones)
+            .unwrap();
+        let failure = formula.roll(DegreeOfSuccess::Failure, &mut Ones).unwrap();
+        let critical_failure = formula
+            .roll(DegreeOfSuccess::CriticalFailure, &mut Ones)
+            .unwrap();
+
+        // 1d4-3 becomes 1 and is doubled, precision is doubled, splash isn't
+        assert_eq!(critical.by_type()[&DamageType::Bludgeoning], 2 + 4);
+        assert_eq!(critical.by_type()[&DamageType::Fire], 1);
+        assert_eq!(failure.total(), 1);
+        assert_eq!(critical_failure.total(), 0);
+    }
+
+    #[test]
+    fn defenses_apply_per_type() {
+        let mut formula = DamageFormula::new(1, 6, 4, DamageType::Slashing);
+        formula.add_part(part("2d6", DamageType::Slashing, DamageCategory::Precision));
+        formula.add_part(part("3", DamageType::Fire, DamageCategory::Normal));
+        formula.add_part(part("2", DamageType::Poison, DamageCategory::Normal));
+        let damage = formula.roll(DegreeOfSuccess::Success, &mut Ones).unwrap();
+
+        let mut defenses = Defenses::new();
+        defenses.set_precision_immunity(true);
+        defenses.set_resistance(DamageType::Slashing, 2);
+        defenses.set_weakness(DamageType::Fire, 5);
+        defenses.add_immunity(DamageType::Poison);
+
+        assert_eq!(defenses.apply(&damage), (5 - 2) + (3 + 5));
+    }
+}
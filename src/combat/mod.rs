@@ -0,0 +1,3 @@
+mod damage;
+
+pub use damage::{Damage, DamageCategory, DamageFormula, DamagePart, DamageType, Defenses};
@@ -0,0 +1,159 @@
+use crate::character::{Ancestry, Background, Class, Feat};
+use crate::item::Item;
+use crate::spell::Spell;
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ContentKind {
+    Ancestry,
+    Background,
+    Class,
+    Feat,
+    Item,
+    Spell,
+}
+
+impl ContentKind {
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            ContentKind::Ancestry => "ancestry",
+            ContentKind::Background => "background",
+            ContentKind::Class => "class",
+            ContentKind::Feat => "feat",
+            ContentKind::Item => "item",
+            ContentKind::Spell => "spell",
+        }
+    }
+}
+
+/// Holds all content characters can be built from. Content is looked up by name, ignoring case.
+#[derive(Default)]
+pub struct ContentLibrary {
+    ancestries: HashMap<String, Ancestry>,
+    backgrounds: HashMap<String, Background>,
+    classes: HashMap<String, Class>,
+    feats: HashMap<String, Feat>,
+    items: HashMap<String, Item>,
+    spells: HashMap<String, Spell>,
+}
+
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+impl ContentLibrary {
+    pub fn new() -> ContentLibrary {
+        ContentLibrary {
+            ancestries: HashMap::new(),
+            backgrounds: HashMap::new(),
+            classes: HashMap::new(),
+            feats: HashMap::new(),
+            items: HashMap::new(),
+            spells: HashMap::new(),
+        }
+    }
+
+    // ============================= Ancestries =============================
+
+    pub fn add_ancestry(&mut self, ancestry: Ancestry) {
+        self.ancestries.insert(key(ancestry.name()), ancestry);
+    }
+
+    pub fn ancestry(&self, name: &str) -> Option<&Ancestry> {
+        self.ancestries.get(&key(name))
+    }
+
+    pub fn ancestries(&self) -> impl Iterator<Item = &Ancestry> {
+        self.ancestries.values()
+    }
+
+    // ============================= Backgrounds =============================
+
+    pub fn add_background(&mut self, background: Background) {
+        self.backgrounds.insert(key(background.name()), background);
+    }
+
+    pub fn background(&self, name: &str) -> Option<&Background> {
+        self.backgrounds.get(&key(name))
+    }
+
+    pub fn backgrounds(&self) -> impl Iterator<Item = &Background> {
+        self.backgrounds.values()
+    }
+
+    // ============================= Classes =============================
+
+    pub fn add_class(&mut self, class: Class) {
+        self.classes.insert(key(class.name()), class);
+    }
+
+    pub fn class(&self, name: &str) -> Option<&Class> {
+        self.classes.get(&key(name))
+    }
+
+    pub fn classes(&self) -> impl Iterator<Item = &Class> {
+        self.classes.values()
+    }
+
+    // ============================= Feats =============================
+
+    pub fn add_feat(&mut self, feat: Feat) {
+        self.feats.insert(key(feat.name()), feat);
+    }
+
+    pub fn feat(&self, name: &str) -> Option<&Feat> {
+        self.feats.get(&key(name))
+    }
+
+    pub fn feats(&self) -> impl Iterator<Item = &Feat> {
+        self.feats.values()
+    }
+
+    // ============================= Items =============================
+
+    pub fn add_item(&mut self, item: Item) {
+        self.items.insert(key(item.name()), item);
+    }
+
+    pub fn item(&self, name: &str) -> Option<&Item> {
+        self.items.get(&key(name))
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        self.items.values()
+    }
+
+    // ============================= Spells =============================
+
+    pub fn add_spell(&mut self, spell: Spell) {
+        self.spells.insert(key(spell.name()), spell);
+    }
+
+    pub fn spell(&self, name: &str) -> Option<&Spell> {
+        self.spells.get(&key(name))
+    }
+
+    pub fn spells(&self) -> impl Iterator<Item = &Spell> {
+        self.spells.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::AbilityScoreType;
+
+    #[test]
+    fn lookup_ignores_case() {
+        let mut library = ContentLibrary::new();
+        library.add_class(Class::new(
+            "Fighter".to_string(),
+            AbilityScoreType::Strength,
+            10,
+        ));
+
+        assert!(library.class("fighter").is_some());
+        assert!(library.class(" FIGHTER ").is_some());
+        assert!(library.class("Wizard").is_none());
+    }
+}
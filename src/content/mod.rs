@@ -0,0 +1,6 @@
+mod library;
+mod rarity;
+
+pub use library::ContentKind;
+pub use library::ContentLibrary;
+pub use rarity::Rarity;
@@ -0,0 +1,50 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How hard something is to come by, from least to most restricted. Anything but common content needs access.
+#[derive(
+    Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Unique,
+}
+
+impl Rarity {
+    pub fn all() -> [Rarity; 4] {
+        [
+            Rarity::Common,
+            Rarity::Uncommon,
+            Rarity::Rare,
+            Rarity::Unique,
+        ]
+    }
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rarity::Common => write!(f, "common"),
+            Rarity::Uncommon => write!(f, "uncommon"),
+            Rarity::Rare => write!(f, "rare"),
+            Rarity::Unique => write!(f, "unique"),
+        }
+    }
+}
+
+impl FromStr for Rarity {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<Rarity> {
+        Rarity::all()
+            .iter()
+            .copied()
+            .find(|rarity| rarity.to_string().eq_ignore_ascii_case(source.trim()))
+            .ok_or_else(|| Error::InvalidFormat(format!("unknown rarity {:?}", source)))
+    }
+}
@@ -0,0 +1,375 @@
+use crate::dice::{DiceExpression, DiceRng, Roll, TermKind, Visibility};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+/// The DC of a task, item or effect of the given level. Levels above 25 keep rising by 2 per level.
+pub fn level_dc(level: u32) -> i32 {
+    const DCS: [i32; 26] = [
+        14, 15, 16, 18, 19, 20, 22, 23, 24, 26, 27, 28, 30, 31, 32, 34, 35, 36, 38, 39, 40, 42, 44,
+        46, 48, 50,
+    ];
+    match DCS.get(level as usize) {
+        Some(dc) => *dc,
+        None => 50 + 2 * (level as i32 - 25),
+    }
+}
+
+/// The outcome of a check, ordered from worst to best.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum DegreeOfSuccess {
+    CriticalFailure,
+    Failure,
+    Success,
+    CriticalSuccess,
+}
+
+impl DegreeOfSuccess {
+    /// Compares a check total against a DC, without looking at the natural roll.
+    pub fn from_total(total: i32, dc: i32) -> DegreeOfSuccess {
+        if total >= dc + 10 {
+            DegreeOfSuccess::CriticalSuccess
+        } else if total >= dc {
+            DegreeOfSuccess::Success
+        } else if total <= dc - 10 {
+            DegreeOfSuccess::CriticalFailure
+        } else {
+            DegreeOfSuccess::Failure
+        }
+    }
+
+    pub fn upgrade(self) -> DegreeOfSuccess {
+        match self {
+            DegreeOfSuccess::CriticalFailure => DegreeOfSuccess::Failure,
+            DegreeOfSuccess::Failure => DegreeOfSuccess::Success,
+            _ => DegreeOfSuccess::CriticalSuccess,
+        }
+    }
+
+    pub fn downgrade(self) -> DegreeOfSuccess {
+        match self {
+            DegreeOfSuccess::CriticalSuccess => DegreeOfSuccess::Success,
+            DegreeOfSuccess::Success => DegreeOfSuccess::Failure,
+            _ => DegreeOfSuccess::CriticalFailure,
+        }
+    }
+
+    pub fn is_success(self) -> bool {
+        self >= DegreeOfSuccess::Success
+    }
+}
+
+/// Turns one degree of success into another after the natural 20 or 1 has been applied, e.g. an ability that lets
+/// you treat critical failures as failures.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DegreeAdjustment {
+    name: String,
+    from: DegreeOfSuccess,
+    to: DegreeOfSuccess,
+}
+
+impl DegreeAdjustment {
+    pub fn new(name: &str, from: DegreeOfSuccess, to: DegreeOfSuccess) -> DegreeAdjustment {
+        DegreeAdjustment {
+            name: name.to_string(),
+            from,
+            to,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn from(&self) -> DegreeOfSuccess {
+        self.from
+    }
+
+    pub fn to(&self) -> DegreeOfSuccess {
+        self.to
+    }
+}
+
+/// Fortune effects roll the d20 twice and take the higher result, misfortune effects take the lower one. A check
+/// affected by both rolls normally.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum FortuneEffect {
+    Normal,
+    Fortune,
+    Misfortune,
+}
+
+impl FortuneEffect {
+    pub fn from_effects(fortune: bool, misfortune: bool) -> FortuneEffect {
+        match (fortune, misfortune) {
+            (true, false) => FortuneEffect::Fortune,
+            (false, true) => FortuneEffect::Misfortune,
+            _ => FortuneEffect::Normal,
+        }
+    }
+
+    fn d20(self) -> &'static str {
+        match self {
+            FortuneEffect::Normal => "1d20",
+            FortuneEffect::Fortune => "2d20kh1",
+            FortuneEffect::Misfortune => "2d20kl1",
+        }
+    }
+}
+
+/// A d20 check against a DC.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Check {
+    expression: DiceExpression,
+    dc: i32,
+    adjustments: Vec<DegreeAdjustment>,
+    traits: Vec<String>,
+    visibility: Option<Visibility>,
+}
+
+impl Check {
+    pub fn new(modifier: i32, dc: i32) -> Check {
+        Check::with_fortune(modifier, dc, FortuneEffect::Normal)
+    }
+
+    pub fn with_fortune(modifier: i32, dc: i32, fortune: FortuneEffect) -> Check {
+        let expression = DiceExpression::parse(&format!("{}{:+}", fortune.d20(), modifier))
+            .expect("a d20 plus a number is always valid");
+        Check::with_expression(expression, dc)
+    }
+
+    /// Creates a check from a custom expression. The first d20 of the expression is the natural roll.
+    pub fn with_expression(expression: DiceExpression, dc: i32) -> Check {
+        Check {
+            expression,
+            dc,
+            adjustments: vec![],
+            traits: vec![],
+            visibility: None,
+        }
+    }
+
+    pub fn expression(&self) -> &DiceExpression {
+        &self.expression
+    }
+
+    pub fn dc(&self) -> i32 {
+        self.dc
+    }
+
+    pub fn adjustments(&self) -> &Vec<DegreeAdjustment> {
+        &self.adjustments
+    }
+
+    /// Adjustments are applied in the order they were added.
+    pub fn add_adjustment(&mut self, adjustment: DegreeAdjustment) {
+        self.adjustments.push(adjustment);
+    }
+
+    pub fn traits(&self) -> &Vec<String> {
+        &self.traits
+    }
+
+    pub fn add_trait(&mut self, name: &str) {
+        self.traits.push(name.to_string());
+    }
+
+    /// The visibility set with `set_visibility`, or GM-only for checks with the secret trait and public otherwise.
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+            .unwrap_or_else(|| Visibility::for_traits(&self.traits))
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        self.visibility = Some(visibility);
+    }
+
+    /// Rolls the check. Fails only if a custom expression has `@variables`, roll those with `roll_with`.
+    pub fn roll<R: DiceRng + ?Sized>(&self, rng: &mut R) -> Result<CheckResult> {
+        self.roll_with(rng, |_| None)
+    }
+
+    pub fn roll_with<R, F>(&self, rng: &mut R, variable: F) -> Result<CheckResult>
+    where
+        R: DiceRng + ?Sized,
+        F: Fn(&str) -> Option<i32>,
+    {
+        Ok(self.resolve(self.expression.roll_with(rng, variable)?))
+    }
+
+    /// Determines the degree of success of an already rolled check.
+    pub fn resolve(&self, roll: Roll) -> CheckResult {
+        let natural = roll
+            .terms()
+            .iter()
+            .filter(|term| match term.term().kind() {
+                TermKind::Dice(dice) => dice.sides() == 20,
+                _ => false,
+            })
+            .flat_map(|term| term.dice().iter())
+            .find(|die| die.kept())
+            .map(|die| die.value());
+
+        let unadjusted = DegreeOfSuccess::from_total(roll.total(), self.dc);
+        let mut applied = Vec::new();
+        let degree = self.degree(roll.total(), natural, |adjustment| {
+            applied.push(adjustment.clone())
+        });
+
+        CheckResult {
+            roll,
+            natural,
+            dc: self.dc,
+            visibility: self.visibility(),
+            unadjusted,
+            degree,
+            applied,
+        }
+    }
+
+    /// Determines the degree of success of a total, calling `applied` for every adjustment that changed it.
+    pub(crate) fn degree<F>(
+        &self,
+        total: i32,
+        natural: Option<u32>,
+        mut applied: F,
+    ) -> DegreeOfSuccess
+    where
+        F: FnMut(&DegreeAdjustment),
+    {
+        let unadjusted = DegreeOfSuccess::from_total(total, self.dc);
+        let mut degree = match natural {
+            Some(20) => unadjusted.upgrade(),
+            Some(1) => unadjusted.downgrade(),
+            _ => unadjusted,
+        };
+        for adjustment in self.adjustments.iter() {
+            if adjustment.from == degree {
+                degree = adjustment.to;
+                applied(adjustment);
+            }
+        }
+        degree
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CheckResult {
+    roll: Roll,
+    natural: Option<u32>,
+    dc: i32,
+    visibility: Visibility,
+    unadjusted: DegreeOfSuccess,
+    degree: DegreeOfSuccess,
+    applied: Vec<DegreeAdjustment>,
+}
+
+impl CheckResult {
+    pub fn roll(&self) -> &Roll {
+        &self.roll
+    }
+
+    pub fn total(&self) -> i32 {
+        self.roll.total()
+    }
+
+    /// The face shown by the d20, if the check had one.
+    pub fn natural(&self) -> Option<u32> {
+        self.natural
+    }
+
+    pub fn dc(&self) -> i32 {
+        self.dc
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    /// The degree from comparing the total with the DC alone, before the natural roll and adjustments.
+    pub fn unadjusted_degree(&self) -> DegreeOfSuccess {
+        self.unadjusted
+    }
+
+    pub fn degree(&self) -> DegreeOfSuccess {
+        self.degree
+    }
+
+    /// The adjustments that changed the degree, in the order they were applied.
+    pub fn applied_adjustments(&self) -> &Vec<DegreeAdjustment> {
+        &self.applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Always rolls the same face on a d20.
+    struct Natural(u64);
+
+    impl DiceRng for Natural {
+        fn next_u64(&mut self) -> u64 {
+            self.0 - 1
+        }
+    }
+
+    #[test]
+    fn degree_from_total() {
+        assert_eq!(
+            DegreeOfSuccess::from_total(30, 20),
+            DegreeOfSuccess::CriticalSuccess
+        );
+        assert_eq!(
+            DegreeOfSuccess::from_total(20, 20),
+            DegreeOfSuccess::Success
+        );
+        assert_eq!(
+            DegreeOfSuccess::from_total(19, 20),
+            DegreeOfSuccess::Failure
+        );
+        assert_eq!(
+            DegreeOfSuccess::from_total(10, 20),
+            DegreeOfSuccess::CriticalFailure
+        );
+    }
+
+    #[test]
+    fn natural_rolls_shift_the_degree() {
+        let check = Check::new(0, 25);
+        let result = check.roll(&mut Natural(20)).unwrap();
+        assert_eq!(result.natural(), Some(20));
+        assert_eq!(result.unadjusted_degree(), DegreeOfSuccess::Failure);
+        assert_eq!(result.degree(), DegreeOfSuccess::Success);
+
+        let check = Check::new(30, 15);
+        let result = check.roll(&mut Natural(1)).unwrap();
+        assert_eq!(result.total(), 31);
+        assert_eq!(result.degree(), DegreeOfSuccess::Success);
+    }
+
+    #[test]
+    fn secret_checks_are_gm_only() {
+        let mut check = Check::new(5, 15);
+        check.add_trait("secret");
+        assert_eq!(check.visibility(), Visibility::GmOnly);
+
+        check.set_visibility(Visibility::SelfOnly);
+        let result = check.roll(&mut Natural(10)).unwrap();
+        assert_eq!(result.visibility(), Visibility::SelfOnly);
+    }
+
+    #[test]
+    fn adjustments_apply_after_natural_rolls() {
+        let mut check = Check::new(0, 20);
+        check.add_adjustment(DegreeAdjustment::new(
+            "Incredible Luck",
+            DegreeOfSuccess::CriticalFailure,
+            DegreeOfSuccess::Failure,
+        ));
+
+        let result = check.roll(&mut Natural(1)).unwrap();
+
+        assert_eq!(result.degree(), DegreeOfSuccess::Failure);
+        assert_eq!(result.applied_adjustments()[0].name(), "Incredible Luck");
+    }
+}
@@ -0,0 +1,397 @@
+use crate::dice::roll::MAX_EXPLOSIONS;
+use crate::dice::{Check, DegreeOfSuccess, Dice, DiceExpression, Keep, Term, TermKind};
+use crate::error::{Error, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+/// Exploding dice are followed until a chain becomes less likely than this, the rest of its probability is given to
+/// the last face.
+const CUTOFF: f64 = 1e-15;
+
+/// Roughly how many probabilities computing the distribution of a single pool may add up, so huge pools like
+/// `1000d10000` fail instead of hanging. `30d100` or `100d10kh5` are within it.
+const MAX_WORK: u128 = 5_000_000;
+
+/// The exact probability of every possible total of a roll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    probabilities: BTreeMap<i32, f64>,
+}
+
+impl Distribution {
+    pub fn constant(value: i32) -> Distribution {
+        Distribution {
+            probabilities: btreemap! { value => 1.0 },
+        }
+    }
+
+    /// The distribution of a pool of dice, including keep and explode modifiers. Fails for pools too large to
+    /// compute in reasonable time.
+    pub fn dice(dice: &Dice) -> Result<Distribution> {
+        let die = Distribution::chain(dice.sides(), dice.explode());
+        let count = dice.count() as u128;
+        let faces = die.probabilities.len() as u128;
+        let work = match dice.keep() {
+            // the i-th die is added to about i * faces totals
+            None => count * count * faces * faces / 2,
+            Some(Keep::Highest(kept)) | Some(Keep::Lowest(kept)) => {
+                count * faces * multisets(faces, kept.min(dice.count()) as u128)
+            }
+        };
+        if work > MAX_WORK {
+            return Err(Error::UnsupportedDistribution(format!(
+                "{} has too many possible results",
+                dice
+            )));
+        }
+        Ok(match dice.keep() {
+            None => (0..dice.count()).fold(Distribution::constant(0), |total, _| total.add(&die)),
+            Some(keep) => Distribution::keep(&die, dice.count(), keep),
+        })
+    }
+
+    pub fn probabilities(&self) -> &BTreeMap<i32, f64> {
+        &self.probabilities
+    }
+
+    pub fn probability(&self, value: i32) -> f64 {
+        self.probabilities.get(&value).copied().unwrap_or(0.0)
+    }
+
+    pub fn at_least(&self, value: i32) -> f64 {
+        self.probabilities.range(value..).map(|(_, p)| p).sum()
+    }
+
+    pub fn at_most(&self, value: i32) -> f64 {
+        self.probabilities.range(..=value).map(|(_, p)| p).sum()
+    }
+
+    pub fn min(&self) -> i32 {
+        *self.probabilities.keys().next().expect("never empty")
+    }
+
+    pub fn max(&self) -> i32 {
+        *self.probabilities.keys().next_back().expect("never empty")
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .map(|(&value, p)| value as f64 * p)
+            .sum()
+    }
+
+    /// The distribution of the sum of two independent rolls.
+    pub fn add(&self, other: &Distribution) -> Distribution {
+        let mut probabilities = BTreeMap::new();
+        for (a, p) in self.probabilities.iter() {
+            for (b, q) in other.probabilities.iter() {
+                *probabilities.entry(a + b).or_insert(0.0) += p * q;
+            }
+        }
+        Distribution { probabilities }
+    }
+
+    /// `add`, or `None` if a total doesn't fit into an `i32`.
+    fn checked_add(&self, other: &Distribution) -> Option<Distribution> {
+        self.min().checked_add(other.min())?;
+        self.max().checked_add(other.max())?;
+        Some(self.add(other))
+    }
+
+    pub fn negate(&self) -> Distribution {
+        Distribution {
+            probabilities: self
+                .probabilities
+                .iter()
+                .map(|(value, p)| (-value, *p))
+                .collect(),
+        }
+    }
+
+    /// A single die, together with the dice it causes if it explodes.
+    fn chain(sides: u32, explode: bool) -> Distribution {
+        let face = 1.0 / sides as f64;
+        let mut probabilities = BTreeMap::new();
+        let mut weight = 1.0;
+        for depth in 0..=MAX_EXPLOSIONS {
+            let offset = (depth as u32 * sides) as i32;
+            for value in 1..sides as i32 {
+                *probabilities.entry(offset + value).or_insert(0.0) += weight * face;
+            }
+            weight *= face;
+            if !explode || depth == MAX_EXPLOSIONS || weight < CUTOFF {
+                *probabilities.entry(offset + sides as i32).or_insert(0.0) += weight;
+                break;
+            }
+        }
+        Distribution { probabilities }
+    }
+
+    /// Rolls `count` dice and sums the best ones, tracking the dice kept so far.
+    fn keep(die: &Distribution, count: u32, keep: Keep) -> Distribution {
+        let (kept, highest) = match keep {
+            Keep::Highest(kept) => (kept as usize, true),
+            Keep::Lowest(kept) => (kept as usize, false),
+        };
+        let mut states: HashMap<Vec<i32>, f64> = hashmap! { vec![] => 1.0 };
+        for _ in 0..count {
+            let mut next = HashMap::new();
+            for (state, p) in states.iter() {
+                for (&value, q) in die.probabilities.iter() {
+                    let mut state = state.clone();
+                    state.push(value);
+                    state.sort_unstable();
+                    if highest {
+                        state.reverse();
+                    }
+                    state.truncate(kept);
+                    *next.entry(state).or_insert(0.0) += p * q;
+                }
+            }
+            states = next;
+        }
+
+        let mut probabilities = BTreeMap::new();
+        for (state, p) in states {
+            *probabilities.entry(state.iter().sum()).or_insert(0.0) += p;
+        }
+        Distribution { probabilities }
+    }
+}
+
+/// The number of ways to pick `k` of `n` values with repetition, i.e. the dice a keep modifier may have kept. Stops
+/// counting once it is above `MAX_WORK`.
+fn multisets(n: u128, k: u128) -> u128 {
+    let mut count = 1;
+    for i in 0..k {
+        // stays an integer, it's the number of ways to pick `i + 1` values
+        count = count * (n + i) / (i + 1);
+        if count > MAX_WORK {
+            break;
+        }
+    }
+    count
+}
+
+fn term_distribution<F>(term: &Term, variable: &F) -> Result<Option<Distribution>>
+where
+    F: Fn(&str) -> Option<i32>,
+{
+    let distribution = match term.kind() {
+        TermKind::Dice(dice) => Distribution::dice(dice)?,
+        TermKind::Constant(value) => match i32::try_from(*value) {
+            Ok(value) => Distribution::constant(value),
+            Err(_) => return Ok(None),
+        },
+        TermKind::Variable(name) => Distribution::constant(
+            variable(name).ok_or_else(|| Error::UnknownDiceVariable(name.clone()))?,
+        ),
+    };
+    if !term.negative() {
+        Ok(Some(distribution))
+    } else if distribution.min() == i32::MIN {
+        Ok(None)
+    } else {
+        Ok(Some(distribution.negate()))
+    }
+}
+
+/// Adds up the distributions of `terms`, failing if a total doesn't fit into an `i32`.
+fn sum_terms<'t, F>(
+    expression: &DiceExpression,
+    terms: impl Iterator<Item = &'t Term>,
+    variable: &F,
+) -> Result<Distribution>
+where
+    F: Fn(&str) -> Option<i32>,
+{
+    let mut total = Distribution::constant(0);
+    for term in terms {
+        total = term_distribution(term, variable)?
+            .and_then(|term| total.checked_add(&term))
+            .ok_or_else(|| Error::DiceOverflow(expression.to_string()))?;
+    }
+    Ok(total)
+}
+
+impl DiceExpression {
+    pub fn distribution(&self) -> Result<Distribution> {
+        self.distribution_with(|_| None)
+    }
+
+    pub fn distribution_with<F>(&self, variable: F) -> Result<Distribution>
+    where
+        F: Fn(&str) -> Option<i32>,
+    {
+        sum_terms(self, self.terms().iter(), &variable)
+    }
+}
+
+impl Check {
+    /// The exact chance of every degree of success, taking natural 20s and 1s, fortune and misfortune and the
+    /// check's adjustments into account.
+    pub fn degree_probabilities(&self) -> Result<BTreeMap<DegreeOfSuccess, f64>> {
+        self.degree_probabilities_with(|_| None)
+    }
+
+    pub fn degree_probabilities_with<F>(
+        &self,
+        variable: F,
+    ) -> Result<BTreeMap<DegreeOfSuccess, f64>>
+    where
+        F: Fn(&str) -> Option<i32>,
+    {
+        let terms = self.expression().terms();
+        let d20 = terms.iter().position(|term| match term.kind() {
+            TermKind::Dice(dice) => dice.sides() == 20,
+            _ => false,
+        });
+
+        // the natural roll is only known exactly if the d20 term keeps a single, non-exploding die
+        let natural = match d20 {
+            Some(index) => match terms[index].kind() {
+                TermKind::Dice(dice)
+                    if !terms[index].negative()
+                        && !dice.explode()
+                        && (dice.count() == 1
+                            || matches!(
+                                dice.keep(),
+                                Some(Keep::Highest(1)) | Some(Keep::Lowest(1))
+                            )) =>
+                {
+                    Distribution::dice(dice)?
+                }
+                _ => {
+                    return Err(Error::UnsupportedDistribution(format!(
+                        "{} doesn't roll a single natural d20",
+                        terms[index]
+                    )))
+                }
+            },
+            None => Distribution::constant(0),
+        };
+        let rest = sum_terms(
+            self.expression(),
+            terms
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| Some(*index) != d20)
+                .map(|(_, term)| term),
+            &variable,
+        )?;
+        if natural.checked_add(&rest).is_none() {
+            return Err(Error::DiceOverflow(self.expression().to_string()));
+        }
+
+        let mut degrees = btreemap! {
+            DegreeOfSuccess::CriticalFailure => 0.0,
+            DegreeOfSuccess::Failure => 0.0,
+            DegreeOfSuccess::Success => 0.0,
+            DegreeOfSuccess::CriticalSuccess => 0.0,
+        };
+        for (&roll, p) in natural.probabilities.iter() {
+            let face = d20.map(|_| roll as u32);
+            for (&modifier, q) in rest.probabilities.iter() {
+                let degree = self.degree(roll + modifier, face, |_| {});
+                *degrees.get_mut(&degree).expect("all degrees are present") += p * q;
+            }
+        }
+        Ok(degrees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::{DegreeAdjustment, FortuneEffect};
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn distribution(source: &str) -> Distribution {
+        DiceExpression::parse(source)
+            .unwrap()
+            .distribution()
+            .unwrap()
+    }
+
+    #[test]
+    fn distribution_of_sums() {
+        let distribution = distribution("2d6+1");
+
+        assert_eq!(distribution.min(), 3);
+        assert_eq!(distribution.max(), 13);
+        assert!(close(distribution.probability(8), 6.0 / 36.0));
+        assert!(close(distribution.mean(), 8.0));
+        assert!(close(distribution.at_least(3), 1.0));
+        assert!(matches!(
+            DiceExpression::parse("2147483647+1d4")
+                .unwrap()
+                .distribution(),
+            Err(Error::DiceOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn distribution_keep_and_explode() {
+        assert!(close(distribution("4d6kh3").mean(), 15869.0 / 1296.0));
+        assert!(close(distribution("2d20kh1").probability(20), 39.0 / 400.0));
+        assert!(close(distribution("2d20kl1").probability(20), 1.0 / 400.0));
+        assert!(close(distribution("1d6!").mean(), 4.2));
+    }
+
+    #[test]
+    fn distribution_of_huge_pools_is_unsupported() {
+        for source in ["1000d10000", "1000d100kh500", "200d6!"].iter() {
+            assert!(matches!(
+                DiceExpression::parse(source).unwrap().distribution(),
+                Err(Error::UnsupportedDistribution(_))
+            ));
+        }
+        assert!(DiceExpression::parse("10d100")
+            .unwrap()
+            .distribution()
+            .is_ok());
+        assert!(DiceExpression::parse("10d10kh5")
+            .unwrap()
+            .distribution()
+            .is_ok());
+    }
+
+    #[test]
+    fn degree_probabilities_with_natural_shifts() {
+        let degrees = Check::new(10, 20).degree_probabilities().unwrap();
+
+        assert!(close(
+            degrees[&DegreeOfSuccess::CriticalSuccess],
+            1.0 / 20.0
+        ));
+        assert!(close(degrees[&DegreeOfSuccess::Success], 10.0 / 20.0));
+        assert!(close(degrees[&DegreeOfSuccess::Failure], 8.0 / 20.0));
+        assert!(close(
+            degrees[&DegreeOfSuccess::CriticalFailure],
+            1.0 / 20.0
+        ));
+    }
+
+    #[test]
+    fn degree_probabilities_with_fortune_and_adjustments() {
+        let mut check = Check::with_fortune(10, 20, FortuneEffect::Fortune);
+        check.add_adjustment(DegreeAdjustment::new(
+            "Incredible Luck",
+            DegreeOfSuccess::CriticalFailure,
+            DegreeOfSuccess::Failure,
+        ));
+
+        let degrees = check.degree_probabilities().unwrap();
+
+        assert!(close(
+            degrees[&DegreeOfSuccess::CriticalSuccess],
+            39.0 / 400.0
+        ));
+        assert!(close(degrees[&DegreeOfSuccess::CriticalFailure], 0.0));
+        assert!(close(degrees.values().sum(), 1.0));
+    }
+}
@@ -0,0 +1,422 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// The most dice a single term may roll, to keep typos like `1000000d6` from hanging.
+const MAX_DICE: u32 = 1000;
+const MAX_SIDES: u32 = 10_000;
+
+/// Which dice of a pool count towards its total.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+/// A pool of identical dice such as `4d6kh3` or `3d6!`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Dice {
+    count: u32,
+    sides: u32,
+    keep: Option<Keep>,
+    explode: bool,
+}
+
+impl Dice {
+    pub fn new(count: u32, sides: u32) -> Dice {
+        Dice {
+            count,
+            sides,
+            keep: None,
+            explode: false,
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn sides(&self) -> u32 {
+        self.sides
+    }
+
+    pub fn keep(&self) -> Option<Keep> {
+        self.keep
+    }
+
+    /// Exploding dice roll again whenever they show their highest face.
+    pub fn explode(&self) -> bool {
+        self.explode
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TermKind {
+    Dice(Dice),
+    Constant(u32),
+    /// A named value like `@str` that is filled in when rolling.
+    Variable(String),
+}
+
+/// One summand of an expression, optionally tagged with a type like `[fire]`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Term {
+    negative: bool,
+    kind: TermKind,
+    tag: Option<String>,
+}
+
+impl Term {
+    pub fn negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn kind(&self) -> &TermKind {
+        &self.kind
+    }
+
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+}
+
+/// A parsed dice expression such as `2d8+1d6[fire]+@str`. Use `roll` or `roll_with` to evaluate it. Serialized in
+/// its canonical written form.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct DiceExpression {
+    terms: Vec<Term>,
+}
+
+impl DiceExpression {
+    pub fn parse(source: &str) -> Result<DiceExpression> {
+        Parser::new(source).expression()
+    }
+
+    pub fn terms(&self) -> &Vec<Term> {
+        &self.terms
+    }
+
+    /// Lists the names of all `@variables` in the order they appear.
+    pub fn variables(&self) -> Vec<&str> {
+        self.terms
+            .iter()
+            .filter_map(|term| match &term.kind {
+                TermKind::Variable(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for DiceExpression {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<DiceExpression> {
+        DiceExpression::parse(source)
+    }
+}
+
+impl From<DiceExpression> for String {
+    fn from(expression: DiceExpression) -> String {
+        expression.to_string()
+    }
+}
+
+impl TryFrom<String> for DiceExpression {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<DiceExpression> {
+        source.parse()
+    }
+}
+
+impl fmt::Display for Keep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Keep::Highest(count) => write!(f, "kh{}", count),
+            Keep::Lowest(count) => write!(f, "kl{}", count),
+        }
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        if let Some(keep) = self.keep {
+            write!(f, "{}", keep)?;
+        }
+        if self.explode {
+            write!(f, "!")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TermKind::Dice(dice) => write!(f, "{}", dice)?,
+            TermKind::Constant(value) => write!(f, "{}", value)?,
+            TermKind::Variable(name) => write!(f, "@{}", name)?,
+        }
+        if let Some(tag) = &self.tag {
+            write!(f, "[{}]", tag)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DiceExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, term) in self.terms.iter().enumerate() {
+            if term.negative {
+                write!(f, "-")?;
+            } else if i > 0 {
+                write!(f, "+")?;
+            }
+            write!(f, "{}", term)?;
+        }
+        Ok(())
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Parser<'a> {
+        Parser {
+            source,
+            position: 0,
+        }
+    }
+
+    fn error<T>(&self, position: usize, message: &str) -> Result<T> {
+        Err(Error::InvalidDiceExpression {
+            position,
+            message: message.to_string(),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek().map(|c| c.to_ascii_lowercase()) == Some(expected) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.advance();
+        }
+    }
+
+    fn expression(&mut self) -> Result<DiceExpression> {
+        let mut terms = Vec::new();
+        self.skip_whitespace();
+        let mut negative = self.eat('-');
+        loop {
+            self.skip_whitespace();
+            terms.push(self.term(negative)?);
+            self.skip_whitespace();
+            let position = self.position;
+            match self.advance() {
+                None => return Ok(DiceExpression { terms }),
+                Some('+') => negative = false,
+                Some('-') => negative = true,
+                Some(_) => return self.error(position, "expected '+' or '-'"),
+            }
+        }
+    }
+
+    fn term(&mut self, negative: bool) -> Result<Term> {
+        let start = self.position;
+        let kind = match self.peek() {
+            Some('@') => {
+                self.advance();
+                TermKind::Variable(self.identifier()?)
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let count = self.number()?;
+                if self.eat('d') {
+                    TermKind::Dice(self.dice(start, count)?)
+                } else {
+                    TermKind::Constant(count)
+                }
+            }
+            Some(c) if c.eq_ignore_ascii_case(&'d') => {
+                self.advance();
+                TermKind::Dice(self.dice(start, 1)?)
+            }
+            None => return self.error(start, "expected a term but the expression ended"),
+            Some(_) => return self.error(start, "expected a number, dice or @variable"),
+        };
+        self.skip_whitespace();
+        let tag = if self.eat('[') {
+            Some(self.tag()?)
+        } else {
+            None
+        };
+        Ok(Term {
+            negative,
+            kind,
+            tag,
+        })
+    }
+
+    fn number(&mut self) -> Result<u32> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+        }
+        if start == self.position {
+            return self.error(start, "expected a number");
+        }
+        // totals are `i32`, so no single number may be larger than that
+        match self.source[start..self.position].parse::<u32>() {
+            Ok(number) if i32::try_from(number).is_ok() => Ok(number),
+            _ => self.error(start, "number is too large"),
+        }
+    }
+
+    fn dice(&mut self, start: usize, count: u32) -> Result<Dice> {
+        if count == 0 || count > MAX_DICE {
+            return self.error(start, "dice count must be between 1 and 1000");
+        }
+        let sides_position = self.position;
+        let sides = if self.eat('%') { 100 } else { self.number()? };
+        if sides == 0 || sides > MAX_SIDES {
+            return self.error(sides_position, "dice must have between 1 and 10000 sides");
+        }
+        let mut dice = Dice::new(count, sides);
+        loop {
+            let position = self.position;
+            if self.eat('k') {
+                if dice.keep.is_some() {
+                    return self.error(position, "dice can only have one keep modifier");
+                }
+                let lowest = self.eat('l');
+                if !lowest {
+                    self.eat('h');
+                }
+                let keep_position = self.position;
+                let keep = self.number()?;
+                if keep == 0 || keep > count {
+                    return self.error(keep_position, "can't keep more dice than are rolled");
+                }
+                dice.keep = Some(if lowest {
+                    Keep::Lowest(keep)
+                } else {
+                    Keep::Highest(keep)
+                });
+            } else if self.eat('!') {
+                if dice.explode {
+                    return self.error(position, "dice can only explode once");
+                }
+                if sides == 1 {
+                    return self.error(position, "one-sided dice can't explode");
+                }
+                dice.explode = true;
+            } else {
+                return Ok(dice);
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            self.advance();
+        }
+        if start == self.position {
+            return self.error(start, "expected a variable name after '@'");
+        }
+        Ok(self.source[start..self.position].to_string())
+    }
+
+    fn tag(&mut self) -> Result<String> {
+        let start = self.position;
+        let end = match self.source[start..].find(']') {
+            Some(end) => start + end,
+            None => return self.error(start - 1, "unclosed '['"),
+        };
+        let tag = self.source[start..end].trim();
+        if tag.is_empty() {
+            return self.error(start - 1, "empty type tag");
+        }
+        self.position = end + 1;
+        Ok(tag.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(source: &str) -> usize {
+        match DiceExpression::parse(source) {
+            Err(Error::InvalidDiceExpression { position, .. }) => position,
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_supported_forms() {
+        for source in [
+            "2d6+4",
+            "1d20+@str",
+            "4d6kh3",
+            "2d8+1d6[fire]",
+            "3d6!",
+            "-1d4-2",
+            "1d20kl1",
+        ]
+        .iter()
+        {
+            let expression = DiceExpression::parse(source).unwrap();
+
+            assert_eq!(&expression.to_string(), source);
+        }
+    }
+
+    #[test]
+    fn parse_normalizes_whitespace_and_shorthands() {
+        let expression = DiceExpression::parse(" d% + 2D6k1 [ cold ] ").unwrap();
+
+        assert_eq!(expression.to_string(), "1d100+2d6kh1[cold]");
+        assert_eq!(expression.terms()[1].tag(), Some("cold"));
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_problem() {
+        assert_eq!(position("2d6+"), 4);
+        assert_eq!(position("2d6 x 4"), 4);
+        assert_eq!(position("2d"), 2);
+        assert_eq!(position("4d6kh5"), 5);
+        assert_eq!(position("1d6[fire"), 3);
+        assert_eq!(position("@+1"), 1);
+        assert_eq!(position("2d6é"), 3);
+        assert_eq!(position("4294967295"), 0);
+        assert_eq!(position("1+2147483648"), 2);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::dice::{
+    Check, CheckResult, DiceExpression, DieRoll, Roll, SeededRng, Viewer, Visibility,
+};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One entry of a `RollLog`. Together with the seed and stream position it holds everything needed to roll it again.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RollRecord {
+    roller: String,
+    expression: String,
+    modifiers: BTreeMap<String, i32>,
+    seed: u64,
+    position: u64,
+    total: i32,
+    dice: Vec<DieRoll>,
+    timestamp: u64,
+    #[serde(default)]
+    visibility: Visibility,
+}
+
+impl RollRecord {
+    pub fn roller(&self) -> &str {
+        &self.roller
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The values the roll used for its `@variables`.
+    pub fn modifiers(&self) -> &BTreeMap<String, i32> {
+        &self.modifiers
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// How many numbers the generator had produced before this roll.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn total(&self) -> i32 {
+        self.total
+    }
+
+    pub fn dice(&self) -> &Vec<DieRoll> {
+        &self.dice
+    }
+
+    /// Seconds since the Unix epoch.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn visible_to(&self, viewer: &Viewer) -> bool {
+        self.visibility.visible_to(&self.roller, viewer)
+    }
+
+    /// Rolls the recorded expression again with the same generator state.
+    pub fn replay(&self) -> Result<Roll> {
+        let expression = DiceExpression::parse(&self.expression)?;
+        let mut rng = SeededRng::at_position(self.seed, self.position);
+        expression.roll_with(&mut rng, |name| self.modifiers.get(name).copied())
+    }
+}
+
+/// Rolls dice with a seeded generator and records every roll, so a session can be audited and replayed.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RollLog {
+    rng: SeededRng,
+    records: Vec<RollRecord>,
+}
+
+impl RollLog {
+    pub fn new(seed: u64) -> RollLog {
+        RollLog {
+            rng: SeededRng::new(seed),
+            records: vec![],
+        }
+    }
+
+    pub fn from_time() -> RollLog {
+        RollLog {
+            rng: SeededRng::from_time(),
+            records: vec![],
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<RollLog> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("roll logs can always be serialized")
+    }
+
+    pub fn rng(&self) -> &SeededRng {
+        &self.rng
+    }
+
+    pub fn records(&self) -> &Vec<RollRecord> {
+        &self.records
+    }
+
+    pub fn roll(
+        &mut self,
+        roller: &str,
+        expression: &DiceExpression,
+        visibility: Visibility,
+    ) -> Result<Roll> {
+        self.roll_with(roller, expression, visibility, |_| None)
+    }
+
+    /// Rolls and records an expression. Fails without rolling if one of its `@variables` is unknown.
+    pub fn roll_with<F>(
+        &mut self,
+        roller: &str,
+        expression: &DiceExpression,
+        visibility: Visibility,
+        variable: F,
+    ) -> Result<Roll>
+    where
+        F: Fn(&str) -> Option<i32>,
+    {
+        let mut modifiers = BTreeMap::new();
+        for name in expression.variables() {
+            let value =
+                variable(name).ok_or_else(|| Error::UnknownDiceVariable(name.to_string()))?;
+            modifiers.insert(name.to_string(), value);
+        }
+
+        let position = self.rng.position();
+        let roll = expression.roll_with(&mut self.rng, |name| modifiers.get(name).copied())?;
+        self.records.push(RollRecord {
+            roller: roller.to_string(),
+            expression: roll.expression().to_string(),
+            modifiers,
+            seed: self.rng.seed(),
+            position,
+            total: roll.total(),
+            dice: roll.dice().copied().collect(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            visibility,
+        });
+        Ok(roll)
+    }
+
+    /// Rolls and records a check, using the check's visibility.
+    pub fn check(&mut self, roller: &str, check: &Check) -> Result<CheckResult> {
+        self.check_with(roller, check, |_| None)
+    }
+
+    pub fn check_with<F>(&mut self, roller: &str, check: &Check, variable: F) -> Result<CheckResult>
+    where
+        F: Fn(&str) -> Option<i32>,
+    {
+        let roll = self.roll_with(roller, check.expression(), check.visibility(), variable)?;
+        Ok(check.resolve(roll))
+    }
+
+    /// The records the viewer is allowed to see, oldest first.
+    pub fn visible_records(&self, viewer: &Viewer) -> Vec<&RollRecord> {
+        self.records
+            .iter()
+            .filter(|record| record.visible_to(viewer))
+            .collect()
+    }
+
+    /// Exports the records the viewer is allowed to see. Unlike `to_json`, this can't be used to continue rolling.
+    pub fn export_for(&self, viewer: &Viewer) -> String {
+        serde_json::to_string_pretty(&self.visible_records(viewer))
+            .expect("roll logs can always be serialized")
+    }
+
+    /// Rolls every recorded roll again, oldest first.
+    pub fn replay(&self) -> Result<Vec<Roll>> {
+        self.records.iter().map(RollRecord::replay).collect()
+    }
+
+    /// Replays the log and fails at the first roll whose dice or total differ from the record.
+    pub fn verify(&self) -> Result<()> {
+        for (index, record) in self.records.iter().enumerate() {
+            let roll = record.replay()?;
+            if roll.total() != record.total || !roll.dice().eq(record.dice.iter()) {
+                return Err(Error::ReplayMismatch { index });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> RollLog {
+        let mut log = RollLog::new(1234);
+        log.roll(
+            "GM",
+            &DiceExpression::parse("4d6kh3").unwrap(),
+            Visibility::GmOnly,
+        )
+        .unwrap();
+        log.roll_with(
+            "Bob",
+            &DiceExpression::parse("1d20+@str").unwrap(),
+            Visibility::SelfOnly,
+            |_| Some(3),
+        )
+        .unwrap();
+        let mut recall_knowledge = Check::new(5, 15);
+        recall_knowledge.add_trait("secret");
+        log.check("Bob", &recall_knowledge).unwrap();
+        log.check("Bob", &Check::new(5, 15)).unwrap();
+        log
+    }
+
+    #[test]
+    fn replay_reproduces_the_session() {
+        let log = session();
+
+        let replayed = log.replay().unwrap();
+
+        assert_eq!(replayed.len(), 4);
+        for (roll, record) in replayed.iter().zip(log.records().iter()) {
+            assert_eq!(roll.total(), record.total());
+        }
+        assert_eq!(log.records()[1].modifiers()["str"], 3);
+        assert!(log.verify().is_ok());
+    }
+
+    #[test]
+    fn json_roundtrip_continues_the_stream() {
+        let mut log = session();
+        let mut restored = RollLog::from_json(&log.to_json()).unwrap();
+        let expression = DiceExpression::parse("10d10").unwrap();
+
+        let next = log.roll("GM", &expression, Visibility::Public).unwrap();
+        let restored_next = restored
+            .roll("GM", &expression, Visibility::Public)
+            .unwrap();
+
+        assert_eq!(next.total(), restored_next.total());
+        assert!(restored.verify().is_ok());
+    }
+
+    #[test]
+    fn records_are_filtered_by_viewer() {
+        let log = session();
+
+        assert_eq!(log.visible_records(&Viewer::Gm).len(), 4);
+        assert_eq!(log.visible_records(&Viewer::player("Bob")).len(), 2);
+        assert_eq!(log.visible_records(&Viewer::player("Alice")).len(), 1);
+
+        let exported: Vec<RollRecord> =
+            serde_json::from_str(&log.export_for(&Viewer::player("Alice"))).unwrap();
+        assert_eq!(exported[0].visibility(), Visibility::Public);
+    }
+
+    #[test]
+    fn verify_detects_tampering() {
+        let json = session().to_json();
+        let tampered = json.replacen("\"total\": ", "\"total\": 100", 1);
+
+        let log = RollLog::from_json(&tampered).unwrap();
+
+        assert!(matches!(
+            log.verify(),
+            Err(Error::ReplayMismatch { index: 0 })
+        ));
+    }
+}
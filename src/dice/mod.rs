@@ -0,0 +1,15 @@
+mod check;
+mod distribution;
+mod expression;
+mod log;
+mod rng;
+mod roll;
+mod visibility;
+
+pub use check::{level_dc, Check, CheckResult, DegreeAdjustment, DegreeOfSuccess, FortuneEffect};
+pub use distribution::Distribution;
+pub use expression::{Dice, DiceExpression, Keep, Term, TermKind};
+pub use log::{RollLog, RollRecord};
+pub use rng::{DiceRng, SeededRng};
+pub use roll::{DieRoll, Roll, TermRoll};
+pub use visibility::{Viewer, Visibility};
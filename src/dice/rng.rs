@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of randomness for dice rolls. Implement this to inject your own generator, e.g. for tests.
+pub trait DiceRng {
+    fn next_u64(&mut self) -> u64;
+
+    /// Rolls a single die with the given number of sides, returning a value from 1 to `sides`.
+    fn roll_die(&mut self, sides: u32) -> u32 {
+        let sides = sides.max(1) as u64;
+        // reject the top end of the range so every side is equally likely
+        let zone = u64::MAX - (u64::MAX % sides);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % sides) as u32 + 1;
+            }
+        }
+    }
+}
+
+/// A small, fast and reproducible generator (SplitMix64). The same seed always produces the same rolls, and
+/// `position` tells how many numbers have been drawn so far.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SeededRng {
+    seed: u64,
+    state: u64,
+    position: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> SeededRng {
+        SeededRng {
+            seed,
+            state: seed,
+            position: 0,
+        }
+    }
+
+    /// Creates a generator with a seed taken from the current time.
+    pub fn from_time() -> SeededRng {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        SeededRng::new(nanos)
+    }
+
+    /// Creates a generator that continues where one with the same seed stood after `position` draws.
+    pub fn at_position(seed: u64, position: u64) -> SeededRng {
+        let mut rng = SeededRng::new(seed);
+        rng.state = seed.wrapping_add(position.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        rng.position = position;
+        rng
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl DiceRng for SeededRng {
+    fn next_u64(&mut self) -> u64 {
+        self.position += 1;
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_rolls() {
+        let mut a = SeededRng::new(42);
+        let mut b = SeededRng::new(42);
+
+        let a: Vec<_> = (0..20).map(|_| a.roll_die(20)).collect();
+        let b: Vec<_> = (0..20).map(|_| b.roll_die(20)).collect();
+
+        assert_eq!(a, b);
+        assert!(a.iter().all(|&value| (1..=20).contains(&value)));
+    }
+
+    #[test]
+    fn at_position_continues_the_stream() {
+        let mut rng = SeededRng::new(7);
+        rng.next_u64();
+        rng.next_u64();
+
+        let mut resumed = SeededRng::at_position(7, 2);
+
+        assert_eq!(resumed.next_u64(), rng.next_u64());
+        assert_eq!(resumed.position(), 3);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::dice::{DiceExpression, DiceRng, Keep, Term, TermKind};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// How often a single exploding die may roll again, so `1d2!` can't run forever on an unlucky generator.
+pub(crate) const MAX_EXPLOSIONS: usize = 100;
+
+/// The outcome of a single die.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DieRoll {
+    sides: u32,
+    value: u32,
+    kept: bool,
+    exploded: bool,
+}
+
+impl DieRoll {
+    pub fn sides(&self) -> u32 {
+        self.sides
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Whether the die counts towards the total. Only keep modifiers like `kh3` drop dice, they keep or drop an
+    /// exploding die together with the dice it caused.
+    pub fn kept(&self) -> bool {
+        self.kept
+    }
+
+    /// Whether the die showed its highest face and caused another die to be rolled.
+    pub fn exploded(&self) -> bool {
+        self.exploded
+    }
+}
+
+/// The outcome of one term of an expression.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TermRoll {
+    term: Term,
+    dice: Vec<DieRoll>,
+    value: i32,
+}
+
+impl TermRoll {
+    pub fn term(&self) -> &Term {
+        &self.term
+    }
+
+    /// The individual dice in the order they were rolled. Empty for constants and variables.
+    pub fn dice(&self) -> &Vec<DieRoll> {
+        &self.dice
+    }
+
+    /// The term's contribution to the total, negative for subtracted terms.
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Roll {
+    expression: String,
+    terms: Vec<TermRoll>,
+    total: i32,
+}
+
+impl Roll {
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    pub fn terms(&self) -> &Vec<TermRoll> {
+        &self.terms
+    }
+
+    pub fn total(&self) -> i32 {
+        self.total
+    }
+
+    /// All dice of the roll, across all terms.
+    pub fn dice(&self) -> impl Iterator<Item = &DieRoll> {
+        self.terms.iter().flat_map(|term| term.dice.iter())
+    }
+
+    /// Sums the terms by their type tag, untagged terms are collected under `None`.
+    pub fn totals_by_tag(&self) -> BTreeMap<Option<&str>, i32> {
+        let mut totals = BTreeMap::new();
+        for term in self.terms.iter() {
+            *totals.entry(term.term.tag()).or_insert(0) += term.value;
+        }
+        totals
+    }
+}
+
+impl DiceExpression {
+    /// Rolls an expression without `@variables`.
+    pub fn roll<R: DiceRng + ?Sized>(&self, rng: &mut R) -> Result<Roll> {
+        self.roll_with(rng, |_| None)
+    }
+
+    /// Rolls the expression, looking up `@variables` with `variable`.
+    pub fn roll_with<R, F>(&self, rng: &mut R, variable: F) -> Result<Roll>
+    where
+        R: DiceRng + ?Sized,
+        F: Fn(&str) -> Option<i32>,
+    {
+        let overflow = || Error::DiceOverflow(self.to_string());
+        let mut terms = Vec::new();
+        for term in self.terms().iter() {
+            let (dice, value) = match term.kind() {
+                TermKind::Dice(pool) => {
+                    let dice =
+                        roll_pool(rng, pool.count(), pool.sides(), pool.keep(), pool.explode());
+                    let value = dice
+                        .iter()
+                        .filter(|die| die.kept)
+                        .try_fold(0i32, |total, die| {
+                            total.checked_add(i32::try_from(die.value).ok()?)
+                        })
+                        .ok_or_else(overflow)?;
+                    (dice, value)
+                }
+                TermKind::Constant(value) => {
+                    (vec![], i32::try_from(*value).map_err(|_| overflow())?)
+                }
+                TermKind::Variable(name) => (
+                    vec![],
+                    variable(name).ok_or_else(|| Error::UnknownDiceVariable(name.clone()))?,
+                ),
+            };
+            let value = if term.negative() {
+                value.checked_neg().ok_or_else(overflow)?
+            } else {
+                value
+            };
+            terms.push(TermRoll {
+                term: term.clone(),
+                dice,
+                value,
+            });
+        }
+        Ok(Roll {
+            expression: self.to_string(),
+            total: terms
+                .iter()
+                .try_fold(0i32, |total, term| total.checked_add(term.value))
+                .ok_or_else(overflow)?,
+            terms,
+        })
+    }
+}
+
+fn roll_pool<R: DiceRng + ?Sized>(
+    rng: &mut R,
+    count: u32,
+    sides: u32,
+    keep: Option<Keep>,
+    explode: bool,
+) -> Vec<DieRoll> {
+    // an exploding die and the dice it caused are one chain, keep modifiers compare the chains' sums
+    let mut chains = Vec::new();
+    for _ in 0..count {
+        let mut chain = Vec::new();
+        loop {
+            let value = rng.roll_die(sides);
+            let exploded = explode && value == sides && chain.len() < MAX_EXPLOSIONS;
+            chain.push(DieRoll {
+                sides,
+                value,
+                kept: true,
+                exploded,
+            });
+            if !exploded {
+                break;
+            }
+        }
+        chains.push(chain);
+    }
+
+    if let Some(keep) = keep {
+        let sum = |chain: &Vec<DieRoll>| chain.iter().map(|die| die.value).sum::<u32>();
+        let mut order: Vec<usize> = (0..chains.len()).collect();
+        let kept = match keep {
+            Keep::Highest(kept) => {
+                order.sort_by(|&a, &b| sum(&chains[b]).cmp(&sum(&chains[a])));
+                kept
+            }
+            Keep::Lowest(kept) => {
+                order.sort_by(|&a, &b| sum(&chains[a]).cmp(&sum(&chains[b])));
+                kept
+            }
+        };
+        for &index in order.iter().skip(kept as usize) {
+            for die in chains[index].iter_mut() {
+                die.kept = false;
+            }
+        }
+    }
+    chains.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::SeededRng;
+
+    /// Returns the given die faces in order, assuming every die has the same number of sides.
+    struct FixedRng {
+        sides: u64,
+        values: Vec<u64>,
+    }
+
+    impl DiceRng for FixedRng {
+        fn next_u64(&mut self) -> u64 {
+            (self.values.remove(0) - 1) % self.sides
+        }
+    }
+
+    fn fixed(sides: u64, values: &[u64]) -> FixedRng {
+        FixedRng {
+            sides,
+            values: values.to_vec(),
+        }
+    }
+
+    #[test]
+    fn roll_reports_every_die() {
+        let expression = DiceExpression::parse("2d6+4").unwrap();
+
+        let roll = expression.roll(&mut fixed(6, &[3, 5])).unwrap();
+
+        assert_eq!(roll.total(), 12);
+        let values: Vec<_> = roll.dice().map(|die| die.value()).collect();
+        assert_eq!(values, vec![3, 5]);
+    }
+
+    #[test]
+    fn roll_keep_highest_drops_lowest() {
+        let expression = DiceExpression::parse("4d6kh3").unwrap();
+
+        let roll = expression.roll(&mut fixed(6, &[2, 6, 1, 4])).unwrap();
+
+        assert_eq!(roll.total(), 12);
+        let kept: Vec<_> = roll.dice().map(|die| die.kept()).collect();
+        assert_eq!(kept, vec![true, true, false, true]);
+    }
+
+    #[test]
+    fn roll_exploding_dice_roll_again() {
+        let expression = DiceExpression::parse("3d6!").unwrap();
+
+        let roll = expression.roll(&mut fixed(6, &[6, 6, 2, 3, 1])).unwrap();
+
+        assert_eq!(roll.total(), 18);
+        assert_eq!(roll.dice().count(), 5);
+        assert_eq!(roll.dice().filter(|die| die.exploded()).count(), 2);
+    }
+
+    #[test]
+    fn roll_variables_and_tags() {
+        let expression = DiceExpression::parse("1d20+@str").unwrap();
+        let roll = expression
+            .roll_with(&mut fixed(20, &[11]), |name| match name {
+                "str" => Some(4),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(roll.total(), 15);
+
+        let expression = DiceExpression::parse("2d8+1d6[fire]").unwrap();
+        let roll = expression.roll(&mut SeededRng::new(1)).unwrap();
+        let totals = roll.totals_by_tag();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[&None] + totals[&Some("fire")], roll.total());
+    }
+
+    #[test]
+    fn roll_overflow_is_an_error() {
+        let expression = DiceExpression::parse("2147483647+1").unwrap();
+        assert!(matches!(
+            expression.roll(&mut SeededRng::new(1)),
+            Err(Error::DiceOverflow(_))
+        ));
+
+        let expression = DiceExpression::parse("-@x").unwrap();
+        assert!(expression
+            .roll_with(&mut SeededRng::new(1), |_| Some(i32::MIN))
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn roll_unknown_variable_fails() {
+        let expression = DiceExpression::parse("1d20+@dex").unwrap();
+
+        expression.roll(&mut SeededRng::new(1)).unwrap();
+    }
+}
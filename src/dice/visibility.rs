@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Who gets to see a roll. The GM can always see every roll, so logs stay auditable.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Visibility {
+    #[default]
+    Public,
+    /// Only the GM sees the result, e.g. for secret checks like Recall Knowledge.
+    GmOnly,
+    /// Only the roller and the GM see the result.
+    SelfOnly,
+}
+
+impl Visibility {
+    /// Rolls with the secret trait are GM-only, all others are public.
+    pub fn for_traits<S: AsRef<str>>(traits: &[S]) -> Visibility {
+        if traits
+            .iter()
+            .any(|name| name.as_ref().eq_ignore_ascii_case("secret"))
+        {
+            Visibility::GmOnly
+        } else {
+            Visibility::Public
+        }
+    }
+
+    pub fn visible_to(self, roller: &str, viewer: &Viewer) -> bool {
+        match (self, viewer) {
+            (Visibility::Public, _) | (_, Viewer::Gm) => true,
+            (Visibility::GmOnly, Viewer::Player(_)) => false,
+            (Visibility::SelfOnly, Viewer::Player(name)) => name == roller,
+        }
+    }
+}
+
+/// Someone looking at rolls.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Viewer {
+    Gm,
+    /// A player, identified by the same name they roll under.
+    Player(String),
+}
+
+impl Viewer {
+    pub fn player(name: &str) -> Viewer {
+        Viewer::Player(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_to_viewers() {
+        let bob = Viewer::player("Bob");
+        let alice = Viewer::player("Alice");
+
+        assert!(Visibility::Public.visible_to("Bob", &alice));
+        assert!(Visibility::GmOnly.visible_to("Bob", &Viewer::Gm));
+        assert!(!Visibility::GmOnly.visible_to("Bob", &bob));
+        assert!(Visibility::SelfOnly.visible_to("Bob", &bob));
+        assert!(!Visibility::SelfOnly.visible_to("Bob", &alice));
+        assert_eq!(Visibility::for_traits(&["Secret"]), Visibility::GmOnly);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::character::{Proficiency, StatisticCheck};
+use crate::dice::DegreeOfSuccess;
+use crate::item::{Item, Price};
+use serde::{Deserialize, Serialize};
+
+/// Days of work every Crafting project takes before it can be finished.
+pub const SETUP_DAYS: u32 = 2;
+
+/// The Crafting proficiency needed for items of the given level.
+pub fn crafting_requirement(level: u32) -> Proficiency {
+    match level {
+        0..=8 => Proficiency::Trained,
+        9..=15 => Proficiency::Master,
+        _ => Proficiency::Legendary,
+    }
+}
+
+/// The formulas a character knows. Each formula is the item it produces.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FormulaBook {
+    formulas: Vec<Item>,
+}
+
+impl FormulaBook {
+    pub fn new() -> FormulaBook {
+        FormulaBook::default()
+    }
+
+    pub fn formulas(&self) -> &Vec<Item> {
+        &self.formulas
+    }
+
+    /// Adds a formula, replacing any formula for an item with the same name.
+    pub fn add(&mut self, item: Item) {
+        self.formulas
+            .retain(|formula| !formula.name().eq_ignore_ascii_case(item.name()));
+        self.formulas.push(item);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Item> {
+        self.formulas
+            .iter()
+            .find(|formula| formula.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// An item being crafted. The raw materials, half the price, are paid when starting. After the setup days the rest
+/// can be paid to finish the item, or more days can be spent to lower it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CraftingProject {
+    item: Item,
+    quantity: u32,
+    /// The check isn't saved, only its degree of success.
+    #[serde(skip)]
+    check: Option<StatisticCheck>,
+    degree: DegreeOfSuccess,
+    days: u32,
+    materials: Price,
+    reduction: Price,
+}
+
+impl CraftingProject {
+    pub(crate) fn new(
+        item: Item,
+        quantity: u32,
+        check: StatisticCheck,
+        materials: Price,
+    ) -> CraftingProject {
+        CraftingProject {
+            item,
+            quantity,
+            degree: check.result().degree(),
+            check: Some(check),
+            days: SETUP_DAYS,
+            materials,
+            reduction: Price::free(),
+        }
+    }
+
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    /// The Crafting check, unless the project was loaded from a save.
+    pub fn check(&self) -> Option<&StatisticCheck> {
+        self.check.as_ref()
+    }
+
+    pub fn degree(&self) -> DegreeOfSuccess {
+        self.degree
+    }
+
+    /// Failed projects can't be finished, their materials were salvaged when the check was rolled.
+    pub fn is_failed(&self) -> bool {
+        !self.degree().is_success()
+    }
+
+    /// Days spent so far, including the setup days.
+    pub fn days(&self) -> u32 {
+        self.days
+    }
+
+    /// What was paid for raw materials. For failed projects, what was lost after salvaging.
+    pub fn materials(&self) -> Price {
+        self.materials
+    }
+
+    pub fn price(&self) -> Price {
+        self.item.price() * self.quantity
+    }
+
+    /// What is left to pay to finish the item. Extra days can bring it down to nothing, but never below the raw
+    /// materials already paid.
+    pub fn remaining_cost(&self) -> Price {
+        self.price()
+            .checked_sub(self.materials + self.reduction)
+            .unwrap_or_else(Price::free)
+    }
+
+    pub(crate) fn add_days(&mut self, days: u32, per_day: Price) {
+        self.days += days;
+        self.reduction = self.reduction + per_day * days;
+    }
+
+    pub(crate) fn into_item(self) -> (Item, u32) {
+        (self.item, self.quantity)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::character::{Proficiency, StatisticCheck};
+use crate::dice::DegreeOfSuccess;
+use crate::item::Price;
+
+/// Income per day in copper for task levels 0 to 20: on a failure, then on a success when trained, expert, master
+/// and legendary.
+const INCOME: [[u64; 5]; 21] = [
+    [1, 5, 5, 5, 5],
+    [2, 20, 20, 20, 20],
+    [4, 30, 30, 30, 30],
+    [8, 50, 50, 50, 50],
+    [10, 70, 80, 80, 80],
+    [20, 90, 100, 100, 100],
+    [30, 150, 200, 200, 200],
+    [40, 200, 250, 250, 250],
+    [50, 250, 300, 300, 300],
+    [60, 300, 400, 400, 400],
+    [70, 400, 500, 600, 600],
+    [80, 500, 600, 800, 800],
+    [90, 600, 800, 1000, 1000],
+    [100, 700, 1000, 1500, 1500],
+    [150, 800, 1500, 2000, 2000],
+    [200, 1000, 2000, 2800, 2800],
+    [250, 1300, 2500, 3600, 4000],
+    [300, 1500, 3000, 4500, 5500],
+    [400, 2000, 4500, 7000, 9000],
+    [600, 3000, 6000, 10000, 13000],
+    [800, 4000, 7500, 15000, 20000],
+];
+
+/// What a critical success at task level 20 earns, when trained, expert, master and legendary.
+const INCOME_20_CRITICAL: [u64; 4] = [5000, 9000, 17500, 30000];
+
+/// Coins earned per day of a task of the given level. A critical success pays as if the task were a level higher, a
+/// failure pays the failure column and a critical failure or an untrained character earns nothing.
+pub fn income_per_day(level: u32, proficiency: Proficiency, degree: DegreeOfSuccess) -> Price {
+    let level = level.min(20) as usize;
+    let rank = match proficiency {
+        Proficiency::Untrained => return Price::free(),
+        Proficiency::Trained => 1,
+        Proficiency::Expert => 2,
+        Proficiency::Master => 3,
+        Proficiency::Legendary => 4,
+    };
+    Price::cp(match degree {
+        DegreeOfSuccess::CriticalSuccess if level == 20 => INCOME_20_CRITICAL[rank - 1],
+        DegreeOfSuccess::CriticalSuccess => INCOME[level + 1][rank],
+        DegreeOfSuccess::Success => INCOME[level][rank],
+        DegreeOfSuccess::Failure => INCOME[level][0],
+        DegreeOfSuccess::CriticalFailure => 0,
+    })
+}
+
+/// The result of Earning Income over a number of days.
+#[derive(Debug, Clone)]
+pub struct EarnIncome {
+    check: StatisticCheck,
+    task_level: u32,
+    days: u32,
+    per_day: Price,
+}
+
+impl EarnIncome {
+    pub(crate) fn new(
+        check: StatisticCheck,
+        task_level: u32,
+        days: u32,
+        per_day: Price,
+    ) -> EarnIncome {
+        EarnIncome {
+            check,
+            task_level,
+            days,
+            per_day,
+        }
+    }
+
+    pub fn check(&self) -> &StatisticCheck {
+        &self.check
+    }
+
+    pub fn task_level(&self) -> u32 {
+        self.task_level
+    }
+
+    pub fn days(&self) -> u32 {
+        self.days
+    }
+
+    pub fn per_day(&self) -> Price {
+        self.per_day
+    }
+
+    pub fn total(&self) -> Price {
+        self.per_day * self.days
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn income_by_level_and_rank() {
+        assert_eq!(
+            income_per_day(3, Proficiency::Trained, DegreeOfSuccess::Success),
+            Price::sp(5)
+        );
+        assert_eq!(
+            income_per_day(3, Proficiency::Expert, DegreeOfSuccess::CriticalSuccess),
+            Price::sp(8)
+        );
+        assert_eq!(
+            income_per_day(3, Proficiency::Master, DegreeOfSuccess::Failure),
+            Price::cp(8)
+        );
+        assert_eq!(
+            income_per_day(20, Proficiency::Legendary, DegreeOfSuccess::CriticalSuccess),
+            Price::gp(300)
+        );
+        assert!(income_per_day(5, Proficiency::Untrained, DegreeOfSuccess::Success).is_free());
+    }
+}
@@ -0,0 +1,5 @@
+mod crafting;
+mod income;
+
+pub use crafting::{crafting_requirement, CraftingProject, FormulaBook, SETUP_DAYS};
+pub use income::{income_per_day, EarnIncome};
@@ -0,0 +1,501 @@
+use crate::character::{AbilityScoreType, BoostSource, LegalityIssue, Proficiency, Skill};
+use crate::content::ContentKind;
+use crate::item::{Bulk, ItemId, Price};
+use crate::spell::Tradition;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// A restricted ability boost was given a choice outside of its options. `index` is the position of the boost
+    /// within its set, a single `choose` call counts as position 0. `boost_source` is the set, if known.
+    InvalidBoostChoice {
+        boost_source: Option<BoostSource>,
+        index: usize,
+        choice: AbilityScoreType,
+        options: Vec<AbilityScoreType>,
+    },
+    /// The same ability score was boosted twice by one set of boosts.
+    DuplicateBoost {
+        boost_source: Option<BoostSource>,
+        index: usize,
+        choice: AbilityScoreType,
+    },
+    TooFewBoostChoices {
+        boost_source: Option<BoostSource>,
+        expected: usize,
+        provided: usize,
+    },
+    TooManyBoostChoices {
+        boost_source: Option<BoostSource>,
+        expected: usize,
+        provided: usize,
+    },
+    /// A restricted skill training was given a choice outside of its options, see `InvalidBoostChoice`.
+    InvalidSkillChoice {
+        index: usize,
+        choice: Skill,
+        options: Vec<Skill>,
+    },
+    DuplicateSkill {
+        index: usize,
+        choice: Skill,
+    },
+    TooFewSkillChoices {
+        expected: usize,
+        provided: usize,
+    },
+    TooManySkillChoices {
+        expected: usize,
+        provided: usize,
+    },
+    UnknownContent {
+        kind: ContentKind,
+        name: String,
+    },
+    UnknownAbilityScore(String),
+    UnknownSkill(String),
+    UnknownStatistic(String),
+    /// A file or record doesn't have the expected structure.
+    InvalidFormat(String),
+    UnsupportedVersion {
+        version: u32,
+        newest: u32,
+    },
+    /// There is no stored record of the given kind with this id.
+    NotFound {
+        kind: &'static str,
+        id: i64,
+    },
+    /// Items can only be stowed in containers.
+    NotAContainer {
+        id: ItemId,
+    },
+    ContainerFull {
+        id: ItemId,
+        capacity: Bulk,
+    },
+    /// Items are added at least once.
+    ZeroQuantity(String),
+    /// A container can't be stowed inside itself or one of its own contents.
+    ItemInsideItself {
+        id: ItemId,
+    },
+    /// Selling more of an item than is carried.
+    NotEnoughItems {
+        id: ItemId,
+        available: u32,
+    },
+    /// Containers have to be emptied before they're sold.
+    ContainerNotEmpty {
+        id: ItemId,
+    },
+    InsufficientFunds {
+        price: Price,
+        available: Price,
+    },
+    /// Only items with the invested trait can be invested.
+    NotInvestable {
+        id: ItemId,
+    },
+    InvestmentLimit {
+        limit: usize,
+    },
+    /// The item has to be worn or held, and invested if it needs to be, before it can be activated.
+    ItemNotActive {
+        id: ItemId,
+    },
+    UnknownActivation(String),
+    /// An activation was already used as often as its frequency allows.
+    ActivationExhausted(String),
+    NotConsumable {
+        id: ItemId,
+    },
+    NotAWand {
+        id: ItemId,
+    },
+    /// A broken wand has to be repaired before it can cast again.
+    WandBroken {
+        id: ItemId,
+    },
+    /// A wand was used up for the day and wasn't overcharged.
+    NoCharges {
+        id: ItemId,
+    },
+    /// Crafting an item needs its formula.
+    MissingFormula(String),
+    ItemLevelTooHigh {
+        level: u32,
+        max: u32,
+    },
+    InsufficientProficiency {
+        required: Proficiency,
+        actual: Proficiency,
+    },
+    /// A Crafting project whose check failed can't be finished.
+    CraftingFailed(String),
+    UnknownCraftingProject(usize),
+    /// Earning Income is limited to tasks up to the character's level.
+    TaskLevelTooHigh {
+        level: u32,
+        max: u32,
+    },
+    /// The skill potency bonuses don't fit the character's level.
+    SkillPotencyLimit {
+        level: u32,
+    },
+    UnknownSpellcasting(String),
+    /// The spell isn't in the spellbook or repertoire.
+    UnknownSpell(String),
+    SpellNotInTradition {
+        spell: String,
+        tradition: Tradition,
+    },
+    InvalidSpellRank {
+        spell: String,
+        rank: u32,
+    },
+    /// Only prepared casters prepare spells.
+    NotPreparedCaster(String),
+    /// Only repertoires hold heightened spells, spellbooks are heightened when preparing.
+    NotSpontaneousCaster(String),
+    SpellNotPrepared {
+        spell: String,
+        rank: u32,
+    },
+    NoSpellSlots {
+        rank: u32,
+    },
+    /// The character isn't allowed to have an item without the GM's permission.
+    Illegal(LegalityIssue),
+    /// Treasure tables cover parties of level 1 to 20 with at least one character.
+    InvalidParty {
+        level: u32,
+        size: u32,
+    },
+    /// A rune that doesn't exist or doesn't fit on the item.
+    InvalidRune(String),
+    UnclosedPlaceholder {
+        position: usize,
+    },
+    EmptyPlaceholder {
+        position: usize,
+    },
+    UnknownPlaceholder(String),
+    /// A dice expression couldn't be parsed. `position` is the byte offset of the offending character.
+    InvalidDiceExpression {
+        position: usize,
+        message: String,
+    },
+    /// A dice expression references an `@variable` that has no value.
+    UnknownDiceVariable(String),
+    /// The total of a dice expression doesn't fit into an `i32`.
+    DiceOverflow(String),
+    /// The exact distribution of a roll can't be computed, e.g. because a check has no single natural d20.
+    UnsupportedDistribution(String),
+    /// Replaying a logged roll gave a different result than the one recorded.
+    ReplayMismatch {
+        index: usize,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Json(serde_json::Error),
+    Database(rusqlite::Error),
+}
+
+/// Names the set of boosts in a message, if known.
+fn boost_set(boost_source: &Option<BoostSource>) -> String {
+    match boost_source {
+        Some(boost_source) => format!(" in the {} boosts", boost_source),
+        None => "".to_string(),
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidBoostChoice {
+                boost_source,
+                index,
+                choice,
+                options,
+            } => write!(
+                f,
+                "Boost {} to {:?} is not a valid choice{}! Options: {:?}",
+                index,
+                choice,
+                boost_set(boost_source),
+                options
+            ),
+            Error::DuplicateBoost {
+                boost_source,
+                index,
+                choice,
+            } => write!(
+                f,
+                "Duplicate boost choice {}{}! {:?} was already chosen!",
+                index,
+                boost_set(boost_source),
+                choice
+            ),
+            Error::TooFewBoostChoices {
+                boost_source,
+                expected,
+                provided,
+            } => write!(
+                f,
+                "Too few boost choices{}! Expected {} but got {}.",
+                boost_set(boost_source),
+                expected,
+                provided
+            ),
+            Error::TooManyBoostChoices {
+                boost_source,
+                expected,
+                provided,
+            } => write!(
+                f,
+                "Too many boost choices{}! Expected {} but got {}.",
+                boost_set(boost_source),
+                expected,
+                provided
+            ),
+            Error::InvalidSkillChoice {
+                index,
+                choice,
+                options,
+            } => write!(
+                f,
+                "Training {} in {:?} is not a valid choice! Options: {:?}",
+                index, choice, options
+            ),
+            Error::DuplicateSkill { index, choice } => write!(
+                f,
+                "Duplicate skill choice {}! {:?} was already chosen!",
+                index, choice
+            ),
+            Error::TooFewSkillChoices { expected, provided } => write!(
+                f,
+                "Too few skill choices! Expected {} but got {}.",
+                expected, provided
+            ),
+            Error::TooManySkillChoices { expected, provided } => write!(
+                f,
+                "Too many skill choices! Expected {} but got {}.",
+                expected, provided
+            ),
+            Error::UnknownContent { kind, name } => write!(f, "Unknown {:?} {:?}!", kind, name),
+            Error::UnknownAbilityScore(name) => write!(f, "Unknown ability score {:?}!", name),
+            Error::UnknownSkill(name) => write!(f, "Unknown skill {:?}!", name),
+            Error::UnknownStatistic(name) => write!(f, "Unknown statistic {:?}!", name),
+            Error::InvalidFormat(message) => write!(f, "Invalid format: {}", message),
+            Error::UnsupportedVersion { version, newest } => write!(
+                f,
+                "Unsupported version {}! Versions up to {} are supported.",
+                version, newest
+            ),
+            Error::NotFound { kind, id } => write!(f, "There is no {} with id {}!", kind, id),
+            Error::NotAContainer { id } => write!(f, "Item {} is not a container!", id),
+            Error::ContainerFull { id, capacity } => {
+                write!(f, "Container {} can only hold {} Bulk!", id, capacity)
+            }
+            Error::ZeroQuantity(name) => write!(f, "Can't add 0 of {}!", name),
+            Error::ItemInsideItself { id } => {
+                write!(f, "Item {} can't be stowed inside itself!", id)
+            }
+            Error::NotEnoughItems { id, available } => {
+                write!(f, "There are only {} of item {}!", available, id)
+            }
+            Error::ContainerNotEmpty { id } => write!(f, "Container {} isn't empty!", id),
+            Error::InsufficientFunds { price, available } => {
+                write!(f, "Can't pay {} with only {}!", price, available)
+            }
+            Error::NotInvestable { id } => write!(f, "Item {} can't be invested!", id),
+            Error::InvestmentLimit { limit } => {
+                write!(f, "Can't invest more than {} items!", limit)
+            }
+            Error::ItemNotActive { id } => write!(f, "Item {} isn't worn, held or invested!", id),
+            Error::UnknownActivation(name) => write!(f, "Unknown activation {:?}!", name),
+            Error::ActivationExhausted(name) => {
+                write!(f, "{} can't be used again until tomorrow!", name)
+            }
+            Error::NotConsumable { id } => write!(f, "Item {} isn't a consumable!", id),
+            Error::NotAWand { id } => write!(f, "Item {} isn't a wand!", id),
+            Error::WandBroken { id } => write!(f, "Wand {} is broken!", id),
+            Error::NoCharges { id } => write!(f, "Wand {} has no charges left today!", id),
+            Error::MissingFormula(name) => write!(f, "There is no formula for {}!", name),
+            Error::ItemLevelTooHigh { level, max } => write!(
+                f,
+                "Items of level {} are above the maximum level of {}!",
+                level, max
+            ),
+            Error::InsufficientProficiency { required, actual } => write!(
+                f,
+                "Requires {:?} proficiency, but only {:?}!",
+                required, actual
+            ),
+            Error::CraftingFailed(name) => write!(f, "Crafting {} failed!", name),
+            Error::UnknownCraftingProject(index) => {
+                write!(f, "There is no Crafting project {}!", index)
+            }
+            Error::TaskLevelTooHigh { level, max } => write!(
+                f,
+                "Tasks of level {} are above the maximum level of {}!",
+                level, max
+            ),
+            Error::SkillPotencyLimit { level } => write!(
+                f,
+                "Too many or too high skill potency bonuses for level {}!",
+                level
+            ),
+            Error::UnknownSpellcasting(name) => {
+                write!(f, "There is no spellcasting entry named {}!", name)
+            }
+            Error::UnknownSpell(name) => write!(f, "{} is not a known spell!", name),
+            Error::SpellNotInTradition { spell, tradition } => {
+                write!(f, "{} is not a {:?} spell!", spell, tradition)
+            }
+            Error::InvalidSpellRank { spell, rank } => {
+                write!(f, "{} can't be cast at rank {}!", spell, rank)
+            }
+            Error::NotPreparedCaster(name) => write!(f, "{} doesn't prepare spells!", name),
+            Error::NotSpontaneousCaster(name) => write!(f, "{} has no repertoire!", name),
+            Error::SpellNotPrepared { spell, rank } => {
+                write!(f, "{} is not prepared at rank {}!", spell, rank)
+            }
+            Error::NoSpellSlots { rank } => write!(f, "There are no rank {} slots left!", rank),
+            Error::Illegal(issue) => write!(f, "{}!", issue),
+            Error::InvalidParty { level, size } => write!(
+                f,
+                "There is no treasure for a party of {} at level {}!",
+                size, level
+            ),
+            Error::InvalidRune(message) => write!(f, "Invalid rune: {}", message),
+            Error::UnclosedPlaceholder { position } => {
+                write!(f, "Unclosed placeholder at position {}!", position)
+            }
+            Error::EmptyPlaceholder { position } => {
+                write!(f, "Empty placeholder at position {}!", position)
+            }
+            Error::UnknownPlaceholder(name) => write!(f, "Unknown placeholder {:?}!", name),
+            Error::InvalidDiceExpression { position, message } => write!(
+                f,
+                "Invalid dice expression at position {}: {}",
+                position, message
+            ),
+            Error::UnknownDiceVariable(name) => write!(f, "Unknown dice variable @{}!", name),
+            Error::DiceOverflow(expression) => {
+                write!(f, "The total of {} is too large!", expression)
+            }
+            Error::ReplayMismatch { index } => {
+                write!(f, "Roll {} of the log doesn't match its replay!", index)
+            }
+            Error::UnsupportedDistribution(message) => {
+                write!(f, "Can't compute the distribution: {}", message)
+            }
+            Error::Io { path, source } => write!(f, "Could not access {:?}: {}", path, source),
+            Error::Json(error) => write!(f, "Invalid JSON: {}", error),
+            Error::Database(error) => write!(f, "Database error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Json(error) => Some(error),
+            Error::Database(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Json(error)
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Error::Database(error)
+    }
+}
+
+impl Error {
+    /// Moves an invalid choice error to the given position in its set of choices.
+    pub(crate) fn at_index(self, index: usize) -> Error {
+        match self {
+            Error::InvalidBoostChoice {
+                boost_source,
+                choice,
+                options,
+                ..
+            } => Error::InvalidBoostChoice {
+                boost_source,
+                index,
+                choice,
+                options,
+            },
+            Error::InvalidSkillChoice {
+                choice, options, ..
+            } => Error::InvalidSkillChoice {
+                index,
+                choice,
+                options,
+            },
+            error => error,
+        }
+    }
+
+    /// Attributes a boost choice error to the set of boosts it came from.
+    pub(crate) fn with_boost_source(self, boost_source: BoostSource) -> Error {
+        let boost_source = Some(boost_source);
+        match self {
+            Error::InvalidBoostChoice {
+                index,
+                choice,
+                options,
+                ..
+            } => Error::InvalidBoostChoice {
+                boost_source,
+                index,
+                choice,
+                options,
+            },
+            Error::DuplicateBoost { index, choice, .. } => Error::DuplicateBoost {
+                boost_source,
+                index,
+                choice,
+            },
+            Error::TooFewBoostChoices {
+                expected, provided, ..
+            } => Error::TooFewBoostChoices {
+                boost_source,
+                expected,
+                provided,
+            },
+            Error::TooManyBoostChoices {
+                expected, provided, ..
+            } => Error::TooManyBoostChoices {
+                boost_source,
+                expected,
+                provided,
+            },
+            error => error,
+        }
+    }
+
+    pub(crate) fn io(path: &std::path::Path, source: io::Error) -> Error {
+        Error::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
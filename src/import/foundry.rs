@@ -0,0 +1,814 @@
+use crate::character::{
+    AbilityBoostChoice, AbilityScoreType, Ancestry, Background, Class, Feat, FeatType, Size, Skill,
+    SkillTrainingChoice,
+};
+use crate::combat::DamageType;
+use crate::content::{ContentLibrary, Rarity};
+use crate::dice::DiceExpression;
+use crate::error::{Error, Result};
+use crate::item::{
+    Armor, ArmorCategory, Bulk, Coin, Consumable, ConsumableEffect, Item, Price, Weapon,
+    WeaponCategory, WeaponGroup,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Summary of a Foundry import. Documents that could not be converted are listed in `warnings`.
+#[derive(Debug, Default)]
+pub struct FoundryImport {
+    imported: usize,
+    skipped: usize,
+    warnings: Vec<String>,
+}
+
+impl FoundryImport {
+    /// The number of documents that were added to the library.
+    pub fn imported(&self) -> usize {
+        self.imported
+    }
+
+    /// The number of documents with a type the library doesn't hold (e.g. spells or actions), or items whose weapon,
+    /// armor or consumable data couldn't be mapped.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
+}
+
+/// Imports every compendium document below `path` into the library.
+///
+/// Both the current layout with one `.json` file per document and the older `.db` packs with one document per line
+/// are supported. Feats are imported before backgrounds so that backgrounds can refer to their skill feat.
+pub fn import_foundry_directory<P: AsRef<Path>>(
+    path: P,
+    library: &mut ContentLibrary,
+) -> Result<FoundryImport> {
+    let mut result = FoundryImport::default();
+    let mut documents = Vec::new();
+    collect_documents(path.as_ref(), &mut documents, &mut result.warnings)?;
+
+    documents.sort_by_key(|(_, document)| document_type(document) != "feat");
+    for (source, document) in documents {
+        import_document(&source, &document, library, &mut result);
+    }
+    Ok(result)
+}
+
+/// Imports a single compendium document.
+pub fn import_foundry_json(json: &str, library: &mut ContentLibrary) -> Result<FoundryImport> {
+    let document: Value = serde_json::from_str(json)?;
+    let mut result = FoundryImport::default();
+    import_document("<json>", &document, library, &mut result);
+    Ok(result)
+}
+
+fn collect_documents(
+    path: &Path,
+    documents: &mut Vec<(String, Value)>,
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    let entries = fs::read_dir(path).map_err(|e| Error::io(path, e))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|e| e.path())
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let source = path.display().to_string();
+        if path.is_dir() {
+            collect_documents(&path, documents, warnings)?;
+            continue;
+        }
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if extension != "json" && extension != "db" {
+            continue;
+        }
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                warnings.push(format!("Could not read {}: {}", source, e));
+                continue;
+            }
+        };
+        let lines: Vec<&str> = if extension == "db" {
+            contents.lines().filter(|l| !l.trim().is_empty()).collect()
+        } else {
+            vec![&contents]
+        };
+        for line in lines {
+            match serde_json::from_str(line) {
+                Ok(document) => documents.push((source.clone(), document)),
+                Err(e) => warnings.push(format!("{} is not valid JSON: {}", source, e)),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn document_type(document: &Value) -> &str {
+    document["type"].as_str().unwrap_or("")
+}
+
+/// Foundry moved the document data from `data` to `system` in v10, so both are accepted.
+fn system(document: &Value) -> &Value {
+    match document.get("system") {
+        Some(system) => system,
+        None => &document["data"],
+    }
+}
+
+fn import_document(
+    source: &str,
+    document: &Value,
+    library: &mut ContentLibrary,
+    result: &mut FoundryImport,
+) {
+    let name = match document["name"].as_str() {
+        Some(name) => name.to_string(),
+        None => {
+            result.warnings.push(format!("{} has no name", source));
+            return;
+        }
+    };
+    let system = system(document);
+    let warnings = &mut result.warnings;
+    let rarity = rarity(&name, system, warnings);
+
+    match document_type(document) {
+        "ancestry" => {
+            let size = match system["size"].as_str().unwrap_or("med") {
+                "tiny" => Size::Tiny,
+                "sm" => Size::Small,
+                "med" => Size::Medium,
+                "lg" => Size::Large,
+                "huge" => Size::Huge,
+                "grg" => Size::Gargantuan,
+                other => {
+                    warnings.push(format!("{}: unknown size {:?}", name, other));
+                    Size::Medium
+                }
+            };
+            if has_entries(&system["flaws"]) {
+                warnings.push(format!("{}: ability flaws are not supported", name));
+            }
+            let mut ancestry = Ancestry::new(
+                name.clone(),
+                as_u32(&system["hp"]),
+                size,
+                as_u32(&system["speed"]),
+                boosts(&name, &system["boosts"], warnings),
+            );
+            ancestry.set_rarity(rarity);
+            library.add_ancestry(ancestry);
+        }
+        "background" => {
+            let skill_feat = system["items"]
+                .as_object()
+                .and_then(|items| items.values().next())
+                .map(|item| {
+                    let feat_name = item["name"].as_str().unwrap_or("");
+                    library.feat(feat_name).cloned().unwrap_or_else(|| {
+                        warnings.push(format!(
+                            "{}: skill feat {:?} is not in the library",
+                            name, feat_name
+                        ));
+                        Feat::new(
+                            feat_name.to_string(),
+                            "".to_string(),
+                            FeatType::Skill,
+                            as_u32(&item["level"]).max(1),
+                        )
+                    })
+                });
+            let mut background = Background::new(
+                name.clone(),
+                description(system),
+                boosts(&name, &system["boosts"], warnings),
+                skill_trainings(&name, system, warnings),
+                skill_feat,
+            );
+            background.set_rarity(rarity);
+            library.add_background(background);
+        }
+        "class" => {
+            let key_abilities = abilities(&name, &system["keyAbility"]["value"], warnings);
+            let key_ability = match key_abilities.first() {
+                Some(key_ability) => *key_ability,
+                None => {
+                    warnings.push(format!("{}: class has no key ability", name));
+                    return;
+                }
+            };
+            if key_abilities.len() > 1 {
+                warnings.push(format!(
+                    "{}: only a single key ability is supported, using {:?}",
+                    name, key_ability
+                ));
+            }
+            let mut class = Class::new(name.clone(), key_ability, as_u32(&system["hp"]));
+            class.set_rarity(rarity);
+            library.add_class(class);
+        }
+        "feat" => {
+            let category = system["category"]
+                .as_str()
+                .or_else(|| system["featType"]["value"].as_str())
+                .unwrap_or("");
+            let feat_type = match category {
+                "ancestry" | "heritage" => FeatType::Ancestry,
+                "class" | "classfeature" => FeatType::Class,
+                "general" => FeatType::General,
+                "skill" => FeatType::Skill,
+                other => {
+                    warnings.push(format!("{}: unsupported feat category {:?}", name, other));
+                    return;
+                }
+            };
+            let mut feat = Feat::new(
+                name.clone(),
+                description(system),
+                feat_type,
+                as_u32(&system["level"]["value"]),
+            );
+            feat.set_rarity(rarity);
+            library.add_feat(feat);
+        }
+        item_type @ ("equipment" | "weapon" | "armor" | "consumable") => {
+            let mut item = Item::new(
+                &name,
+                as_u32(&system["level"]["value"]),
+                bulk(&name, system, warnings),
+            );
+            item.set_description(&description(system));
+            item.set_price(price(&name, system, warnings));
+            for value in system["traits"]["value"].as_array().into_iter().flatten() {
+                if let Some(name) = value.as_str() {
+                    item.add_trait(name);
+                }
+            }
+            item.set_rarity(rarity);
+            let mapped = match item_type {
+                "weapon" => weapon(system).map(|weapon| item.set_weapon(Some(weapon))),
+                "armor" => armor(system).map(|armor| item.set_armor(Some(armor))),
+                "consumable" => {
+                    consumable(system).map(|consumable| item.set_consumable(Some(consumable)))
+                }
+                _ => Some(()),
+            };
+            if mapped.is_none() {
+                warnings.push(format!("{}: unsupported {} data", name, item_type));
+                result.skipped += 1;
+                return;
+            }
+            library.add_item(item);
+        }
+        _ => {
+            result.skipped += 1;
+            return;
+        }
+    }
+    result.imported += 1;
+}
+
+/// Weapons name their damage die like `d8`, traits with a value are slugs like `deadly-d10` or `thrown-20`.
+fn weapon(system: &Value) -> Option<Weapon> {
+    let damage = &system["damage"];
+    let die = damage["die"].as_str()?.strip_prefix('d')?.parse().ok()?;
+    let mut weapon = Weapon::new(
+        weapon_category(system["category"].as_str()?)?,
+        weapon_group(system["group"].as_str()?)?,
+        die,
+        DamageType::from_name(damage["damageType"].as_str()?)?,
+    );
+    if system["usage"]["value"].as_str() == Some("held-in-two-hands") {
+        weapon.set_hands(2);
+    }
+    weapon.set_range(system["range"].as_u64().map(|range| range as u32));
+    for value in system["traits"]["value"].as_array().into_iter().flatten() {
+        let slug = value.as_str()?;
+        let with_value = [
+            "deadly-",
+            "fatal-",
+            "thrown-",
+            "two-hand-",
+            "versatile-",
+            "volley-",
+        ]
+        .iter()
+        .find(|prefix| slug.starts_with(*prefix));
+        let source = match with_value {
+            Some(prefix) => format!("{} {}", &prefix[..prefix.len() - 1], &slug[prefix.len()..]),
+            None => slug.to_string(),
+        };
+        weapon.add_trait(source.parse().ok()?);
+    }
+    Some(weapon)
+}
+
+/// Armor without a Dexterity cap stores `null`, the check and Speed penalties are negative numbers.
+fn armor(system: &Value) -> Option<Armor> {
+    let mut armor = Armor::new(
+        armor_category(system["category"].as_str()?)?,
+        system["acBonus"].as_u64()? as u32,
+        system["dexCap"].as_u64().map(|cap| cap as u32),
+    );
+    armor.set_check_penalty(system["checkPenalty"].as_i64().unwrap_or(0).unsigned_abs() as u32);
+    armor.set_speed_penalty(system["speedPenalty"].as_i64().unwrap_or(0).unsigned_abs() as u32);
+    armor.set_strength(system["strength"].as_u64().map(|strength| strength as u32));
+    for value in system["traits"]["value"].as_array().into_iter().flatten() {
+        armor.add_trait(value.as_str()?.parse().ok()?);
+    }
+    Some(armor)
+}
+
+/// Only healing consumables and scrolls map onto an effect, everything else is left to the GM.
+fn consumable(system: &Value) -> Option<Consumable> {
+    if let Some(spell) = system["spell"].as_object() {
+        let spell_system = spell.get("system").or_else(|| spell.get("data"))?;
+        let rank = system["location"]["heightenedLevel"]
+            .as_u64()
+            .or_else(|| spell_system["level"]["value"].as_u64())?;
+        let actions = spell_system["time"]["value"].as_str()?.parse().ok()?;
+        return Some(Consumable::scroll(
+            spell.get("name")?.as_str()?,
+            rank as u32,
+            actions,
+        ));
+    }
+    let damage = &system["damage"];
+    if damage["kind"].as_str() == Some("healing") {
+        let formula: DiceExpression = damage["formula"].as_str()?.parse().ok()?;
+        return Some(Consumable::new(1, ConsumableEffect::Heal(formula)));
+    }
+    None
+}
+
+fn weapon_category(category: &str) -> Option<WeaponCategory> {
+    Some(match category {
+        "unarmed" => WeaponCategory::Unarmed,
+        "simple" => WeaponCategory::Simple,
+        "martial" => WeaponCategory::Martial,
+        "advanced" => WeaponCategory::Advanced,
+        _ => return None,
+    })
+}
+
+fn weapon_group(group: &str) -> Option<WeaponGroup> {
+    Some(match group {
+        "axe" => WeaponGroup::Axe,
+        "bomb" => WeaponGroup::Bomb,
+        "bow" => WeaponGroup::Bow,
+        "brawling" => WeaponGroup::Brawling,
+        "club" => WeaponGroup::Club,
+        "crossbow" => WeaponGroup::Crossbow,
+        "dart" => WeaponGroup::Dart,
+        "firearm" => WeaponGroup::Firearm,
+        "flail" => WeaponGroup::Flail,
+        "hammer" => WeaponGroup::Hammer,
+        "knife" => WeaponGroup::Knife,
+        "pick" => WeaponGroup::Pick,
+        "polearm" => WeaponGroup::Polearm,
+        "shield" => WeaponGroup::Shield,
+        "sling" => WeaponGroup::Sling,
+        "spear" => WeaponGroup::Spear,
+        "sword" => WeaponGroup::Sword,
+        _ => return None,
+    })
+}
+
+fn armor_category(category: &str) -> Option<ArmorCategory> {
+    Some(match category {
+        "unarmored" => ArmorCategory::Unarmored,
+        "light" => ArmorCategory::Light,
+        "medium" => ArmorCategory::Medium,
+        "heavy" => ArmorCategory::Heavy,
+        _ => return None,
+    })
+}
+
+/// Prices are counts per coin, older documents wrote them out like the books, e.g. "4 gp".
+fn price(name: &str, system: &Value, warnings: &mut Vec<String>) -> Price {
+    let value = &system["price"]["value"];
+    if let Some(price) = value.as_str() {
+        return price.parse().unwrap_or_else(|_| {
+            warnings.push(format!("{}: unknown price {:?}", name, price));
+            Price::free()
+        });
+    }
+    Coin::all()
+        .iter()
+        .map(|coin| Price::new(value[coin.abbreviation()].as_u64().unwrap_or(0), *coin))
+        .fold(Price::free(), |total, price| total + price)
+}
+
+/// Newer documents store Bulk as a number with 0.1 for light items, older ones wrote it like the books.
+fn bulk(name: &str, system: &Value, warnings: &mut Vec<String>) -> Bulk {
+    let value = match system.get("bulk") {
+        Some(bulk) => &bulk["value"],
+        None => &system["weight"]["value"],
+    };
+    if let Some(bulk) = value.as_str() {
+        return bulk.parse().unwrap_or_else(|_| {
+            warnings.push(format!("{}: unknown Bulk {:?}", name, bulk));
+            Bulk::negligible()
+        });
+    }
+    match value.as_f64() {
+        Some(bulk) if bulk > 0.0 && bulk < 1.0 => Bulk::light(),
+        Some(bulk) => Bulk::new(bulk as u32),
+        None => Bulk::negligible(),
+    }
+}
+
+/// Foundry keeps the rarity next to the traits, documents without one are common.
+fn rarity(name: &str, system: &Value, warnings: &mut Vec<String>) -> Rarity {
+    match system["traits"]["rarity"].as_str() {
+        Some(rarity) => rarity.parse().unwrap_or_else(|_| {
+            warnings.push(format!("{}: unknown rarity {:?}", name, rarity));
+            Rarity::Common
+        }),
+        None => Rarity::Common,
+    }
+}
+
+fn as_u32(value: &Value) -> u32 {
+    value.as_u64().unwrap_or(0) as u32
+}
+
+fn has_entries(value: &Value) -> bool {
+    value
+        .as_object()
+        .map(|entries| entries.values().any(|entry| has_entries(&entry["value"])))
+        .or_else(|| value.as_array().map(|values| !values.is_empty()))
+        .unwrap_or(false)
+}
+
+/// Strips the HTML markup from a description.
+fn description(system: &Value) -> String {
+    let html = system["description"]["value"].as_str().unwrap_or("");
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+fn abilities(name: &str, values: &Value, warnings: &mut Vec<String>) -> Vec<AbilityScoreType> {
+    values
+        .as_array()
+        .map(|values| values.as_slice())
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|value| {
+            let ability = value.as_str().and_then(AbilityScoreType::from_abbreviation);
+            if ability.is_none() {
+                warnings.push(format!("{}: unknown ability score {}", name, value));
+            }
+            ability
+        })
+        .collect()
+}
+
+/// Converts Foundry's boost slots. A slot with one option is predetermined, one with all six is free and everything
+/// else is restricted.
+fn boosts(name: &str, boosts: &Value, warnings: &mut Vec<String>) -> Vec<AbilityBoostChoice> {
+    let mut slots: Vec<(&String, &Value)> = match boosts.as_object() {
+        Some(slots) => slots.iter().collect(),
+        None => return vec![],
+    };
+    slots.sort_by_key(|(key, _)| key.parse::<u32>().unwrap_or(u32::MAX));
+
+    slots
+        .into_iter()
+        .filter_map(|(_, slot)| {
+            let options: HashSet<_> = abilities(name, &slot["value"], warnings)
+                .into_iter()
+                .collect();
+            match options.len() {
+                0 => None,
+                1 => Some(AbilityBoostChoice::predetermined(
+                    *options.iter().next().unwrap(),
+                )),
+                6 => Some(AbilityBoostChoice::free()),
+                _ => Some(AbilityBoostChoice::restricted(options)),
+            }
+        })
+        .collect()
+}
+
+fn skill(abbreviation: &str) -> Option<Skill> {
+    Some(match abbreviation {
+        "acr" => Skill::Acrobatics,
+        "arc" => Skill::Arcana,
+        "ath" => Skill::Athletics,
+        "cra" => Skill::Crafting,
+        "dec" => Skill::Deception,
+        "dip" => Skill::Diplomacy,
+        "itm" => Skill::Intimidation,
+        "med" => Skill::Medicine,
+        "nat" => Skill::Nature,
+        "occ" => Skill::Occultism,
+        "prf" => Skill::Performance,
+        "rel" => Skill::Religion,
+        "soc" => Skill::Society,
+        "ste" => Skill::Stealth,
+        "sur" => Skill::Survival,
+        "thi" => Skill::Thievery,
+        _ => return None,
+    })
+}
+
+/// Backgrounds that let the player pick between several skills list all of them, which maps onto a restricted choice.
+fn skill_trainings(
+    name: &str,
+    system: &Value,
+    warnings: &mut Vec<String>,
+) -> Vec<SkillTrainingChoice> {
+    let skills: HashSet<Skill> = system["trainedSkills"]["value"]
+        .as_array()
+        .map(|values| values.as_slice())
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|value| {
+            let skill = value.as_str().and_then(skill);
+            if skill.is_none() {
+                warnings.push(format!("{}: unknown skill {}", name, value));
+            }
+            skill
+        })
+        .collect();
+
+    let mut trainings = match skills.len() {
+        0 => vec![],
+        1 => vec![SkillTrainingChoice::predetermined(
+            skills.into_iter().next().unwrap(),
+        )],
+        _ => vec![SkillTrainingChoice::restricted(skills)],
+    };
+
+    let lores: Vec<&str> = match system["trainedSkills"]["lore"].as_array() {
+        Some(lores) => lores.iter().filter_map(|lore| lore.as_str()).collect(),
+        None => system["trainedLore"].as_str().into_iter().collect(),
+    };
+    for lore in lores {
+        match Skill::from_name(lore) {
+            Some(skill) => trainings.push(SkillTrainingChoice::predetermined(skill)),
+            None => trainings.push(SkillTrainingChoice::predetermined(Skill::lore(lore))),
+        }
+    }
+    trainings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{AbilityBoostChoiceSet, SkillTrainingChoiceSet};
+    use crate::item::{ArmorTrait, TreasureKind, WeaponTrait};
+    use std::env;
+
+    const DWARF: &str = r#"{
+        "_id": "BYj5ZvlXZdpaEgA1",
+        "name": "Dwarf",
+        "type": "ancestry",
+        "system": {
+            "hp": 10,
+            "size": "med",
+            "speed": 20,
+            "boosts": {
+                "0": { "value": ["con"] },
+                "1": { "value": ["wis"] },
+                "2": { "value": ["cha", "con", "dex", "int", "str", "wis"] }
+            },
+            "flaws": { "0": { "value": ["cha"] } }
+        }
+    }"#;
+
+    const WARRIOR: &str = r#"{
+        "name": "Warrior",
+        "type": "background",
+        "system": {
+            "description": { "value": "<p>In your youth you waded into battle.</p>" },
+            "boosts": {
+                "0": { "value": ["con", "str"] },
+                "1": { "value": ["cha", "con", "dex", "int", "str", "wis"] }
+            },
+            "trainedSkills": { "lore": ["Warfare Lore"], "value": ["itm"] },
+            "items": { "abc": { "name": "Intimidating Glare", "level": 1 } }
+        }
+    }"#;
+
+    const FIGHTER: &str = r#"{
+        "name": "Fighter",
+        "type": "class",
+        "data": { "hp": 10, "keyAbility": { "value": ["str", "dex"] } }
+    }"#;
+
+    const GLARE: &str = r#"{
+        "name": "Intimidating Glare",
+        "type": "feat",
+        "system": {
+            "category": "skill",
+            "level": { "value": 1 },
+            "traits": { "rarity": "uncommon", "value": ["general", "skill"] }
+        }
+    }"#;
+
+    const POTION: &str = r#"{
+        "name": "Minor Healing Potion",
+        "type": "consumable",
+        "system": {
+            "level": { "value": 1 },
+            "price": { "value": { "gp": 4 } },
+            "bulk": { "value": 0.1 },
+            "damage": { "formula": "1d8", "type": "vitality", "kind": "healing" },
+            "traits": { "rarity": "common", "value": ["consumable", "healing", "potion"] }
+        }
+    }"#;
+
+    const SMOKESTICK: &str = r#"{
+        "name": "Smokestick (Lesser)",
+        "type": "consumable",
+        "system": {
+            "level": { "value": 1 },
+            "price": { "value": { "gp": 3 } },
+            "traits": { "value": ["alchemical", "consumable"] }
+        }
+    }"#;
+
+    const RAPIER: &str = r#"{
+        "name": "Rapier",
+        "type": "weapon",
+        "system": {
+            "category": "martial",
+            "group": "sword",
+            "damage": { "dice": 1, "die": "d6", "damageType": "piercing" },
+            "usage": { "value": "held-in-one-hand" },
+            "range": null,
+            "price": { "value": { "gp": 2 } },
+            "bulk": { "value": 1 },
+            "traits": { "value": ["deadly-d8", "disarm", "finesse"] }
+        }
+    }"#;
+
+    const FULL_PLATE: &str = r#"{
+        "name": "Full Plate",
+        "type": "armor",
+        "data": {
+            "level": { "value": 2 },
+            "price": { "value": "30 gp" },
+            "weight": { "value": "4" },
+            "category": "heavy",
+            "acBonus": 6,
+            "dexCap": 0,
+            "checkPenalty": -3,
+            "speedPenalty": -10,
+            "strength": 4,
+            "traits": { "rarity": "uncommon", "value": ["bulwark"] }
+        }
+    }"#;
+
+    #[test]
+    fn ancestry_boosts_are_mapped() {
+        let mut library = ContentLibrary::new();
+        let result = import_foundry_json(DWARF, &mut library).unwrap();
+
+        let dwarf = library.ancestry("Dwarf").unwrap();
+        assert_eq!(result.imported(), 1);
+        assert_eq!(dwarf.base_hp(), 10);
+        assert_eq!(dwarf.speed(), 20);
+        assert_eq!(
+            dwarf
+                .ability_boosts()
+                .apply_choices(&[AbilityScoreType::Strength])
+                .unwrap(),
+            hashset![
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Wisdom,
+                AbilityScoreType::Strength
+            ]
+        );
+        assert_eq!(result.warnings().len(), 1);
+    }
+
+    #[test]
+    fn class_supports_legacy_data_field() {
+        let mut library = ContentLibrary::new();
+        let result = import_foundry_json(FIGHTER, &mut library).unwrap();
+
+        let fighter = library.class("Fighter").unwrap();
+        assert_eq!(fighter.hp_increment(), 10);
+        assert_eq!(fighter.key_ability(), AbilityScoreType::Strength);
+        assert_eq!(result.warnings().len(), 1);
+    }
+
+    #[test]
+    fn items_keep_price_level_bulk_and_rarity() {
+        let mut library = ContentLibrary::new();
+        import_foundry_json(POTION, &mut library).unwrap();
+        let result = import_foundry_json(FULL_PLATE, &mut library).unwrap();
+
+        assert_eq!(result.imported(), 1);
+        assert!(result.warnings().is_empty());
+        let potion = library.item("Minor Healing Potion").unwrap();
+        assert_eq!(potion.price(), Price::gp(4));
+        assert_eq!(potion.level(), 1);
+        assert_eq!(potion.bulk(), Bulk::light());
+        assert!(potion.has_trait("potion"));
+        let plate = library.item("Full Plate").unwrap();
+        assert_eq!(plate.price(), Price::gp(30));
+        assert_eq!(plate.level(), 2);
+        assert_eq!(plate.bulk(), Bulk::new(4));
+        assert_eq!(plate.rarity(), Rarity::Uncommon);
+    }
+
+    #[test]
+    fn items_keep_their_weapon_armor_and_consumable_data() {
+        let mut library = ContentLibrary::new();
+        import_foundry_json(POTION, &mut library).unwrap();
+        import_foundry_json(FULL_PLATE, &mut library).unwrap();
+        import_foundry_json(RAPIER, &mut library).unwrap();
+
+        let potion = library.item("Minor Healing Potion").unwrap();
+        assert_eq!(
+            potion.consumable().unwrap().effect(),
+            &ConsumableEffect::Heal("1d8".parse().unwrap())
+        );
+        assert_eq!(TreasureKind::of(potion), TreasureKind::Consumable);
+        let plate = library.item("Full Plate").unwrap().armor().unwrap();
+        assert_eq!(plate.category(), ArmorCategory::Heavy);
+        assert_eq!(plate.ac_bonus(), 6);
+        assert_eq!(plate.dex_cap(), Some(0));
+        assert_eq!(plate.check_penalty(), 3);
+        assert_eq!(plate.speed_penalty(), 10);
+        assert_eq!(plate.strength(), Some(4));
+        assert!(plate.has_trait(&ArmorTrait::Bulwark));
+        let rapier = library.item("Rapier").unwrap().weapon().unwrap();
+        assert_eq!(rapier.category(), WeaponCategory::Martial);
+        assert_eq!(rapier.group(), WeaponGroup::Sword);
+        assert_eq!(rapier.damage_die(), 6);
+        assert_eq!(rapier.damage_type(), DamageType::Piercing);
+        assert_eq!(rapier.deadly(), Some(8));
+        assert!(rapier.has_trait(&WeaponTrait::Finesse));
+    }
+
+    #[test]
+    fn consumables_without_a_known_effect_are_skipped() {
+        let mut library = ContentLibrary::new();
+        let result = import_foundry_json(SMOKESTICK, &mut library).unwrap();
+
+        assert_eq!(result.imported(), 0);
+        assert_eq!(result.skipped(), 1);
+        assert_eq!(
+            result.warnings(),
+            &vec!["Smokestick (Lesser): unsupported consumable data".to_string()]
+        );
+        assert!(library.item("Smokestick (Lesser)").is_none());
+    }
+
+    #[test]
+    fn directory_import_resolves_background_feats() {
+        let dir = env::temp_dir().join(format!("ttrpg_sim_foundry_{}", std::process::id()));
+        fs::create_dir_all(dir.join("feats")).unwrap();
+        fs::write(dir.join("warrior.json"), WARRIOR).unwrap();
+        fs::write(dir.join("feats").join("glare.json"), GLARE).unwrap();
+        fs::write(
+            dir.join("legacy.db"),
+            format!(
+                "{}\n{}\n",
+                FIGHTER.replace('\n', " "),
+                r#"{"name": "Fireball", "type": "spell", "system": {}}"#
+            ),
+        )
+        .unwrap();
+
+        let mut library = ContentLibrary::new();
+        let result = import_foundry_directory(&dir, &mut library).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.imported(), 3);
+        assert_eq!(result.skipped(), 1);
+        let warrior = library.background("Warrior").unwrap();
+        assert_eq!(
+            warrior.description(),
+            "In your youth you waded into battle."
+        );
+        assert_eq!(warrior.skill_feat().unwrap().feat_type(), &FeatType::Skill);
+        assert_eq!(warrior.skill_feat().unwrap().rarity(), Rarity::Uncommon);
+        assert_eq!(
+            warrior.skill_trainings().apply_choices(&[]).unwrap(),
+            hashset![Skill::Intimidation, Skill::lore("Warfare")]
+        );
+    }
+}
@@ -0,0 +1,9 @@
+mod foundry;
+mod pathbuilder;
+
+pub use foundry::import_foundry_directory;
+pub use foundry::import_foundry_json;
+pub use foundry::FoundryImport;
+pub use pathbuilder::import_pathbuilder;
+pub use pathbuilder::import_pathbuilder_file;
+pub use pathbuilder::PathbuilderImport;
@@ -0,0 +1,702 @@
+use crate::character::{
+    AbilityBoostChoice, AbilityScoreType, Ancestry, Background, Character, Class, Proficiency,
+    Size, Skill, SkillTrainingChoice,
+};
+use crate::content::ContentLibrary;
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// The name of the content that stands in for a class, ancestry or background the library doesn't have.
+const PLACEHOLDER: &str = "Unknown";
+
+#[derive(Deserialize)]
+struct Export {
+    build: Build,
+}
+
+#[derive(Deserialize)]
+struct Build {
+    name: String,
+    class: String,
+    level: u32,
+    ancestry: String,
+    background: String,
+    #[serde(default)]
+    keyability: String,
+    #[serde(default)]
+    attributes: Attributes,
+    abilities: Abilities,
+    #[serde(default)]
+    proficiencies: HashMap<String, u32>,
+    #[serde(default)]
+    feats: Vec<serde_json::Value>,
+    #[serde(default)]
+    lores: Vec<(String, u32)>,
+}
+
+#[derive(Deserialize, Default)]
+struct Attributes {
+    #[serde(default)]
+    ancestryhp: u32,
+    #[serde(default)]
+    classhp: u32,
+    #[serde(default)]
+    speed: u32,
+}
+
+#[derive(Deserialize)]
+struct Abilities {
+    str: u32,
+    dex: u32,
+    con: u32,
+    int: u32,
+    wis: u32,
+    cha: u32,
+    #[serde(default)]
+    breakdown: Breakdown,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Breakdown {
+    ancestry_free: Vec<String>,
+    ancestry_boosts: Vec<String>,
+    ancestry_flaws: Vec<String>,
+    background_boosts: Vec<String>,
+    map_levelled_boosts: HashMap<String, Vec<String>>,
+}
+
+impl Abilities {
+    fn get(&self, ability: AbilityScoreType) -> u32 {
+        match ability {
+            AbilityScoreType::Strength => self.str,
+            AbilityScoreType::Dexterity => self.dex,
+            AbilityScoreType::Constitution => self.con,
+            AbilityScoreType::Intelligence => self.int,
+            AbilityScoreType::Wisdom => self.wis,
+            AbilityScoreType::Charisma => self.cha,
+        }
+    }
+}
+
+/// The result of a Pathbuilder import. Anything that could not be mapped onto the content library is listed in
+/// `warnings` instead of failing the import.
+pub struct PathbuilderImport<'a> {
+    character: Character<'a, 'a, 'a>,
+    warnings: Vec<String>,
+}
+
+impl<'a> PathbuilderImport<'a> {
+    pub fn character(&self) -> &Character<'a, 'a, 'a> {
+        &self.character
+    }
+
+    pub fn warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
+
+    pub fn into_character(self) -> Character<'a, 'a, 'a> {
+        self.character
+    }
+}
+
+pub fn import_pathbuilder_file<'a, P: AsRef<Path>>(
+    path: P,
+    library: &'a ContentLibrary,
+) -> Result<PathbuilderImport<'a>> {
+    let json = fs::read_to_string(path.as_ref()).map_err(|e| Error::io(path.as_ref(), e))?;
+    import_pathbuilder(&json, library)
+}
+
+/// Imports a character from a Pathbuilder 2e JSON export.
+///
+/// Anything that doesn't match up with the library is reported as a warning. A class, ancestry or background that
+/// is missing or whose choices can't be made is replaced by a placeholder named `Unknown`.
+pub fn import_pathbuilder<'a>(
+    json: &str,
+    library: &'a ContentLibrary,
+) -> Result<PathbuilderImport<'a>> {
+    let build = serde_json::from_str::<Export>(json)?.build;
+    let mut warnings = Vec::new();
+
+    let class = match library.class(&build.class) {
+        Some(class) => class,
+        None => {
+            warnings.push(format!(
+                "Unknown class {:?} was replaced by a placeholder",
+                build.class
+            ));
+            placeholder_class(
+                AbilityScoreType::from_abbreviation(&build.keyability)
+                    .unwrap_or(AbilityScoreType::Strength),
+            )
+        }
+    };
+    let ancestry = library.ancestry(&build.ancestry).unwrap_or_else(|| {
+        warnings.push(format!(
+            "Unknown ancestry {:?} was replaced by a placeholder",
+            build.ancestry
+        ));
+        placeholder_ancestry()
+    });
+    let background = library.background(&build.background).unwrap_or_else(|| {
+        warnings.push(format!(
+            "Unknown background {:?} was replaced by a placeholder",
+            build.background
+        ));
+        placeholder_background()
+    });
+
+    let breakdown = &build.abilities.breakdown;
+    if !breakdown.ancestry_flaws.is_empty() {
+        warnings.push(format!(
+            "Ancestry flaws {:?} are not supported and were ignored",
+            breakdown.ancestry_flaws
+        ));
+    }
+
+    let mut ancestry_boosts = breakdown.ancestry_boosts.clone();
+    ancestry_boosts.extend(breakdown.ancestry_free.iter().cloned());
+    let ancestry_boosts = parse_abilities(&ancestry_boosts, &mut warnings);
+    let (ancestry, ancestry_boosts) = match fill_boost_choices(
+        "ancestry",
+        ancestry.ability_boosts(),
+        &ancestry_boosts,
+        &mut warnings,
+    ) {
+        Some(choices) => (ancestry, choices),
+        None => {
+            warnings.push(format!(
+                "Ancestry {:?} was replaced by a placeholder",
+                ancestry.name()
+            ));
+            let placeholder = placeholder_ancestry();
+            let choices = fill_free_boosts(
+                "ancestry",
+                placeholder.ability_boosts(),
+                &ancestry_boosts,
+                &mut warnings,
+            );
+            (placeholder, choices)
+        }
+    };
+    let extra_boosts = fill_free_boosts(
+        "level 1",
+        &free_boosts(),
+        &parse_abilities(&levelled_boosts(breakdown, 1), &mut warnings),
+        &mut warnings,
+    );
+
+    let mut trained: Vec<(Skill, Proficiency)> = build
+        .proficiencies
+        .iter()
+        .filter_map(|(name, &rank)| Some((Skill::from_name(name)?, proficiency(rank))))
+        .chain(
+            build
+                .lores
+                .iter()
+                .map(|(topic, rank)| (Skill::lore(topic), proficiency(*rank))),
+        )
+        .collect();
+    trained.sort();
+    let trained_skills: Vec<Skill> = trained
+        .iter()
+        .filter(|(_, proficiency)| *proficiency > Proficiency::Untrained)
+        .map(|(skill, _)| skill.clone())
+        .collect();
+    let background_boosts = parse_abilities(&breakdown.background_boosts, &mut warnings);
+    let choices = fill_boost_choices(
+        "background",
+        background.ability_boosts(),
+        &background_boosts,
+        &mut warnings,
+    )
+    .zip(fill_skill_choices(
+        background.skill_trainings(),
+        &trained_skills,
+        &mut warnings,
+    ));
+    let (background, background_boosts, background_skills) = match choices {
+        Some((boosts, skills)) => (background, boosts, skills),
+        None => {
+            warnings.push(format!(
+                "Background {:?} was replaced by a placeholder",
+                background.name()
+            ));
+            let placeholder = placeholder_background();
+            let boosts = fill_free_boosts(
+                "background",
+                placeholder.ability_boosts(),
+                &background_boosts,
+                &mut warnings,
+            );
+            (placeholder, boosts, vec![])
+        }
+    };
+
+    let mut character = Character::new(
+        &build.name,
+        class,
+        ancestry,
+        &ancestry_boosts,
+        background,
+        &background_boosts,
+        &background_skills,
+        &extra_boosts,
+    )?;
+
+    for level in 2..=build.level {
+        character.level_up();
+        if level % 5 == 0 {
+            let boosts = fill_free_boosts(
+                &format!("level {}", level),
+                &free_boosts(),
+                &parse_abilities(&levelled_boosts(breakdown, level), &mut warnings),
+                &mut warnings,
+            );
+            character.apply_level_boosts(&boosts)?;
+        }
+    }
+
+    for (skill, proficiency) in trained {
+        if proficiency > character.proficiency(&skill) {
+            character.set_proficiency(skill, proficiency);
+        }
+    }
+
+    for feat in build.feats.iter() {
+        let name = match feat.get(0).and_then(|name| name.as_str()) {
+            Some(name) => name,
+            None => {
+                warnings.push(format!("Malformed feat entry {}", feat));
+                continue;
+            }
+        };
+        if character.feats().iter().any(|f| f.name() == name) {
+            continue;
+        }
+        match library.feat(name) {
+            Some(feat) => character.add_feat(feat.clone()),
+            None => warnings.push(format!("Unknown feat {:?} was skipped", name)),
+        }
+    }
+
+    for ability in AbilityScoreType::all().iter() {
+        let expected = build.abilities.get(*ability);
+        let actual = character.abliity_scores().get(*ability).value();
+        if expected != actual {
+            warnings.push(format!(
+                "{:?} is {} in Pathbuilder but {} after import",
+                ability, expected, actual
+            ));
+        }
+    }
+    if build.attributes.ancestryhp != ancestry.base_hp() {
+        warnings.push(format!(
+            "Ancestry HP is {} in Pathbuilder but {} in the library",
+            build.attributes.ancestryhp,
+            ancestry.base_hp()
+        ));
+    }
+    if build.attributes.classhp != class.hp_increment() {
+        warnings.push(format!(
+            "Class HP is {} in Pathbuilder but {} in the library",
+            build.attributes.classhp,
+            class.hp_increment()
+        ));
+    }
+    if build.attributes.speed != ancestry.speed() {
+        warnings.push(format!(
+            "Speed is {} in Pathbuilder but {} in the library",
+            build.attributes.speed,
+            ancestry.speed()
+        ));
+    }
+
+    Ok(PathbuilderImport {
+        character,
+        warnings,
+    })
+}
+
+fn placeholder_class(key_ability: AbilityScoreType) -> &'static Class {
+    static CLASSES: OnceLock<Vec<Class>> = OnceLock::new();
+    CLASSES
+        .get_or_init(|| {
+            AbilityScoreType::all()
+                .iter()
+                .map(|&ability| Class::new(PLACEHOLDER.to_string(), ability, 8))
+                .collect()
+        })
+        .iter()
+        .find(|class| class.key_ability() == key_ability)
+        .expect("there is a placeholder for every key ability")
+}
+
+fn placeholder_ancestry() -> &'static Ancestry {
+    static ANCESTRY: OnceLock<Ancestry> = OnceLock::new();
+    ANCESTRY.get_or_init(|| {
+        Ancestry::new(
+            PLACEHOLDER.to_string(),
+            8,
+            Size::Medium,
+            25,
+            vec![AbilityBoostChoice::free(), AbilityBoostChoice::free()],
+        )
+    })
+}
+
+fn placeholder_background() -> &'static Background {
+    static BACKGROUND: OnceLock<Background> = OnceLock::new();
+    BACKGROUND.get_or_init(|| {
+        Background::new(
+            PLACEHOLDER.to_string(),
+            "".to_string(),
+            vec![AbilityBoostChoice::free(), AbilityBoostChoice::free()],
+            vec![],
+            None,
+        )
+    })
+}
+
+fn free_boosts() -> Vec<AbilityBoostChoice> {
+    vec![
+        AbilityBoostChoice::free(),
+        AbilityBoostChoice::free(),
+        AbilityBoostChoice::free(),
+        AbilityBoostChoice::free(),
+    ]
+}
+
+fn levelled_boosts(breakdown: &Breakdown, level: u32) -> Vec<String> {
+    breakdown
+        .map_levelled_boosts
+        .get(&level.to_string())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn proficiency(rank: u32) -> Proficiency {
+    match rank {
+        0 | 1 => Proficiency::Untrained,
+        2 | 3 => Proficiency::Trained,
+        4 | 5 => Proficiency::Expert,
+        6 | 7 => Proficiency::Master,
+        _ => Proficiency::Legendary,
+    }
+}
+
+fn parse_abilities(names: &[String], warnings: &mut Vec<String>) -> Vec<AbilityScoreType> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let ability = AbilityScoreType::from_abbreviation(name);
+            if ability.is_none() {
+                warnings.push(format!("Unknown ability score {:?}", name));
+            }
+            ability
+        })
+        .collect()
+}
+
+/// Turns the boosts Pathbuilder lists into choices for `set`. Predetermined boosts are skipped, restricted boosts get
+/// the first listed boost they allow and free boosts take whatever is left. If the export doesn't provide a valid
+/// choice, the first option that isn't taken yet is used instead and a warning is recorded. Returns `None` if every
+/// option of a choice is taken already.
+fn fill_boost_choices(
+    source: &str,
+    set: &[AbilityBoostChoice],
+    boosts: &[AbilityScoreType],
+    warnings: &mut Vec<String>,
+) -> Option<Vec<AbilityScoreType>> {
+    let mut remaining = boosts.to_vec();
+    let mut taken: Vec<AbilityScoreType> = set
+        .iter()
+        .filter_map(|boost| match boost {
+            AbilityBoostChoice::Predetermined(ability) => Some(*ability),
+            _ => None,
+        })
+        .collect();
+    remaining.retain(|ability| !taken.contains(ability));
+
+    let mut choices: Vec<Option<AbilityScoreType>> = Vec::new();
+    for boost in set.iter() {
+        if let AbilityBoostChoice::Restricted(restricted) = boost {
+            let position = remaining
+                .iter()
+                .position(|ability| restricted.options().contains(ability));
+            let choice = position.map(|i| remaining.remove(i));
+            taken.extend(choice);
+            choices.push(choice);
+        } else if let AbilityBoostChoice::Free(_) = boost {
+            choices.push(None);
+        }
+    }
+    for (boost, choice) in set
+        .iter()
+        .filter(|boost| !matches!(boost, AbilityBoostChoice::Predetermined(_)))
+        .zip(choices.iter_mut())
+    {
+        if let AbilityBoostChoice::Free(_) = boost {
+            if !remaining.is_empty() {
+                *choice = Some(remaining.remove(0));
+                taken.extend(*choice);
+            }
+        }
+    }
+    if !remaining.is_empty() {
+        warnings.push(format!("Ignored extra {} boosts {:?}", source, remaining));
+    }
+
+    set.iter()
+        .filter(|boost| !matches!(boost, AbilityBoostChoice::Predetermined(_)))
+        .zip(choices)
+        .map(|(boost, choice)| {
+            choice.or_else(|| {
+                let options = match boost {
+                    AbilityBoostChoice::Restricted(restricted) => restricted.options().clone(),
+                    _ => AbilityScoreType::all().to_vec(),
+                };
+                let fallback = options.into_iter().find(|ability| !taken.contains(ability));
+                match fallback {
+                    Some(fallback) => {
+                        warnings.push(format!(
+                            "Missing {} boost, picked {:?} instead",
+                            source, fallback
+                        ));
+                        taken.push(fallback);
+                    }
+                    None => warnings.push(format!(
+                        "Every option of a {} boost is taken already",
+                        source
+                    )),
+                }
+                fallback
+            })
+        })
+        .collect()
+}
+
+/// `fill_boost_choices` for sets of up to six free boosts, which can always be filled.
+fn fill_free_boosts(
+    source: &str,
+    set: &[AbilityBoostChoice],
+    boosts: &[AbilityScoreType],
+    warnings: &mut Vec<String>,
+) -> Vec<AbilityScoreType> {
+    fill_boost_choices(source, set, boosts, warnings)
+        .expect("free boosts always have an option left")
+}
+
+/// Picks the background's skill choices from the skills the export has trained, see `fill_boost_choices`.
+fn fill_skill_choices(
+    set: &[SkillTrainingChoice],
+    trained: &[Skill],
+    warnings: &mut Vec<String>,
+) -> Option<Vec<Skill>> {
+    let mut taken: Vec<Skill> = set
+        .iter()
+        .filter_map(|training| match training {
+            SkillTrainingChoice::Predetermined(skill) => Some(skill.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut choices = Vec::new();
+    for training in set.iter() {
+        let options = match training {
+            SkillTrainingChoice::Predetermined(_) => continue,
+            SkillTrainingChoice::Restricted(restricted) => restricted.options().clone(),
+            SkillTrainingChoice::Free(_) => Skill::all().to_vec(),
+        };
+        let choice = trained
+            .iter()
+            .find(|skill| options.contains(skill) && !taken.contains(skill))
+            .cloned();
+        let choice = match choice {
+            Some(choice) => choice,
+            None => match options.iter().find(|skill| !taken.contains(skill)) {
+                Some(fallback) => {
+                    warnings.push(format!(
+                        "No background skill matches the export, picked {:?} instead",
+                        fallback
+                    ));
+                    fallback.clone()
+                }
+                None => {
+                    warnings
+                        .push("Every option of a background skill is taken already".to_string());
+                    return None;
+                }
+            },
+        };
+        taken.push(choice.clone());
+        choices.push(choice);
+    }
+    Some(choices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Feat, FeatType};
+
+    fn library() -> ContentLibrary {
+        let mut library = ContentLibrary::new();
+        library.add_class(Class::new(
+            "Fighter".to_string(),
+            AbilityScoreType::Strength,
+            10,
+        ));
+        library.add_ancestry(Ancestry::new(
+            "Dwarf".to_string(),
+            10,
+            Size::Medium,
+            20,
+            vec![
+                AbilityBoostChoice::predetermined(AbilityScoreType::Constitution),
+                AbilityBoostChoice::predetermined(AbilityScoreType::Wisdom),
+                AbilityBoostChoice::free(),
+            ],
+        ));
+        library.add_background(Background::new(
+            "Warrior".to_string(),
+            "".to_string(),
+            vec![
+                AbilityBoostChoice::restricted(hashset![
+                    AbilityScoreType::Strength,
+                    AbilityScoreType::Constitution
+                ]),
+                AbilityBoostChoice::free(),
+            ],
+            vec![
+                SkillTrainingChoice::restricted(hashset![Skill::Athletics, Skill::Intimidation]),
+                SkillTrainingChoice::predetermined(Skill::lore("Warfare")),
+            ],
+            None,
+        ));
+        library.add_feat(Feat::new(
+            "Power Attack".to_string(),
+            "".to_string(),
+            FeatType::Class,
+            1,
+        ));
+        library
+    }
+
+    const EXPORT: &str = r#"{
+        "success": true,
+        "build": {
+            "name": "Thorgrim",
+            "class": "Fighter",
+            "level": 5,
+            "ancestry": "Dwarf",
+            "heritage": "Rock Dwarf",
+            "background": "Warrior",
+            "attributes": { "ancestryhp": 10, "classhp": 10, "speed": 20 },
+            "abilities": {
+                "str": 19, "dex": 16, "con": 16, "int": 12, "wis": 14, "cha": 8,
+                "breakdown": {
+                    "ancestryFree": ["Str"],
+                    "ancestryBoosts": ["Con", "Wis"],
+                    "ancestryFlaws": ["Cha"],
+                    "backgroundBoosts": ["Dex", "Str"],
+                    "classBoosts": ["Str"],
+                    "mapLevelledBoosts": {
+                        "1": ["Str", "Dex", "Con", "Wis"],
+                        "5": ["Str", "Dex", "Con", "Int"]
+                    }
+                }
+            },
+            "proficiencies": { "athletics": 4, "intimidation": 2, "perception": 4 },
+            "lores": [["Warfare", 2]],
+            "feats": [
+                ["Power Attack", null, "Class Feat", 1],
+                ["Unknown Feat", null, "General Feat", 3]
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn import_maps_content_and_choices() {
+        let library = library();
+        let import = import_pathbuilder(EXPORT, &library).unwrap();
+        let character = import.character();
+
+        assert_eq!(character.name(), "Thorgrim");
+        assert_eq!(character.level(), 5);
+        assert_eq!(character.hp().max(), 60);
+        assert_eq!(character.strength().value(), 19);
+        assert_eq!(character.dexterity().value(), 16);
+        assert_eq!(character.constitution().value(), 16);
+        assert_eq!(
+            character.proficiency(&Skill::Athletics),
+            Proficiency::Expert
+        );
+        assert_eq!(
+            character.proficiency(&Skill::lore("Warfare")),
+            Proficiency::Trained
+        );
+        assert_eq!(character.feats().len(), 1);
+    }
+
+    #[test]
+    fn import_reports_mismatches_as_warnings() {
+        let library = library();
+        let import = import_pathbuilder(EXPORT, &library).unwrap();
+
+        assert!(import
+            .warnings()
+            .iter()
+            .any(|warning| warning.contains("Unknown Feat")));
+        // ancestry flaws are ignored, so only Charisma differs
+        let abilities: Vec<_> = import
+            .warnings()
+            .iter()
+            .filter(|warning| warning.contains("after import"))
+            .collect();
+        assert_eq!(
+            abilities,
+            ["Charisma is 8 in Pathbuilder but 10 after import"]
+        );
+    }
+
+    #[test]
+    fn import_replaces_unknown_content_by_placeholders() {
+        let library = library();
+        let export = EXPORT
+            .replace(r#""Fighter""#, r#""Wizard""#)
+            .replace(r#""Dwarf""#, r#""Elf""#)
+            .replace(r#""level""#, r#""keyability": "int", "level""#);
+        let import = import_pathbuilder(&export, &library).unwrap();
+        let character = import.character();
+
+        assert_eq!(character.class().name(), "Unknown");
+        assert_eq!(
+            character.class().key_ability(),
+            AbilityScoreType::Intelligence
+        );
+        assert_eq!(character.ancestry().name(), "Unknown");
+        assert_eq!(character.background().name(), "Warrior");
+        assert!(import
+            .warnings()
+            .iter()
+            .any(|warning| warning.contains("Unknown class \"Wizard\"")));
+    }
+
+    #[test]
+    fn fill_boost_choices_never_duplicates() {
+        let mut warnings = vec![];
+        let set = vec![
+            AbilityBoostChoice::predetermined(AbilityScoreType::Strength),
+            AbilityBoostChoice::restricted(hashset![AbilityScoreType::Strength]),
+        ];
+
+        assert_eq!(fill_boost_choices("test", &set, &[], &mut warnings), None);
+        assert_eq!(warnings.len(), 1);
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// How often an activation can be used.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Frequency {
+    Unlimited,
+    /// The given number of times between daily preparations.
+    PerDay(u32),
+}
+
+/// A way to activate an item, e.g. the `Interact` activation of a wayfinder.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Activation {
+    name: String,
+    /// The number of actions it takes, 0 for free actions and reactions.
+    actions: u32,
+    frequency: Frequency,
+    /// How often it was used since the last daily preparations.
+    used: u32,
+}
+
+impl Activation {
+    pub fn new(name: &str, actions: u32, frequency: Frequency) -> Activation {
+        Activation {
+            name: name.to_string(),
+            actions,
+            frequency,
+            used: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn actions(&self) -> u32 {
+        self.actions
+    }
+
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    pub fn used(&self) -> u32 {
+        self.used
+    }
+
+    /// How many more uses are left today, `None` if there is no limit.
+    pub fn remaining(&self) -> Option<u32> {
+        match self.frequency {
+            Frequency::Unlimited => None,
+            Frequency::PerDay(uses) => Some(uses.saturating_sub(self.used)),
+        }
+    }
+
+    pub(crate) fn use_once(&mut self) -> bool {
+        if self.remaining() == Some(0) {
+            return false;
+        }
+        self.used += 1;
+        true
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.used = 0;
+    }
+}
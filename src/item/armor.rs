@@ -0,0 +1,264 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Decides which proficiency is used for AC while wearing the armor.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ArmorCategory {
+    Unarmored,
+    Light,
+    Medium,
+    Heavy,
+}
+
+impl ArmorCategory {
+    pub fn all() -> [ArmorCategory; 4] {
+        [
+            ArmorCategory::Unarmored,
+            ArmorCategory::Light,
+            ArmorCategory::Medium,
+            ArmorCategory::Heavy,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum ArmorTrait {
+    /// Protects against damaging Reflex effects as if the Dexterity modifier were +3.
+    Bulwark,
+    Comfort,
+    /// The check penalty doesn't apply to Acrobatics and Athletics.
+    Flexible,
+    /// The check penalty applies to Stealth even when meeting the Strength threshold.
+    Noisy,
+    /// Any trait without rules of its own here.
+    Other(String),
+}
+
+impl fmt::Display for ArmorTrait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArmorTrait::Bulwark => write!(f, "bulwark"),
+            ArmorTrait::Comfort => write!(f, "comfort"),
+            ArmorTrait::Flexible => write!(f, "flexible"),
+            ArmorTrait::Noisy => write!(f, "noisy"),
+            ArmorTrait::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl FromStr for ArmorTrait {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<ArmorTrait> {
+        let source = source.trim().to_lowercase();
+        Ok(match source.as_str() {
+            "" => {
+                return Err(Error::InvalidFormat(
+                    "armor trait without a name".to_string(),
+                ))
+            }
+            "bulwark" => ArmorTrait::Bulwark,
+            "comfort" => ArmorTrait::Comfort,
+            "flexible" => ArmorTrait::Flexible,
+            "noisy" => ArmorTrait::Noisy,
+            _ => ArmorTrait::Other(source),
+        })
+    }
+}
+
+impl From<ArmorTrait> for String {
+    fn from(armor_trait: ArmorTrait) -> String {
+        armor_trait.to_string()
+    }
+}
+
+impl TryFrom<String> for ArmorTrait {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<ArmorTrait> {
+        source.parse()
+    }
+}
+
+/// Fundamental runes etched onto armor.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ArmorRunes {
+    potency: u32,
+    resilient: u32,
+}
+
+impl ArmorRunes {
+    /// Adds to the armor's item bonus to AC, from +1 to +3.
+    pub fn potency(&self) -> u32 {
+        self.potency
+    }
+
+    pub fn set_potency(&mut self, potency: u32) -> Result<()> {
+        if potency > 3 {
+            return Err(Error::InvalidRune(format!(
+                "there is no +{} armor potency rune",
+                potency
+            )));
+        }
+        self.potency = potency;
+        Ok(())
+    }
+
+    /// The item bonus to saves, from +1 to +3.
+    pub fn resilient(&self) -> u32 {
+        self.resilient
+    }
+
+    pub fn set_resilient(&mut self, resilient: u32) -> Result<()> {
+        if resilient > 3 {
+            return Err(Error::InvalidRune(format!(
+                "there is no +{} resilient rune",
+                resilient
+            )));
+        }
+        self.resilient = resilient;
+        Ok(())
+    }
+}
+
+/// Makes an item wearable as armor.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Armor {
+    category: ArmorCategory,
+    ac_bonus: u32,
+    /// The most Dexterity modifier that still counts towards AC, `None` for no limit.
+    dex_cap: Option<u32>,
+    check_penalty: u32,
+    speed_penalty: u32,
+    /// The Strength score that removes the check penalty and reduces the speed penalty.
+    strength: Option<u32>,
+    traits: Vec<ArmorTrait>,
+    runes: ArmorRunes,
+}
+
+impl Armor {
+    /// Armor without penalties, use the setters to add them.
+    pub fn new(category: ArmorCategory, ac_bonus: u32, dex_cap: Option<u32>) -> Armor {
+        Armor {
+            category,
+            ac_bonus,
+            dex_cap,
+            check_penalty: 0,
+            speed_penalty: 0,
+            strength: None,
+            traits: vec![],
+            runes: ArmorRunes::default(),
+        }
+    }
+
+    pub fn category(&self) -> ArmorCategory {
+        self.category
+    }
+
+    pub fn ac_bonus(&self) -> u32 {
+        self.ac_bonus
+    }
+
+    pub fn dex_cap(&self) -> Option<u32> {
+        self.dex_cap
+    }
+
+    /// The penalty to Strength- and Dexterity-based skill checks, as a positive number.
+    pub fn check_penalty(&self) -> u32 {
+        self.check_penalty
+    }
+
+    pub fn set_check_penalty(&mut self, penalty: u32) {
+        self.check_penalty = penalty;
+    }
+
+    /// The penalty to Speed in feet, as a positive number.
+    pub fn speed_penalty(&self) -> u32 {
+        self.speed_penalty
+    }
+
+    pub fn set_speed_penalty(&mut self, penalty: u32) {
+        self.speed_penalty = penalty;
+    }
+
+    pub fn strength(&self) -> Option<u32> {
+        self.strength
+    }
+
+    pub fn set_strength(&mut self, strength: Option<u32>) {
+        self.strength = strength;
+    }
+
+    pub fn traits(&self) -> &Vec<ArmorTrait> {
+        &self.traits
+    }
+
+    pub fn add_trait(&mut self, armor_trait: ArmorTrait) {
+        self.traits.push(armor_trait);
+    }
+
+    pub fn has_trait(&self, armor_trait: &ArmorTrait) -> bool {
+        self.traits.contains(armor_trait)
+    }
+
+    pub fn runes(&self) -> &ArmorRunes {
+        &self.runes
+    }
+
+    pub fn runes_mut(&mut self) -> &mut ArmorRunes {
+        &mut self.runes
+    }
+
+    /// The full item bonus to AC, including the potency rune.
+    pub fn item_bonus(&self) -> u32 {
+        self.ac_bonus + self.runes.potency
+    }
+
+    pub fn meets_strength(&self, strength: u32) -> bool {
+        self.strength.is_some_and(|required| strength >= required)
+    }
+
+    /// The check penalty for a wearer with the given Strength score. Meeting the threshold removes it.
+    pub fn check_penalty_for(&self, strength: u32) -> u32 {
+        if self.meets_strength(strength) {
+            0
+        } else {
+            self.check_penalty
+        }
+    }
+
+    /// The Speed penalty for a wearer with the given Strength score. Meeting the threshold reduces it by 5 feet.
+    pub fn speed_penalty_for(&self, strength: u32) -> u32 {
+        if self.meets_strength(strength) {
+            self.speed_penalty.saturating_sub(5)
+        } else {
+            self.speed_penalty
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strength_reduces_penalties() {
+        let mut full_plate = Armor::new(ArmorCategory::Heavy, 6, Some(0));
+        full_plate.set_check_penalty(3);
+        full_plate.set_speed_penalty(10);
+        full_plate.set_strength(Some(18));
+
+        assert_eq!(full_plate.check_penalty_for(16), 3);
+        assert_eq!(full_plate.speed_penalty_for(16), 10);
+        assert_eq!(full_plate.check_penalty_for(18), 0);
+        assert_eq!(full_plate.speed_penalty_for(18), 5);
+
+        full_plate.runes_mut().set_potency(2).unwrap();
+        assert_eq!(full_plate.item_bonus(), 8);
+        assert!(full_plate.runes_mut().set_resilient(4).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+/// How much light Bulk makes up one Bulk.
+const LIGHT_PER_BULK: u32 = 10;
+
+/// The weight and unwieldiness of items. Stored in units of light Bulk, ten of which make up one Bulk.
+#[derive(
+    Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+#[serde(into = "String", try_from = "String")]
+pub struct Bulk {
+    light: u32,
+}
+
+impl Bulk {
+    pub fn negligible() -> Bulk {
+        Bulk { light: 0 }
+    }
+
+    pub fn light() -> Bulk {
+        Bulk { light: 1 }
+    }
+
+    pub fn new(bulk: u32) -> Bulk {
+        Bulk {
+            light: bulk * LIGHT_PER_BULK,
+        }
+    }
+
+    /// Whole Bulk, ignoring leftover light items as the rules do.
+    pub fn bulk(&self) -> u32 {
+        self.light / LIGHT_PER_BULK
+    }
+
+    pub fn light_items(&self) -> u32 {
+        self.light % LIGHT_PER_BULK
+    }
+
+    pub fn is_negligible(&self) -> bool {
+        self.light == 0
+    }
+
+    pub fn saturating_sub(self, other: Bulk) -> Bulk {
+        Bulk {
+            light: self.light.saturating_sub(other.light),
+        }
+    }
+}
+
+impl Add for Bulk {
+    type Output = Bulk;
+
+    fn add(self, other: Bulk) -> Bulk {
+        Bulk {
+            light: self.light + other.light,
+        }
+    }
+}
+
+impl Mul<u32> for Bulk {
+    type Output = Bulk;
+
+    fn mul(self, quantity: u32) -> Bulk {
+        Bulk {
+            light: self.light * quantity,
+        }
+    }
+}
+
+impl Sum for Bulk {
+    fn sum<I: Iterator<Item = Bulk>>(iter: I) -> Bulk {
+        iter.fold(Bulk::negligible(), Add::add)
+    }
+}
+
+/// Written like on item stat blocks: `-` for negligible, `L` for light and numbers for Bulk, e.g. `2`, `3L` or `1; 2L`.
+impl fmt::Display for Bulk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.bulk(), self.light_items()) {
+            (0, 0) => write!(f, "-"),
+            (0, 1) => write!(f, "L"),
+            (0, light) => write!(f, "{}L", light),
+            (bulk, 0) => write!(f, "{}", bulk),
+            (bulk, 1) => write!(f, "{}; L", bulk),
+            (bulk, light) => write!(f, "{}; {}L", bulk, light),
+        }
+    }
+}
+
+impl FromStr for Bulk {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<Bulk> {
+        let invalid = || Error::InvalidFormat(format!("invalid Bulk {:?}", source));
+        let mut total = Bulk::negligible();
+        for part in source.split(';').map(str::trim) {
+            total = total
+                + match part {
+                    "-" | "—" | "" => Bulk::negligible(),
+                    "L" | "l" => Bulk::light(),
+                    _ => match part.strip_suffix('L').or_else(|| part.strip_suffix('l')) {
+                        Some(light) => Bulk {
+                            light: light.trim().parse().map_err(|_| invalid())?,
+                        },
+                        None => Bulk::new(part.parse().map_err(|_| invalid())?),
+                    },
+                };
+        }
+        Ok(total)
+    }
+}
+
+impl From<Bulk> for String {
+    fn from(bulk: Bulk) -> String {
+        bulk.to_string()
+    }
+}
+
+impl TryFrom<String> for Bulk {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<Bulk> {
+        source.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_items_add_up() {
+        let total: Bulk = vec![Bulk::light(); 12].into_iter().sum();
+
+        assert_eq!(total.bulk(), 1);
+        assert_eq!(total.light_items(), 2);
+        assert_eq!(total.to_string(), "1; 2L");
+    }
+
+    #[test]
+    fn parse_roundtrip() {
+        for source in ["-", "L", "3L", "2", "1; L", "1; 2L"].iter() {
+            assert_eq!(&source.parse::<Bulk>().unwrap().to_string(), source);
+        }
+        assert!("heavy".parse::<Bulk>().is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::character::{Character, Modifier};
+use crate::dice::{DiceExpression, DiceRng};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+/// What happens when a consumable is used.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ConsumableEffect {
+    /// Heals the rolled amount, e.g. `1d8` for a minor healing potion.
+    Heal(DiceExpression),
+    /// Grants the rolled amount of temporary HP.
+    TemporaryHp(DiceExpression),
+    /// Adds a modifier to the target, e.g. an item bonus from an elixir.
+    Modifier(Modifier),
+    /// Casts the spell at the given rank, like a scroll.
+    CastSpell { spell: String, rank: u32 },
+}
+
+/// The result of using a consumable on a target.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ConsumableOutcome {
+    /// The amount healed, which can be less than rolled if the target was close to their maximum HP.
+    Healed(u32),
+    TemporaryHp(u32),
+    ModifierAdded(Modifier),
+    /// The spell that was cast, resolving it is up to the caller.
+    SpellCast {
+        spell: String,
+        rank: u32,
+    },
+}
+
+/// Makes an item single-use: each use takes one of it away.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Consumable {
+    actions: u32,
+    effect: ConsumableEffect,
+}
+
+impl Consumable {
+    pub fn new(actions: u32, effect: ConsumableEffect) -> Consumable {
+        Consumable { actions, effect }
+    }
+
+    /// A scroll of the given spell.
+    pub fn scroll(spell: &str, rank: u32, actions: u32) -> Consumable {
+        Consumable::new(
+            actions,
+            ConsumableEffect::CastSpell {
+                spell: spell.to_string(),
+                rank,
+            },
+        )
+    }
+
+    /// The actions it takes to use, e.g. 1 to drink a potion.
+    pub fn actions(&self) -> u32 {
+        self.actions
+    }
+
+    pub fn effect(&self) -> &ConsumableEffect {
+        &self.effect
+    }
+
+    pub fn apply<R: DiceRng + ?Sized>(
+        &self,
+        target: &mut Character,
+        rng: &mut R,
+    ) -> Result<ConsumableOutcome> {
+        Ok(match &self.effect {
+            ConsumableEffect::Heal(expression) => {
+                let amount = expression.roll(rng)?.total().max(0) as u32;
+                let before = target.hp().current();
+                let after = target.hp_mut().heal(amount);
+                ConsumableOutcome::Healed(after - before)
+            }
+            ConsumableEffect::TemporaryHp(expression) => {
+                let amount = expression.roll(rng)?.total().max(0) as u32;
+                target.hp_mut().set_temp(amount);
+                ConsumableOutcome::TemporaryHp(amount)
+            }
+            ConsumableEffect::Modifier(modifier) => {
+                target.add_modifier(modifier.clone());
+                ConsumableOutcome::ModifierAdded(modifier.clone())
+            }
+            ConsumableEffect::CastSpell { spell, rank } => ConsumableOutcome::SpellCast {
+                spell: spell.clone(),
+                rank: *rank,
+            },
+        })
+    }
+}
+
+/// What happened to a wand when a spell was cast from it. The spell is cast in every case.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum WandOutcome {
+    Cast,
+    /// Overcharging succeeded, the wand is broken until repaired.
+    Broken,
+    /// Overcharging failed and the wand is gone.
+    Destroyed,
+}
+
+/// Holds a spell that can be cast a number of times per day and recharges during daily preparations.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Wand {
+    spell: String,
+    rank: u32,
+    actions: u32,
+    charges: u32,
+    used: u32,
+    broken: bool,
+    overcharged_today: bool,
+}
+
+impl Wand {
+    /// A wand that can cast its spell once per day.
+    pub fn new(spell: &str, rank: u32, actions: u32) -> Wand {
+        Wand {
+            spell: spell.to_string(),
+            rank,
+            actions,
+            charges: 1,
+            used: 0,
+            broken: false,
+            overcharged_today: false,
+        }
+    }
+
+    pub fn spell(&self) -> &str {
+        &self.spell
+    }
+
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    pub fn actions(&self) -> u32 {
+        self.actions
+    }
+
+    pub fn charges(&self) -> u32 {
+        self.charges
+    }
+
+    pub fn set_charges(&mut self, charges: u32) {
+        self.charges = charges;
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.charges.saturating_sub(self.used)
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Whether the wand was overcharged since it was last recharged. Overcharging it again destroys it.
+    pub fn overcharged_today(&self) -> bool {
+        self.overcharged_today
+    }
+
+    pub fn repair(&mut self) {
+        self.broken = false;
+    }
+
+    pub(crate) fn use_charge(&mut self) {
+        self.used += 1;
+    }
+
+    pub(crate) fn set_broken(&mut self) {
+        self.broken = true;
+        self.overcharged_today = true;
+    }
+
+    pub(crate) fn recharge(&mut self) {
+        self.used = 0;
+        self.overcharged_today = false;
+    }
+}
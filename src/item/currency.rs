@@ -0,0 +1,349 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Coin {
+    Copper,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+impl Coin {
+    /// From the most to the least valuable.
+    pub fn all() -> [Coin; 4] {
+        [Coin::Platinum, Coin::Gold, Coin::Silver, Coin::Copper]
+    }
+
+    /// The value in copper pieces.
+    pub fn value(self) -> u64 {
+        match self {
+            Coin::Copper => 1,
+            Coin::Silver => 10,
+            Coin::Gold => 100,
+            Coin::Platinum => 1000,
+        }
+    }
+
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Coin::Copper => "cp",
+            Coin::Silver => "sp",
+            Coin::Gold => "gp",
+            Coin::Platinum => "pp",
+        }
+    }
+
+    pub fn from_abbreviation(abbreviation: &str) -> Option<Coin> {
+        Coin::all()
+            .iter()
+            .copied()
+            .find(|coin| coin.abbreviation().eq_ignore_ascii_case(abbreviation))
+    }
+}
+
+/// An amount of money, stored in copper pieces.
+#[derive(
+    Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+#[serde(into = "String", try_from = "String")]
+pub struct Price {
+    copper: u64,
+}
+
+impl Price {
+    pub fn new(count: u64, coin: Coin) -> Price {
+        Price {
+            copper: count * coin.value(),
+        }
+    }
+
+    pub fn free() -> Price {
+        Price { copper: 0 }
+    }
+
+    pub fn cp(count: u64) -> Price {
+        Price::new(count, Coin::Copper)
+    }
+
+    pub fn sp(count: u64) -> Price {
+        Price::new(count, Coin::Silver)
+    }
+
+    pub fn gp(count: u64) -> Price {
+        Price::new(count, Coin::Gold)
+    }
+
+    pub fn pp(count: u64) -> Price {
+        Price::new(count, Coin::Platinum)
+    }
+
+    pub fn copper(&self) -> u64 {
+        self.copper
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.copper == 0
+    }
+
+    /// Half the price, rounded down, which is what items sell for.
+    pub fn half(self) -> Price {
+        Price {
+            copper: self.copper / 2,
+        }
+    }
+
+    pub fn checked_sub(self, other: Price) -> Option<Price> {
+        self.copper
+            .checked_sub(other.copper)
+            .map(|copper| Price { copper })
+    }
+
+    /// Splits the amount into as few gold, silver and copper pieces as possible. Platinum is only handed out on
+    /// purpose, so it's left out.
+    fn coins(self) -> [(Coin, u64); 3] {
+        [
+            (Coin::Gold, self.copper / 100),
+            (Coin::Silver, self.copper / 10 % 10),
+            (Coin::Copper, self.copper % 10),
+        ]
+    }
+}
+
+impl Add for Price {
+    type Output = Price;
+
+    fn add(self, other: Price) -> Price {
+        Price {
+            copper: self.copper + other.copper,
+        }
+    }
+}
+
+impl Mul<u32> for Price {
+    type Output = Price;
+
+    fn mul(self, quantity: u32) -> Price {
+        Price {
+            copper: self.copper * quantity as u64,
+        }
+    }
+}
+
+impl Sum for Price {
+    fn sum<I: Iterator<Item = Price>>(iter: I) -> Price {
+        iter.fold(Price::free(), Add::add)
+    }
+}
+
+/// Written like on item stat blocks, e.g. `15 gp`, `1 gp 5 sp` or `0 cp` for free items.
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_free() {
+            return write!(f, "0 cp");
+        }
+        let parts: Vec<String> = self
+            .coins()
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(coin, count)| format!("{} {}", count, coin.abbreviation()))
+            .collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+impl FromStr for Price {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<Price> {
+        let invalid = || Error::InvalidFormat(format!("invalid price {:?}", source));
+        let mut total = Price::free();
+        let mut rest = source.trim();
+        if rest.is_empty() || rest == "-" {
+            return Ok(total);
+        }
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let count: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+            rest = rest[digits..].trim_start();
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let coin = Coin::from_abbreviation(&rest[..end]).ok_or_else(invalid)?;
+            total = total + Price::new(count, coin);
+            rest = rest[end..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        }
+        Ok(total)
+    }
+}
+
+impl From<Price> for String {
+    fn from(price: Price) -> String {
+        price.to_string()
+    }
+}
+
+impl TryFrom<String> for Price {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<Price> {
+        source.parse()
+    }
+}
+
+/// Gold a character built at the given level starts with instead of picking items.
+pub fn starting_wealth(level: u32) -> Price {
+    const WEALTH: [u64; 20] = [
+        15, 30, 75, 140, 270, 450, 720, 1100, 1600, 2300, 3200, 4500, 6400, 9300, 13500, 20000,
+        30000, 45000, 69000, 112000,
+    ];
+    Price::gp(WEALTH[level.clamp(1, 20) as usize - 1])
+}
+
+/// The coins a character carries.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Purse {
+    cp: u64,
+    sp: u64,
+    gp: u64,
+    pp: u64,
+}
+
+impl Purse {
+    pub fn new() -> Purse {
+        Purse::default()
+    }
+
+    pub fn count(&self, coin: Coin) -> u64 {
+        match coin {
+            Coin::Copper => self.cp,
+            Coin::Silver => self.sp,
+            Coin::Gold => self.gp,
+            Coin::Platinum => self.pp,
+        }
+    }
+
+    fn count_mut(&mut self, coin: Coin) -> &mut u64 {
+        match coin {
+            Coin::Copper => &mut self.cp,
+            Coin::Silver => &mut self.sp,
+            Coin::Gold => &mut self.gp,
+            Coin::Platinum => &mut self.pp,
+        }
+    }
+
+    pub fn add_coins(&mut self, coin: Coin, count: u64) {
+        *self.count_mut(coin) += count;
+    }
+
+    /// Adds an amount in as few gold, silver and copper pieces as possible.
+    pub fn receive(&mut self, amount: Price) {
+        for (coin, count) in amount.coins().iter() {
+            self.add_coins(*coin, *count);
+        }
+    }
+
+    pub fn total(&self) -> Price {
+        Coin::all()
+            .iter()
+            .map(|coin| Price::new(self.count(*coin), *coin))
+            .sum()
+    }
+
+    pub fn can_afford(&self, price: Price) -> bool {
+        self.total() >= price
+    }
+
+    /// Pays with the most valuable coins that fit. If those don't add up to the price, the smallest coin left covers
+    /// the rest and the change comes back.
+    pub fn pay(&mut self, price: Price) -> Result<()> {
+        if !self.can_afford(price) {
+            return Err(Error::InsufficientFunds {
+                price,
+                available: self.total(),
+            });
+        }
+        let mut remaining = price.copper();
+        for coin in Coin::all().iter() {
+            let used = self.count(*coin).min(remaining / coin.value());
+            *self.count_mut(*coin) -= used;
+            remaining -= used * coin.value();
+        }
+        if remaining > 0 {
+            // every coin left is worth more than the rest, otherwise it would have been used above
+            let coin = *Coin::all()
+                .iter()
+                .rev()
+                .find(|coin| self.count(**coin) > 0)
+                .expect("the purse holds enough money");
+            *self.count_mut(coin) -= 1;
+            self.receive(Price::cp(coin.value() - remaining));
+        }
+        Ok(())
+    }
+}
+
+/// Shows the coins, e.g. `2 pp 15 gp 3 sp`.
+impl fmt::Display for Purse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = Coin::all()
+            .iter()
+            .filter(|coin| self.count(**coin) > 0)
+            .map(|coin| format!("{} {}", self.count(*coin), coin.abbreviation()))
+            .collect();
+        if parts.is_empty() {
+            write!(f, "0 cp")
+        } else {
+            write!(f, "{}", parts.join(" "))
+        }
+    }
+}
+
+impl From<Price> for Purse {
+    fn from(amount: Price) -> Purse {
+        let mut purse = Purse::new();
+        purse.receive(amount);
+        purse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_prices() {
+        assert_eq!("15 gp".parse::<Price>().unwrap(), Price::gp(15));
+        assert_eq!("2pp, 1 gp 5 sp".parse::<Price>().unwrap(), Price::cp(2150));
+        assert_eq!(Price::cp(2150).to_string(), "21 gp 5 sp");
+        assert!("5 doubloons".parse::<Price>().is_err());
+    }
+
+    #[test]
+    fn pay_makes_change() {
+        let mut purse = Purse::new();
+        purse.add_coins(Coin::Platinum, 1);
+        purse.add_coins(Coin::Silver, 3);
+
+        purse.pay(Price::sp(5)).unwrap();
+
+        // the 3 sp aren't enough, so the platinum piece goes too and 9 gp 8 sp come back
+        assert_eq!(purse.total(), Price::cp(980));
+        assert_eq!(purse.count(Coin::Platinum), 0);
+        assert_eq!(purse.count(Coin::Gold), 9);
+        assert_eq!(purse.count(Coin::Silver), 8);
+
+        assert!(matches!(
+            purse.pay(Price::gp(10)),
+            Err(Error::InsufficientFunds { .. })
+        ));
+        assert_eq!(purse.total(), Price::cp(980));
+    }
+}
@@ -0,0 +1,527 @@
+use crate::dice::DiceRng;
+use crate::error::{Error, Result};
+use crate::item::{Activation, Bulk, Consumable, Item, WandOutcome};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The most items a character can have invested at once.
+pub const MAX_INVESTED: usize = 10;
+
+/// Identifies an item within one inventory. Ids are never reused.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct ItemId(u32);
+
+impl fmt::Display for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Where a carried item is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ItemState {
+    Worn,
+    Held,
+    /// Inside the given container.
+    Stowed(ItemId),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    id: ItemId,
+    item: Item,
+    quantity: u32,
+    state: ItemState,
+    invested: bool,
+}
+
+impl InventoryEntry {
+    pub fn id(&self) -> ItemId {
+        self.id
+    }
+
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    pub fn state(&self) -> ItemState {
+        self.state
+    }
+
+    pub fn invested(&self) -> bool {
+        self.invested
+    }
+
+    /// Whether the item's effects and activations work: it has to be worn or held, and invested if it needs to be.
+    pub fn is_active(&self) -> bool {
+        !matches!(self.state, ItemState::Stowed(_))
+            && (self.invested || !self.item.requires_investment())
+    }
+}
+
+/// Everything a character carries.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    next_id: u32,
+    entries: Vec<InventoryEntry>,
+}
+
+impl Inventory {
+    pub fn new() -> Inventory {
+        Inventory::default()
+    }
+
+    pub fn entries(&self) -> &Vec<InventoryEntry> {
+        &self.entries
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<&InventoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub(crate) fn entry(&self, id: ItemId) -> Result<&InventoryEntry> {
+        self.get(id).ok_or(Error::NotFound {
+            kind: "item",
+            id: id.0 as i64,
+        })
+    }
+
+    fn entry_mut(&mut self, id: ItemId) -> Result<&mut InventoryEntry> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.id == id)
+            .ok_or(Error::NotFound {
+                kind: "item",
+                id: id.0 as i64,
+            })
+    }
+
+    /// The items directly inside a container.
+    pub fn contents(&self, container: ItemId) -> Vec<&InventoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.state == ItemState::Stowed(container))
+            .collect()
+    }
+
+    pub fn add(&mut self, item: Item, quantity: u32, state: ItemState) -> Result<ItemId> {
+        if quantity == 0 {
+            return Err(Error::ZeroQuantity(item.name().to_string()));
+        }
+        self.check_space(state, item.bulk() * quantity, None)?;
+        let id = ItemId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(InventoryEntry {
+            id,
+            item,
+            quantity,
+            state,
+            invested: false,
+        });
+        Ok(id)
+    }
+
+    pub fn move_item(&mut self, id: ItemId, state: ItemState) -> Result<()> {
+        let bulk = self.carried_bulk(self.entry(id)?);
+        self.check_space(state, bulk, Some(id))?;
+        self.entry_mut(id)?.state = state;
+        Ok(())
+    }
+
+    /// Changes how many of an item are carried. Setting the quantity to 0 removes the item.
+    pub fn set_quantity(&mut self, id: ItemId, quantity: u32) -> Result<()> {
+        if quantity == 0 {
+            return self.remove(id).map(|_| ());
+        }
+        let entry = self.entry(id)?;
+        let contents = self
+            .carried_bulk(entry)
+            .saturating_sub(entry.item.bulk() * entry.quantity);
+        let bulk = entry.item.bulk() * quantity + contents;
+        self.check_space(entry.state, bulk, Some(id))?;
+        self.entry_mut(id)?.quantity = quantity;
+        Ok(())
+    }
+
+    /// Removes an item together with everything inside it and returns the removed entries.
+    pub fn remove(&mut self, id: ItemId) -> Result<Vec<InventoryEntry>> {
+        self.entry(id)?;
+        let mut removed_ids = vec![id];
+        let mut i = 0;
+        while i < removed_ids.len() {
+            let container = removed_ids[i];
+            removed_ids.extend(self.contents(container).iter().map(|entry| entry.id));
+            i += 1;
+        }
+        let (removed, kept) = self
+            .entries
+            .drain(..)
+            .partition(|entry| removed_ids.contains(&entry.id));
+        self.entries = kept;
+        Ok(removed)
+    }
+
+    /// Invests an item with the invested trait. At most `MAX_INVESTED` items can be invested at once.
+    pub fn invest(&mut self, id: ItemId) -> Result<()> {
+        let entry = self.entry(id)?;
+        if !entry.item.requires_investment() {
+            return Err(Error::NotInvestable { id });
+        }
+        if entry.invested {
+            return Ok(());
+        }
+        if self.invested().count() >= MAX_INVESTED {
+            return Err(Error::InvestmentLimit {
+                limit: MAX_INVESTED,
+            });
+        }
+        self.entry_mut(id)?.invested = true;
+        Ok(())
+    }
+
+    pub fn divest(&mut self, id: ItemId) -> Result<()> {
+        self.entry_mut(id)?.invested = false;
+        Ok(())
+    }
+
+    pub fn invested(&self) -> impl Iterator<Item = &InventoryEntry> {
+        self.entries.iter().filter(|entry| entry.invested)
+    }
+
+    /// Uses one of an item's activations, counting it towards its frequency.
+    pub fn activate(&mut self, id: ItemId, activation: &str) -> Result<&Activation> {
+        if !self.entry(id)?.is_active() {
+            return Err(Error::ItemNotActive { id });
+        }
+        let activation = self
+            .entry_mut(id)?
+            .item
+            .activations_mut()
+            .iter_mut()
+            .find(|a| a.name().eq_ignore_ascii_case(activation))
+            .ok_or_else(|| Error::UnknownActivation(activation.to_string()))?;
+        if !activation.use_once() {
+            return Err(Error::ActivationExhausted(activation.name().to_string()));
+        }
+        Ok(activation)
+    }
+
+    pub fn consumable(&self, id: ItemId) -> Result<&Consumable> {
+        self.entry(id)?
+            .item
+            .consumable()
+            .ok_or(Error::NotConsumable { id })
+    }
+
+    /// Uses up one of a consumable.
+    pub fn consume(&mut self, id: ItemId) -> Result<()> {
+        self.consumable(id)?;
+        let quantity = self.entry(id)?.quantity;
+        self.set_quantity(id, quantity - 1)
+    }
+
+    /// Casts the spell of a wand. Once its charges are used up it can only be overcharged: on a successful DC 10 flat
+    /// check the wand breaks, otherwise it's destroyed. A wand that was already overcharged today is always destroyed.
+    pub fn use_wand<R: DiceRng + ?Sized>(
+        &mut self,
+        id: ItemId,
+        overcharge: bool,
+        rng: &mut R,
+    ) -> Result<WandOutcome> {
+        let wand = self
+            .entry_mut(id)?
+            .item
+            .wand_mut()
+            .ok_or(Error::NotAWand { id })?;
+        if wand.is_broken() {
+            return Err(Error::WandBroken { id });
+        }
+        if wand.remaining() > 0 {
+            wand.use_charge();
+            return Ok(WandOutcome::Cast);
+        }
+        if !overcharge {
+            return Err(Error::NoCharges { id });
+        }
+        if !wand.overcharged_today() && rng.roll_die(20) >= 10 {
+            wand.set_broken();
+            Ok(WandOutcome::Broken)
+        } else {
+            self.remove(id)?;
+            Ok(WandOutcome::Destroyed)
+        }
+    }
+
+    /// Makes every activation available again and recharges wands, as happens during daily preparations.
+    pub fn reset_daily(&mut self) {
+        for entry in self.entries.iter_mut() {
+            for activation in entry.item.activations_mut() {
+                activation.reset();
+            }
+            if let Some(wand) = entry.item.wand_mut() {
+                wand.recharge();
+            }
+        }
+    }
+
+    /// The Bulk an item adds to whoever carries it, including its contents.
+    pub fn carried_bulk(&self, entry: &InventoryEntry) -> Bulk {
+        let own = entry.item.bulk() * entry.quantity;
+        match entry.item.container() {
+            Some(container) => {
+                own + self
+                    .content_bulk(entry.id)
+                    .saturating_sub(container.ignored())
+            }
+            None => own,
+        }
+    }
+
+    /// The Bulk of everything inside a container, before the container reduces it.
+    pub fn content_bulk(&self, container: ItemId) -> Bulk {
+        self.contents(container)
+            .into_iter()
+            .map(|entry| self.carried_bulk(entry))
+            .sum()
+    }
+
+    /// The Bulk of all worn and held items and their contents.
+    pub fn total_bulk(&self) -> Bulk {
+        self.entries
+            .iter()
+            .filter(|entry| !matches!(entry.state, ItemState::Stowed(_)))
+            .map(|entry| self.carried_bulk(entry))
+            .sum()
+    }
+
+    /// Makes sure `bulk` more fits where `state` points to. `moving` is the item being moved, it can't end up inside
+    /// itself.
+    fn check_space(&self, state: ItemState, bulk: Bulk, moving: Option<ItemId>) -> Result<()> {
+        let container = match state {
+            ItemState::Stowed(container) => container,
+            _ => return Ok(()),
+        };
+        let entry = self.entry(container)?;
+        let capacity = entry
+            .item
+            .container()
+            .ok_or(Error::NotAContainer { id: container })?
+            .capacity();
+
+        let mut parent = Some(container);
+        while let Some(id) = parent {
+            if Some(id) == moving {
+                return Err(Error::ItemInsideItself { id });
+            }
+            parent = match self.entry(id)?.state {
+                ItemState::Stowed(outer) => Some(outer),
+                _ => None,
+            };
+        }
+
+        let already = match moving {
+            Some(id) if self.entry(id)?.state == state => self
+                .contents(container)
+                .into_iter()
+                .filter(|entry| entry.id != id)
+                .map(|entry| self.carried_bulk(entry))
+                .sum(),
+            _ => self.content_bulk(container),
+        };
+        if already + bulk > capacity {
+            return Err(Error::ContainerFull {
+                id: container,
+                capacity,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::{Container, Frequency, Wand};
+
+    fn backpack() -> Item {
+        let mut backpack = Item::new("Backpack", 0, Bulk::light());
+        backpack.set_container(Some(Container::new(Bulk::new(4), Bulk::new(2))));
+        backpack
+    }
+
+    #[test]
+    fn containers_reduce_bulk() {
+        let mut inventory = Inventory::new();
+        let pack = inventory.add(backpack(), 1, ItemState::Worn).unwrap();
+        inventory
+            .add(
+                Item::new("Bedroll", 0, Bulk::light()),
+                1,
+                ItemState::Stowed(pack),
+            )
+            .unwrap();
+        inventory
+            .add(
+                Item::new("Rations", 0, Bulk::light()),
+                10,
+                ItemState::Stowed(pack),
+            )
+            .unwrap();
+        inventory
+            .add(Item::new("Longsword", 0, Bulk::new(1)), 1, ItemState::Held)
+            .unwrap();
+
+        // the backpack ignores the first 2 Bulk of its contents
+        assert_eq!(inventory.total_bulk(), Bulk::new(1) + Bulk::light());
+
+        let torches = inventory
+            .add(
+                Item::new("Torch", 0, Bulk::light()),
+                10,
+                ItemState::Stowed(pack),
+            )
+            .unwrap();
+        assert_eq!(inventory.total_bulk(), Bulk::new(1) + Bulk::light() * 2);
+
+        assert!(matches!(
+            inventory.set_quantity(torches, 30),
+            Err(Error::ContainerFull { .. })
+        ));
+    }
+
+    #[test]
+    fn items_cant_be_stowed_in_themselves() {
+        let mut inventory = Inventory::new();
+        let outer = inventory.add(backpack(), 1, ItemState::Worn).unwrap();
+        let inner = inventory
+            .add(backpack(), 1, ItemState::Stowed(outer))
+            .unwrap();
+        let sword = inventory
+            .add(Item::new("Sword", 0, Bulk::new(1)), 1, ItemState::Held)
+            .unwrap();
+
+        assert!(matches!(
+            inventory.move_item(outer, ItemState::Stowed(inner)),
+            Err(Error::ItemInsideItself { .. })
+        ));
+        assert!(matches!(
+            inventory.move_item(inner, ItemState::Stowed(sword)),
+            Err(Error::NotAContainer { .. })
+        ));
+
+        assert_eq!(inventory.remove(outer).unwrap().len(), 2);
+        assert_eq!(inventory.entries().len(), 1);
+    }
+
+    #[test]
+    fn investment_and_activations() {
+        let mut inventory = Inventory::new();
+        let mut ring = Item::new("Ring", 1, Bulk::negligible());
+        ring.add_trait("invested");
+        ring.add_activation(Activation::new("Shine", 1, Frequency::PerDay(1)));
+        let ids: Vec<ItemId> = (0..=MAX_INVESTED)
+            .map(|_| inventory.add(ring.clone(), 1, ItemState::Worn).unwrap())
+            .collect();
+        let rope = inventory
+            .add(Item::new("Rope", 0, Bulk::light()), 1, ItemState::Held)
+            .unwrap();
+
+        assert!(matches!(
+            inventory.activate(ids[0], "Shine"),
+            Err(Error::ItemNotActive { .. })
+        ));
+        for id in &ids[..MAX_INVESTED] {
+            inventory.invest(*id).unwrap();
+        }
+        assert!(matches!(
+            inventory.invest(ids[MAX_INVESTED]),
+            Err(Error::InvestmentLimit { .. })
+        ));
+        assert!(matches!(
+            inventory.invest(rope),
+            Err(Error::NotInvestable { .. })
+        ));
+
+        inventory.activate(ids[0], "shine").unwrap();
+        assert!(matches!(
+            inventory.activate(ids[0], "Shine"),
+            Err(Error::ActivationExhausted(_))
+        ));
+        inventory.reset_daily();
+        assert_eq!(
+            inventory.activate(ids[0], "Shine").unwrap().remaining(),
+            Some(0)
+        );
+    }
+
+    /// Always draws the same number.
+    struct Fixed(u64);
+
+    impl DiceRng for Fixed {
+        fn next_u64(&mut self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn wands_overcharge() {
+        let mut inventory = Inventory::new();
+        let mut wand = Item::new("Wand of Heal", 3, Bulk::light());
+        wand.set_wand(Some(Wand::new("Heal", 1, 2)));
+        let first = inventory.add(wand.clone(), 1, ItemState::Held).unwrap();
+        let second = inventory.add(wand, 1, ItemState::Held).unwrap();
+
+        assert_eq!(
+            inventory.use_wand(first, false, &mut Fixed(0)).unwrap(),
+            WandOutcome::Cast
+        );
+        assert!(matches!(
+            inventory.use_wand(first, false, &mut Fixed(0)),
+            Err(Error::NoCharges { .. })
+        ));
+        // a 10 on the flat check breaks the wand
+        assert_eq!(
+            inventory.use_wand(first, true, &mut Fixed(9)).unwrap(),
+            WandOutcome::Broken
+        );
+        inventory.reset_daily();
+        assert!(matches!(
+            inventory.use_wand(first, false, &mut Fixed(0)),
+            Err(Error::WandBroken { .. })
+        ));
+
+        inventory.use_wand(second, false, &mut Fixed(0)).unwrap();
+        assert_eq!(
+            inventory.use_wand(second, true, &mut Fixed(0)).unwrap(),
+            WandOutcome::Destroyed
+        );
+        assert!(inventory.get(second).is_none());
+
+        // repairing doesn't allow a second overcharge on the same day
+        let mut wand = Item::new("Wand of Heal", 3, Bulk::light());
+        wand.set_wand(Some(Wand::new("Heal", 1, 2)));
+        let third = inventory.add(wand, 1, ItemState::Held).unwrap();
+        inventory.use_wand(third, false, &mut Fixed(0)).unwrap();
+        inventory.use_wand(third, true, &mut Fixed(9)).unwrap();
+        let wand = inventory.entry_mut(third).unwrap().item.wand_mut().unwrap();
+        wand.repair();
+        assert_eq!(
+            inventory.use_wand(third, true, &mut Fixed(9)).unwrap(),
+            WandOutcome::Destroyed
+        );
+    }
+
+    #[test]
+    fn items_are_added_at_least_once() {
+        let mut inventory = Inventory::new();
+        assert!(matches!(
+            inventory.add(Item::new("Torch", 0, Bulk::light()), 0, ItemState::Held),
+            Err(Error::ZeroQuantity(_))
+        ));
+        assert!(inventory.entries().is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+use crate::character::Modifier;
+use crate::content::Rarity;
+use crate::item::{Activation, Armor, Bulk, Consumable, Price, Wand, Weapon};
+use serde::{Deserialize, Serialize};
+
+/// Lets an item hold other items, like a backpack or a bag of holding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+    capacity: Bulk,
+    ignored: Bulk,
+}
+
+impl Container {
+    /// `ignored` is how much of the contents doesn't count towards the carrier's Bulk, e.g. 2 for a backpack and the
+    /// full capacity for a bag of holding.
+    pub fn new(capacity: Bulk, ignored: Bulk) -> Container {
+        Container { capacity, ignored }
+    }
+
+    pub fn capacity(&self) -> Bulk {
+        self.capacity
+    }
+
+    pub fn ignored(&self) -> Bulk {
+        self.ignored
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    name: String,
+    description: String,
+    level: u32,
+    price: Price,
+    bulk: Bulk,
+    traits: Vec<String>,
+    rarity: Rarity,
+    /// What grants access to the item if it isn't common.
+    access: Option<String>,
+    container: Option<Container>,
+    weapon: Option<Weapon>,
+    armor: Option<Armor>,
+    /// Bonuses and penalties the item grants while it's worn or held, and invested if it needs to be.
+    effects: Vec<Modifier>,
+    activations: Vec<Activation>,
+    consumable: Option<Consumable>,
+    wand: Option<Wand>,
+}
+
+impl Item {
+    pub fn new(name: &str, level: u32, bulk: Bulk) -> Item {
+        Item {
+            name: name.to_string(),
+            description: "".to_string(),
+            level,
+            price: Price::free(),
+            bulk,
+            traits: vec![],
+            rarity: Rarity::Common,
+            access: None,
+            container: None,
+            weapon: None,
+            armor: None,
+            effects: vec![],
+            activations: vec![],
+            consumable: None,
+            wand: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn set_description(&mut self, description: &str) {
+        self.description = description.to_string();
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn price(&self) -> Price {
+        self.price
+    }
+
+    pub fn set_price(&mut self, price: Price) {
+        self.price = price;
+    }
+
+    pub fn bulk(&self) -> Bulk {
+        self.bulk
+    }
+
+    pub fn traits(&self) -> &Vec<String> {
+        &self.traits
+    }
+
+    pub fn add_trait(&mut self, name: &str) {
+        self.traits.push(name.to_string());
+    }
+
+    pub fn has_trait(&self, name: &str) -> bool {
+        self.traits.iter().any(|t| t.eq_ignore_ascii_case(name))
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        self.rarity
+    }
+
+    pub fn set_rarity(&mut self, rarity: Rarity) {
+        self.rarity = rarity;
+    }
+
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+
+    pub fn set_access(&mut self, access: Option<&str>) {
+        self.access = access.map(str::to_string);
+    }
+
+    /// Items with the invested trait only work while invested.
+    pub fn requires_investment(&self) -> bool {
+        self.has_trait("invested")
+    }
+
+    pub fn container(&self) -> Option<&Container> {
+        self.container.as_ref()
+    }
+
+    pub fn set_container(&mut self, container: Option<Container>) {
+        self.container = container;
+    }
+
+    pub fn weapon(&self) -> Option<&Weapon> {
+        self.weapon.as_ref()
+    }
+
+    pub fn weapon_mut(&mut self) -> Option<&mut Weapon> {
+        self.weapon.as_mut()
+    }
+
+    pub fn set_weapon(&mut self, weapon: Option<Weapon>) {
+        self.weapon = weapon;
+    }
+
+    pub fn armor(&self) -> Option<&Armor> {
+        self.armor.as_ref()
+    }
+
+    pub fn armor_mut(&mut self) -> Option<&mut Armor> {
+        self.armor.as_mut()
+    }
+
+    pub fn set_armor(&mut self, armor: Option<Armor>) {
+        self.armor = armor;
+    }
+
+    pub fn effects(&self) -> &Vec<Modifier> {
+        &self.effects
+    }
+
+    pub fn add_effect(&mut self, effect: Modifier) {
+        self.effects.push(effect);
+    }
+
+    pub fn activations(&self) -> &Vec<Activation> {
+        &self.activations
+    }
+
+    pub fn add_activation(&mut self, activation: Activation) {
+        self.activations.push(activation);
+    }
+
+    pub(crate) fn activations_mut(&mut self) -> &mut Vec<Activation> {
+        &mut self.activations
+    }
+
+    pub fn consumable(&self) -> Option<&Consumable> {
+        self.consumable.as_ref()
+    }
+
+    pub fn set_consumable(&mut self, consumable: Option<Consumable>) {
+        self.consumable = consumable;
+    }
+
+    pub fn wand(&self) -> Option<&Wand> {
+        self.wand.as_ref()
+    }
+
+    pub fn wand_mut(&mut self) -> Option<&mut Wand> {
+        self.wand.as_mut()
+    }
+
+    pub fn set_wand(&mut self, wand: Option<Wand>) {
+        self.wand = wand;
+    }
+}
@@ -0,0 +1,22 @@
+mod activation;
+mod armor;
+mod bulk;
+mod consumable;
+mod currency;
+mod inventory;
+#[allow(clippy::module_inception)]
+mod item;
+mod transaction;
+mod treasure;
+mod weapon;
+
+pub use activation::{Activation, Frequency};
+pub use armor::{Armor, ArmorCategory, ArmorRunes, ArmorTrait};
+pub use bulk::Bulk;
+pub use consumable::{Consumable, ConsumableEffect, ConsumableOutcome, Wand, WandOutcome};
+pub use currency::{starting_wealth, Coin, Price, Purse};
+pub use inventory::{Inventory, InventoryEntry, ItemId, ItemState, MAX_INVESTED};
+pub use item::{Container, Item};
+pub use transaction::{Transaction, TransactionKind};
+pub use treasure::{Treasure, TreasureItem, TreasureKind};
+pub use weapon::{PropertyRune, Weapon, WeaponCategory, WeaponGroup, WeaponRunes, WeaponTrait};
@@ -0,0 +1,65 @@
+use crate::item::Price;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TransactionKind {
+    Buy,
+    Sell,
+    /// Paying for raw materials or finishing a Crafting project.
+    Craft,
+    /// Coins earned with Earn Income.
+    Income,
+}
+
+/// A completed change to a character's wealth, e.g. a purchase or sale.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    kind: TransactionKind,
+    item: String,
+    quantity: u32,
+    /// What changed hands for all of the items together.
+    price: Price,
+    timestamp: u64,
+}
+
+impl Transaction {
+    pub(crate) fn new(
+        kind: TransactionKind,
+        item: &str,
+        quantity: u32,
+        price: Price,
+    ) -> Transaction {
+        Transaction {
+            kind,
+            item: item.to_string(),
+            quantity,
+            price,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+
+    pub fn kind(&self) -> TransactionKind {
+        self.kind
+    }
+
+    pub fn item(&self) -> &str {
+        &self.item
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    pub fn price(&self) -> Price {
+        self.price
+    }
+
+    /// Seconds since the Unix epoch.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
@@ -0,0 +1,460 @@
+use crate::character::VariantRules;
+use crate::content::{ContentLibrary, Rarity};
+use crate::dice::DiceRng;
+use crate::error::{Error, Result};
+use crate::item::{Item, Price};
+
+/// What a party of four should find over one level: permanent and consumable items as `(item level, count)` and the
+/// currency in gold, followed by the currency to add for each character beyond four.
+struct TreasureRow {
+    permanent: &'static [(u32, u32)],
+    consumables: &'static [(u32, u32)],
+    currency: u64,
+    per_character: u64,
+}
+
+const PARTY_TREASURE: [TreasureRow; 20] = [
+    TreasureRow {
+        permanent: &[(2, 2), (1, 2)],
+        consumables: &[(2, 2), (1, 3)],
+        currency: 40,
+        per_character: 10,
+    },
+    TreasureRow {
+        permanent: &[(3, 2), (2, 2)],
+        consumables: &[(3, 2), (2, 2), (1, 2)],
+        currency: 70,
+        per_character: 18,
+    },
+    TreasureRow {
+        permanent: &[(4, 2), (3, 2)],
+        consumables: &[(4, 2), (3, 2), (2, 2)],
+        currency: 120,
+        per_character: 30,
+    },
+    TreasureRow {
+        permanent: &[(5, 2), (4, 2)],
+        consumables: &[(5, 2), (4, 2), (3, 2)],
+        currency: 200,
+        per_character: 50,
+    },
+    TreasureRow {
+        permanent: &[(6, 2), (5, 2)],
+        consumables: &[(6, 2), (5, 2), (4, 2)],
+        currency: 320,
+        per_character: 80,
+    },
+    TreasureRow {
+        permanent: &[(7, 2), (6, 2)],
+        consumables: &[(7, 2), (6, 2), (5, 2)],
+        currency: 500,
+        per_character: 125,
+    },
+    TreasureRow {
+        permanent: &[(8, 2), (7, 2)],
+        consumables: &[(8, 2), (7, 2), (6, 2)],
+        currency: 720,
+        per_character: 180,
+    },
+    TreasureRow {
+        permanent: &[(9, 2), (8, 2)],
+        consumables: &[(9, 2), (8, 2), (7, 2)],
+        currency: 1000,
+        per_character: 250,
+    },
+    TreasureRow {
+        permanent: &[(10, 2), (9, 2)],
+        consumables: &[(10, 2), (9, 2), (8, 2)],
+        currency: 1400,
+        per_character: 350,
+    },
+    TreasureRow {
+        permanent: &[(11, 2), (10, 2)],
+        consumables: &[(11, 2), (10, 2), (9, 2)],
+        currency: 2000,
+        per_character: 500,
+    },
+    TreasureRow {
+        permanent: &[(12, 2), (11, 2)],
+        consumables: &[(12, 2), (11, 2), (10, 2)],
+        currency: 2800,
+        per_character: 700,
+    },
+    TreasureRow {
+        permanent: &[(13, 2), (12, 2)],
+        consumables: &[(13, 2), (12, 2), (11, 2)],
+        currency: 4000,
+        per_character: 1000,
+    },
+    TreasureRow {
+        permanent: &[(14, 2), (13, 2)],
+        consumables: &[(14, 2), (13, 2), (12, 2)],
+        currency: 6000,
+        per_character: 1500,
+    },
+    TreasureRow {
+        permanent: &[(15, 2), (14, 2)],
+        consumables: &[(15, 2), (14, 2), (13, 2)],
+        currency: 9000,
+        per_character: 2250,
+    },
+    TreasureRow {
+        permanent: &[(16, 2), (15, 2)],
+        consumables: &[(16, 2), (15, 2), (14, 2)],
+        currency: 13000,
+        per_character: 3250,
+    },
+    TreasureRow {
+        permanent: &[(17, 2), (16, 2)],
+        consumables: &[(17, 2), (16, 2), (15, 2)],
+        currency: 20000,
+        per_character: 5000,
+    },
+    TreasureRow {
+        permanent: &[(18, 2), (17, 2)],
+        consumables: &[(18, 2), (17, 2), (16, 2)],
+        currency: 30000,
+        per_character: 7500,
+    },
+    TreasureRow {
+        permanent: &[(19, 2), (18, 2)],
+        consumables: &[(19, 2), (18, 2), (17, 2)],
+        currency: 48000,
+        per_character: 12000,
+    },
+    TreasureRow {
+        permanent: &[(20, 2), (19, 2)],
+        consumables: &[(20, 2), (19, 2), (18, 2)],
+        currency: 80000,
+        per_character: 20000,
+    },
+    TreasureRow {
+        permanent: &[(20, 4)],
+        consumables: &[(20, 4), (19, 2)],
+        currency: 140000,
+        per_character: 35000,
+    },
+];
+
+/// Whether a piece of treasure lasts or gets used up.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TreasureKind {
+    Permanent,
+    Consumable,
+}
+
+impl TreasureKind {
+    /// Items that can be consumed are consumables, everything else is permanent.
+    pub fn of(item: &Item) -> TreasureKind {
+        if item.consumable().is_some() {
+            TreasureKind::Consumable
+        } else {
+            TreasureKind::Permanent
+        }
+    }
+}
+
+/// One item slot of a treasure table, with the item drawn for it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TreasureItem {
+    kind: TreasureKind,
+    level: u32,
+    /// `None` if the library has no fitting item.
+    item: Option<Item>,
+    locked: bool,
+}
+
+impl TreasureItem {
+    pub fn kind(&self) -> TreasureKind {
+        self.kind
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn item(&self) -> Option<&Item> {
+        self.item.as_ref()
+    }
+
+    /// Locked items are kept when rerolling.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+/// The treasure a party should find over one level, drawn from a content library.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Treasure {
+    party_level: u32,
+    party_size: u32,
+    max_rarity: Rarity,
+    items: Vec<TreasureItem>,
+    currency: Price,
+}
+
+impl Treasure {
+    /// Generates the treasure for a party of level 1 to 20. Only items up to `max_rarity` are drawn. The party size
+    /// changes the currency: each character beyond four adds to it and each one missing takes away from it. With
+    /// automatic bonus progression there are two fewer permanent items of the highest level, as the fundamental runes
+    /// they stand for come with the party's level.
+    pub fn generate<R: DiceRng + ?Sized>(
+        party_level: u32,
+        party_size: u32,
+        max_rarity: Rarity,
+        rules: &VariantRules,
+        library: &ContentLibrary,
+        rng: &mut R,
+    ) -> Result<Treasure> {
+        if party_level == 0 || party_level > 20 || party_size == 0 {
+            return Err(Error::InvalidParty {
+                level: party_level,
+                size: party_size,
+            });
+        }
+        let row = &PARTY_TREASURE[party_level as usize - 1];
+
+        let currency = if party_size >= 4 {
+            row.currency + row.per_character * (party_size - 4) as u64
+        } else {
+            row.currency
+                .saturating_sub(row.per_character * (4 - party_size) as u64)
+        };
+        let slots = row
+            .permanent
+            .iter()
+            .map(|slot| (TreasureKind::Permanent, slot))
+            .chain(
+                row.consumables
+                    .iter()
+                    .map(|slot| (TreasureKind::Consumable, slot)),
+            );
+        let mut items = vec![];
+        for (i, (kind, &(level, count))) in slots.enumerate() {
+            let count = if i == 0 && rules.automatic_bonus_progression() {
+                count.saturating_sub(2)
+            } else {
+                count
+            };
+            for _ in 0..count {
+                items.push(TreasureItem {
+                    kind,
+                    level,
+                    item: None,
+                    locked: false,
+                });
+            }
+        }
+
+        let mut treasure = Treasure {
+            party_level,
+            party_size,
+            max_rarity,
+            items,
+            currency: Price::gp(currency),
+        };
+        treasure.reroll(library, rng);
+        Ok(treasure)
+    }
+
+    pub fn party_level(&self) -> u32 {
+        self.party_level
+    }
+
+    pub fn party_size(&self) -> u32 {
+        self.party_size
+    }
+
+    pub fn max_rarity(&self) -> Rarity {
+        self.max_rarity
+    }
+
+    pub fn items(&self) -> &Vec<TreasureItem> {
+        &self.items
+    }
+
+    pub fn currency(&self) -> Price {
+        self.currency
+    }
+
+    /// The currency plus the price of every drawn item.
+    pub fn total_value(&self) -> Price {
+        self.currency
+            + self
+                .items
+                .iter()
+                .filter_map(|slot| slot.item.as_ref())
+                .map(Item::price)
+                .sum()
+    }
+
+    /// Keeps the item at `index` when rerolling.
+    pub fn lock(&mut self, index: usize) -> Result<()> {
+        self.slot_mut(index)?.locked = true;
+        Ok(())
+    }
+
+    pub fn unlock(&mut self, index: usize) -> Result<()> {
+        self.slot_mut(index)?.locked = false;
+        Ok(())
+    }
+
+    /// Draws a new item for every slot that isn't locked.
+    pub fn reroll<R: DiceRng + ?Sized>(&mut self, library: &ContentLibrary, rng: &mut R) {
+        let max_rarity = self.max_rarity;
+        for slot in self.items.iter_mut().filter(|slot| !slot.locked) {
+            // sorted so the same seed always draws the same items
+            let mut candidates: Vec<&Item> = library
+                .items()
+                .filter(|item| {
+                    item.level() == slot.level
+                        && TreasureKind::of(item) == slot.kind
+                        && item.rarity() <= max_rarity
+                })
+                .collect();
+            candidates.sort_by(|a, b| a.name().cmp(b.name()));
+            slot.item = if candidates.is_empty() {
+                None
+            } else {
+                let index = rng.roll_die(candidates.len() as u32) as usize - 1;
+                Some(candidates[index].clone())
+            };
+        }
+    }
+
+    fn slot_mut(&mut self, index: usize) -> Result<&mut TreasureItem> {
+        self.items.get_mut(index).ok_or(Error::NotFound {
+            kind: "treasure item",
+            id: index as i64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::{DiceExpression, SeededRng};
+    use crate::item::{Bulk, Consumable, ConsumableEffect};
+
+    fn library() -> ContentLibrary {
+        let mut library = ContentLibrary::new();
+        for (name, level, rarity) in &[
+            ("Bag of Holding", 4, Rarity::Common),
+            ("Wayfinder", 2, Rarity::Common),
+            ("Hat of Disguise", 2, Rarity::Uncommon),
+            ("Cloak of Elvenkind", 3, Rarity::Rare),
+        ] {
+            let mut item = Item::new(name, *level, Bulk::light());
+            item.set_price(Price::gp(*level as u64 * 10));
+            item.set_rarity(*rarity);
+            library.add_item(item);
+        }
+        for level in 1..=4 {
+            let mut elixir = Item::new(&format!("Elixir {}", level), level, Bulk::light());
+            elixir.set_consumable(Some(Consumable::scroll("Heal", 1, 2)));
+            library.add_item(elixir);
+        }
+        let mut potion = Item::new("Potion", 2, Bulk::light());
+        potion.set_consumable(Some(Consumable::new(
+            1,
+            ConsumableEffect::Heal(DiceExpression::parse("1d8").unwrap()),
+        )));
+        library.add_item(potion);
+        library
+    }
+
+    #[test]
+    fn treasure_follows_the_table() {
+        let library = library();
+        let mut rules = VariantRules::new();
+        let treasure = Treasure::generate(
+            1,
+            4,
+            Rarity::Common,
+            &rules,
+            &library,
+            &mut SeededRng::new(1),
+        )
+        .unwrap();
+
+        assert_eq!(treasure.currency(), Price::gp(40));
+        assert_eq!(treasure.items().len(), 9);
+        let permanent: Vec<_> = treasure
+            .items()
+            .iter()
+            .filter(|slot| slot.kind() == TreasureKind::Permanent)
+            .collect();
+        assert_eq!(permanent.len(), 4);
+        // only the wayfinder is a common permanent item of level 2, there is none of level 1
+        assert_eq!(permanent[0].item().unwrap().name(), "Wayfinder");
+        assert!(permanent[3].item().is_none());
+        // two wayfinders and free consumables
+        assert_eq!(treasure.total_value(), Price::gp(80));
+
+        let bigger = Treasure::generate(
+            1,
+            6,
+            Rarity::Common,
+            &rules,
+            &library,
+            &mut SeededRng::new(1),
+        );
+        assert_eq!(bigger.unwrap().currency(), Price::gp(60));
+        let smaller = Treasure::generate(
+            1,
+            3,
+            Rarity::Common,
+            &rules,
+            &library,
+            &mut SeededRng::new(1),
+        );
+        assert_eq!(smaller.unwrap().currency(), Price::gp(30));
+        rules.set_automatic_bonus_progression(true);
+        let reduced = Treasure::generate(
+            1,
+            4,
+            Rarity::Common,
+            &rules,
+            &library,
+            &mut SeededRng::new(1),
+        );
+        assert_eq!(reduced.unwrap().items().len(), 7);
+        assert!(Treasure::generate(
+            0,
+            4,
+            Rarity::Common,
+            &rules,
+            &library,
+            &mut SeededRng::new(1)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn locked_items_survive_rerolls() {
+        let library = library();
+        let rules = VariantRules::new();
+        let mut rng = SeededRng::new(7);
+        let mut treasure =
+            Treasure::generate(1, 4, Rarity::Uncommon, &rules, &library, &mut rng).unwrap();
+        let consumable = treasure
+            .items()
+            .iter()
+            .position(|slot| slot.kind() == TreasureKind::Consumable && slot.level() == 2)
+            .unwrap();
+        treasure.lock(consumable).unwrap();
+        let kept = treasure.items()[consumable].clone();
+        assert!(treasure.lock(100).is_err());
+
+        for _ in 0..20 {
+            treasure.reroll(&library, &mut rng);
+            assert_eq!(treasure.items()[consumable], kept);
+            assert!(treasure.items()[consumable].is_locked());
+            assert!(treasure
+                .items()
+                .iter()
+                .filter_map(TreasureItem::item)
+                .all(|item| item.rarity() <= Rarity::Uncommon));
+        }
+    }
+}
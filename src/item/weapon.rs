@@ -0,0 +1,430 @@
+use crate::combat::{DamagePart, DamageType};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Decides which proficiency is used to attack with a weapon.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum WeaponCategory {
+    Unarmed,
+    Simple,
+    Martial,
+    Advanced,
+}
+
+impl WeaponCategory {
+    pub fn all() -> [WeaponCategory; 4] {
+        [
+            WeaponCategory::Unarmed,
+            WeaponCategory::Simple,
+            WeaponCategory::Martial,
+            WeaponCategory::Advanced,
+        ]
+    }
+}
+
+/// Weapons of the same group share critical specialization effects.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum WeaponGroup {
+    Axe,
+    Bomb,
+    Bow,
+    Brawling,
+    Club,
+    Crossbow,
+    Dart,
+    Firearm,
+    Flail,
+    Hammer,
+    Knife,
+    Pick,
+    Polearm,
+    Shield,
+    Sling,
+    Spear,
+    Sword,
+}
+
+/// Weapon traits, written like on stat blocks, e.g. `agile`, `deadly d10` or `thrown 20 ft.`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum WeaponTrait {
+    Agile,
+    Backstabber,
+    /// Adds a die of the given size on a critical hit.
+    Deadly(u32),
+    Disarm,
+    /// Upgrades the damage die on a critical hit and adds one more die.
+    Fatal(u32),
+    /// Uses Dexterity instead of Strength for melee attack rolls if it's higher.
+    Finesse,
+    Forceful,
+    FreeHand,
+    Grapple,
+    Nonlethal,
+    Parry,
+    /// Adds half the Strength modifier to ranged damage, or the full modifier if it's negative.
+    Propulsive,
+    Reach,
+    Shove,
+    Sweep,
+    /// Can be thrown with the given range increment in feet.
+    Thrown(u32),
+    Trip,
+    /// Deals damage with the given die when wielded in two hands.
+    TwoHand(u32),
+    /// Can deal the given damage type instead of its usual one.
+    Versatile(DamageType),
+    /// Attacks within the given distance in feet take a -2 penalty.
+    Volley(u32),
+    /// Any trait without rules of its own here.
+    Other(String),
+}
+
+impl fmt::Display for WeaponTrait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeaponTrait::Deadly(die) => write!(f, "deadly d{}", die),
+            WeaponTrait::Fatal(die) => write!(f, "fatal d{}", die),
+            WeaponTrait::Thrown(range) => write!(f, "thrown {} ft.", range),
+            WeaponTrait::TwoHand(die) => write!(f, "two-hand d{}", die),
+            WeaponTrait::Versatile(damage_type) => write!(f, "versatile {}", damage_type.name()),
+            WeaponTrait::Volley(range) => write!(f, "volley {} ft.", range),
+            WeaponTrait::FreeHand => write!(f, "free-hand"),
+            WeaponTrait::Other(name) => write!(f, "{}", name),
+            _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
+        }
+    }
+}
+
+impl FromStr for WeaponTrait {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<WeaponTrait> {
+        let source = source.trim().to_lowercase();
+        let invalid = || Error::InvalidFormat(format!("invalid weapon trait {:?}", source));
+        let die = |value: &str| -> Result<u32> {
+            value
+                .trim()
+                .strip_prefix('d')
+                .and_then(|size| size.parse().ok())
+                .ok_or_else(invalid)
+        };
+        let distance = |value: &str| -> Result<u32> {
+            value
+                .trim()
+                .trim_end_matches("ft.")
+                .trim_end_matches("ft")
+                .trim()
+                .parse()
+                .map_err(|_| invalid())
+        };
+        let (name, value) = match source.find(' ') {
+            Some(space) => (&source[..space], Some(&source[space + 1..])),
+            None => (source.as_str(), None),
+        };
+        Ok(match (name, value) {
+            ("agile", None) => WeaponTrait::Agile,
+            ("backstabber", None) => WeaponTrait::Backstabber,
+            ("deadly", Some(value)) => WeaponTrait::Deadly(die(value)?),
+            ("disarm", None) => WeaponTrait::Disarm,
+            ("fatal", Some(value)) => WeaponTrait::Fatal(die(value)?),
+            ("finesse", None) => WeaponTrait::Finesse,
+            ("forceful", None) => WeaponTrait::Forceful,
+            ("free-hand", None) => WeaponTrait::FreeHand,
+            ("grapple", None) => WeaponTrait::Grapple,
+            ("nonlethal", None) => WeaponTrait::Nonlethal,
+            ("parry", None) => WeaponTrait::Parry,
+            ("propulsive", None) => WeaponTrait::Propulsive,
+            ("reach", None) => WeaponTrait::Reach,
+            ("shove", None) => WeaponTrait::Shove,
+            ("sweep", None) => WeaponTrait::Sweep,
+            ("thrown", Some(value)) => WeaponTrait::Thrown(distance(value)?),
+            ("trip", None) => WeaponTrait::Trip,
+            ("two-hand", Some(value)) => WeaponTrait::TwoHand(die(value)?),
+            ("versatile", Some(value)) => WeaponTrait::Versatile(
+                match value.trim() {
+                    "b" => Some(DamageType::Bludgeoning),
+                    "p" => Some(DamageType::Piercing),
+                    "s" => Some(DamageType::Slashing),
+                    other => DamageType::from_name(other),
+                }
+                .ok_or_else(invalid)?,
+            ),
+            ("volley", Some(value)) => WeaponTrait::Volley(distance(value)?),
+            ("deadly" | "fatal" | "thrown" | "two-hand" | "versatile" | "volley", None) => {
+                return Err(invalid())
+            }
+            _ => WeaponTrait::Other(source.clone()),
+        })
+    }
+}
+
+impl From<WeaponTrait> for String {
+    fn from(weapon_trait: WeaponTrait) -> String {
+        weapon_trait.to_string()
+    }
+}
+
+impl TryFrom<String> for WeaponTrait {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<WeaponTrait> {
+        source.parse()
+    }
+}
+
+/// A property rune, e.g. flaming with its `1d6` fire damage.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PropertyRune {
+    name: String,
+    damage: Vec<DamagePart>,
+}
+
+impl PropertyRune {
+    pub fn new(name: &str) -> PropertyRune {
+        PropertyRune {
+            name: name.to_string(),
+            damage: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Damage the rune adds to every Strike.
+    pub fn damage(&self) -> &Vec<DamagePart> {
+        &self.damage
+    }
+
+    pub fn add_damage(&mut self, part: DamagePart) {
+        self.damage.push(part);
+    }
+}
+
+/// Fundamental and property runes etched onto a weapon.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WeaponRunes {
+    potency: u32,
+    striking: u32,
+    property: Vec<PropertyRune>,
+}
+
+impl WeaponRunes {
+    /// The item bonus to attack rolls, from +1 to +3.
+    pub fn potency(&self) -> u32 {
+        self.potency
+    }
+
+    pub fn set_potency(&mut self, potency: u32) -> Result<()> {
+        if potency > 3 {
+            return Err(Error::InvalidRune(format!(
+                "there is no +{} potency rune",
+                potency
+            )));
+        }
+        if self.property.len() as u32 > potency {
+            return Err(Error::InvalidRune(format!(
+                "a +{} potency rune can't hold {} property runes",
+                potency,
+                self.property.len()
+            )));
+        }
+        self.potency = potency;
+        Ok(())
+    }
+
+    /// The extra weapon damage dice: 1 for striking, 2 for greater striking and 3 for major striking.
+    pub fn striking(&self) -> u32 {
+        self.striking
+    }
+
+    pub fn set_striking(&mut self, striking: u32) -> Result<()> {
+        if striking > 3 {
+            return Err(Error::InvalidRune(format!(
+                "there is no striking rune with {} extra dice",
+                striking
+            )));
+        }
+        self.striking = striking;
+        Ok(())
+    }
+
+    pub fn property(&self) -> &Vec<PropertyRune> {
+        &self.property
+    }
+
+    /// Each point of potency allows one property rune.
+    pub fn add_property(&mut self, rune: PropertyRune) -> Result<()> {
+        if self.property.len() as u32 >= self.potency {
+            return Err(Error::InvalidRune(format!(
+                "a +{} potency rune has no room for {}",
+                self.potency,
+                rune.name()
+            )));
+        }
+        self.property.push(rune);
+        Ok(())
+    }
+}
+
+/// Makes an item usable for Strikes.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Weapon {
+    category: WeaponCategory,
+    group: WeaponGroup,
+    damage_die: u32,
+    damage_type: DamageType,
+    hands: u32,
+    /// The range increment in feet, only ranged weapons have one.
+    range: Option<u32>,
+    /// Actions needed to reload, only ranged weapons have one.
+    reload: Option<u32>,
+    traits: Vec<WeaponTrait>,
+    runes: WeaponRunes,
+}
+
+impl Weapon {
+    /// A one-handed melee weapon, use the setters for anything else.
+    pub fn new(
+        category: WeaponCategory,
+        group: WeaponGroup,
+        damage_die: u32,
+        damage_type: DamageType,
+    ) -> Weapon {
+        Weapon {
+            category,
+            group,
+            damage_die,
+            damage_type,
+            hands: 1,
+            range: None,
+            reload: None,
+            traits: vec![],
+            runes: WeaponRunes::default(),
+        }
+    }
+
+    pub fn category(&self) -> WeaponCategory {
+        self.category
+    }
+
+    pub fn group(&self) -> WeaponGroup {
+        self.group
+    }
+
+    pub fn damage_die(&self) -> u32 {
+        self.damage_die
+    }
+
+    pub fn damage_type(&self) -> DamageType {
+        self.damage_type
+    }
+
+    pub fn hands(&self) -> u32 {
+        self.hands
+    }
+
+    pub fn set_hands(&mut self, hands: u32) {
+        self.hands = hands;
+    }
+
+    pub fn range(&self) -> Option<u32> {
+        self.range
+    }
+
+    pub fn set_range(&mut self, range: Option<u32>) {
+        self.range = range;
+    }
+
+    pub fn reload(&self) -> Option<u32> {
+        self.reload
+    }
+
+    pub fn set_reload(&mut self, reload: Option<u32>) {
+        self.reload = reload;
+    }
+
+    pub fn is_ranged(&self) -> bool {
+        self.range.is_some()
+    }
+
+    pub fn traits(&self) -> &Vec<WeaponTrait> {
+        &self.traits
+    }
+
+    pub fn add_trait(&mut self, weapon_trait: WeaponTrait) {
+        self.traits.push(weapon_trait);
+    }
+
+    pub fn has_trait(&self, weapon_trait: &WeaponTrait) -> bool {
+        self.traits.contains(weapon_trait)
+    }
+
+    pub fn deadly(&self) -> Option<u32> {
+        self.traits.iter().find_map(|t| match t {
+            WeaponTrait::Deadly(die) => Some(*die),
+            _ => None,
+        })
+    }
+
+    pub fn fatal(&self) -> Option<u32> {
+        self.traits.iter().find_map(|t| match t {
+            WeaponTrait::Fatal(die) => Some(*die),
+            _ => None,
+        })
+    }
+
+    pub fn runes(&self) -> &WeaponRunes {
+        &self.runes
+    }
+
+    pub fn runes_mut(&mut self) -> &mut WeaponRunes {
+        &mut self.runes
+    }
+
+    /// The number of weapon damage dice, one plus any from a striking rune.
+    pub fn damage_dice(&self) -> u32 {
+        1 + self.runes.striking
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_traits() {
+        for source in [
+            "agile",
+            "deadly d10",
+            "thrown 20 ft.",
+            "versatile piercing",
+            "magical",
+        ]
+        .iter()
+        {
+            assert_eq!(&source.parse::<WeaponTrait>().unwrap().to_string(), source);
+        }
+        assert_eq!(
+            "Versatile P".parse::<WeaponTrait>().unwrap(),
+            WeaponTrait::Versatile(DamageType::Piercing)
+        );
+        assert!("deadly".parse::<WeaponTrait>().is_err());
+    }
+
+    #[test]
+    fn property_runes_need_potency() {
+        let mut runes = WeaponRunes::default();
+        assert!(runes.add_property(PropertyRune::new("Flaming")).is_err());
+
+        runes.set_potency(1).unwrap();
+        runes.add_property(PropertyRune::new("Flaming")).unwrap();
+        assert!(runes.add_property(PropertyRune::new("Frost")).is_err());
+        assert!(runes.set_potency(0).is_err());
+        assert!(runes.set_striking(4).is_err());
+    }
+}
@@ -1,4 +1,15 @@
 #[macro_use]
 extern crate maplit;
 
+pub mod campaign;
 pub mod character;
+pub mod combat;
+pub mod content;
+pub mod dice;
+pub mod downtime;
+pub mod error;
+pub mod import;
+pub mod item;
+pub mod save;
+pub mod sheet;
+pub mod spell;
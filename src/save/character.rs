@@ -0,0 +1,391 @@
+use crate::character::{
+    AbilityScore, AbilityScoreSet, AbilityScoreType, Character, Feat, Health, Modifier,
+    Proficiency, Size, Skill, SkillSet, Statistic, VariantRules,
+};
+use crate::content::{ContentKind, ContentLibrary};
+use crate::downtime::{CraftingProject, FormulaBook};
+use crate::error::{Error, Result};
+use crate::item::{ArmorCategory, Inventory, Purse, Transaction, WeaponCategory};
+use crate::save::migration::{migrate, CURRENT_VERSION, MIGRATIONS};
+use crate::spell::SpellcastingEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize)]
+struct Save {
+    version: u32,
+    character: SavedCharacter,
+}
+
+/// The on-disk representation of a character. Content is referenced by name and resolved against the library when
+/// loading.
+#[derive(Serialize, Deserialize)]
+struct SavedCharacter {
+    name: String,
+    description: String,
+    class: String,
+    ancestry: String,
+    background: String,
+    level: u32,
+    hp: SavedHealth,
+    /// The Speed without penalties.
+    speed: u32,
+    size: Size,
+    ability_scores: BTreeMap<String, u32>,
+    skills: BTreeMap<String, Proficiency>,
+    /// Perception and save proficiencies, keyed by statistic name.
+    proficiencies: BTreeMap<String, Proficiency>,
+    weapon_proficiencies: BTreeMap<WeaponCategory, Proficiency>,
+    armor_proficiencies: BTreeMap<ArmorCategory, Proficiency>,
+    feats: Vec<Feat>,
+    modifiers: Vec<Modifier>,
+    inventory: Inventory,
+    wealth: Purse,
+    transactions: Vec<Transaction>,
+    formula_book: FormulaBook,
+    crafting_projects: Vec<CraftingProject>,
+    variant_rules: VariantRules,
+    /// Skill potency bonuses, keyed by skill name.
+    skill_potencies: BTreeMap<String, u32>,
+    access: BTreeSet<String>,
+    spellcasting: Vec<SpellcastingEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedHealth {
+    max: u32,
+    current: u32,
+    temp: u32,
+}
+
+pub fn save_character(character: &Character) -> String {
+    let save = Save {
+        version: CURRENT_VERSION,
+        character: SavedCharacter {
+            name: character.name().to_string(),
+            description: character.description().to_string(),
+            class: character.class().name().to_string(),
+            ancestry: character.ancestry().name().to_string(),
+            background: character.background().name().to_string(),
+            level: character.level(),
+            hp: SavedHealth {
+                max: character.hp().max(),
+                current: character.hp().current(),
+                temp: character.hp().temp(),
+            },
+            speed: character.base_speed(),
+            size: character.size(),
+            ability_scores: AbilityScoreType::all()
+                .iter()
+                .map(|ability| {
+                    (
+                        ability.abbreviation().to_string(),
+                        character.abliity_scores().get(*ability).value(),
+                    )
+                })
+                .collect(),
+            skills: character
+                .skills()
+                .trained()
+                .into_iter()
+                .map(|(skill, proficiency)| (skill.name(), proficiency))
+                .collect(),
+            proficiencies: [Statistic::Perception]
+                .iter()
+                .chain(Statistic::saves().iter())
+                .map(|statistic| (statistic.name(), character.statistic_proficiency(statistic)))
+                .collect(),
+            weapon_proficiencies: WeaponCategory::all()
+                .iter()
+                .map(|category| (*category, character.weapon_proficiency(*category)))
+                .collect(),
+            armor_proficiencies: ArmorCategory::all()
+                .iter()
+                .map(|category| (*category, character.armor_proficiency(*category)))
+                .collect(),
+            feats: character.feats().clone(),
+            modifiers: character.modifiers().clone(),
+            inventory: character.inventory().clone(),
+            wealth: *character.wealth(),
+            transactions: character.transactions().clone(),
+            formula_book: character.formula_book().clone(),
+            crafting_projects: character.crafting_projects().clone(),
+            variant_rules: *character.variant_rules(),
+            skill_potencies: character
+                .skill_potencies()
+                .iter()
+                .map(|(skill, bonus)| (skill.name(), *bonus))
+                .collect(),
+            access: character.access().clone(),
+            spellcasting: character.spellcasting().clone(),
+        },
+    };
+    serde_json::to_string_pretty(&save).expect("characters can always be serialized")
+}
+
+/// Loads a character saved by any version of this crate, migrating it to the current format first.
+pub fn load_character<'a>(
+    json: &str,
+    library: &'a ContentLibrary,
+) -> Result<Character<'a, 'a, 'a>> {
+    let save = serde_json::from_str(json)?;
+    let save: Save = serde_json::from_value(migrate(save, MIGRATIONS)?)?;
+    let saved = save.character;
+
+    let class = library
+        .class(&saved.class)
+        .ok_or_else(|| Error::UnknownContent {
+            kind: ContentKind::Class,
+            name: saved.class.clone(),
+        })?;
+    let ancestry = library
+        .ancestry(&saved.ancestry)
+        .ok_or_else(|| Error::UnknownContent {
+            kind: ContentKind::Ancestry,
+            name: saved.ancestry.clone(),
+        })?;
+    let background =
+        library
+            .background(&saved.background)
+            .ok_or_else(|| Error::UnknownContent {
+                kind: ContentKind::Background,
+                name: saved.background.clone(),
+            })?;
+
+    let mut ability_scores = AbilityScoreSet::new();
+    for (abbreviation, value) in saved.ability_scores {
+        let ability = AbilityScoreType::from_abbreviation(&abbreviation)
+            .ok_or_else(|| Error::UnknownAbilityScore(abbreviation.clone()))?;
+        ability_scores.set(ability, AbilityScore::new(value));
+    }
+
+    let mut skills = SkillSet::new();
+    for (name, proficiency) in saved.skills {
+        let skill = Skill::from_name(&name).ok_or_else(|| Error::UnknownSkill(name.clone()))?;
+        skills.set(skill, proficiency);
+    }
+
+    let mut character = Character::restore(
+        saved.name,
+        saved.description,
+        class,
+        ancestry,
+        background,
+        saved.level,
+        Health::restore(saved.hp.max, saved.hp.current, saved.hp.temp),
+        saved.speed,
+        saved.size,
+        ability_scores,
+        skills,
+        saved.feats,
+    );
+    for (name, proficiency) in saved.proficiencies {
+        let statistic: Statistic = name.parse()?;
+        character.set_statistic_proficiency(statistic, proficiency);
+    }
+    for (category, proficiency) in saved.weapon_proficiencies {
+        character.set_weapon_proficiency(category, proficiency);
+    }
+    for (category, proficiency) in saved.armor_proficiencies {
+        character.set_armor_proficiency(category, proficiency);
+    }
+    for modifier in saved.modifiers {
+        character.add_modifier(modifier);
+    }
+    *character.inventory_mut() = saved.inventory;
+    *character.wealth_mut() = saved.wealth;
+    character.set_transactions(saved.transactions);
+    *character.formula_book_mut() = saved.formula_book;
+    character.set_crafting_projects(saved.crafting_projects);
+    character.set_variant_rules(saved.variant_rules);
+    for (name, bonus) in saved.skill_potencies {
+        let skill = Skill::from_name(&name).ok_or_else(|| Error::UnknownSkill(name.clone()))?;
+        character.set_skill_potency(skill, bonus)?;
+    }
+    for name in saved.access {
+        character.grant_access(&name);
+    }
+    for entry in saved.spellcasting {
+        character.add_spellcasting(entry);
+    }
+    Ok(character)
+}
+
+pub fn save_character_file<P: AsRef<Path>>(path: P, character: &Character) -> Result<()> {
+    fs::write(path.as_ref(), save_character(character)).map_err(|e| Error::io(path.as_ref(), e))
+}
+
+pub fn load_character_file<'a, P: AsRef<Path>>(
+    path: P,
+    library: &'a ContentLibrary,
+) -> Result<Character<'a, 'a, 'a>> {
+    let json = fs::read_to_string(path.as_ref()).map_err(|e| Error::io(path.as_ref(), e))?;
+    load_character(&json, library)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{
+        Ancestry, Background, Class, FeatType, ModifierType, Selector, SkillTrainingChoice,
+    };
+    use crate::dice::SeededRng;
+    use crate::downtime::SETUP_DAYS;
+    use crate::item::{Bulk, Container, Item, ItemState, Price};
+    use crate::spell::{CastingType, Spell, Tradition};
+
+    /// One fixture per released save version, oldest first.
+    const FIXTURES: &[&str] = &[include_str!("fixtures/character_v1.json")];
+
+    fn library() -> ContentLibrary {
+        let mut library = ContentLibrary::new();
+        library.add_class(Class::new(
+            "Fighter".to_string(),
+            AbilityScoreType::Strength,
+            10,
+        ));
+        library.add_ancestry(Ancestry::new(
+            "Human".to_string(),
+            8,
+            Size::Medium,
+            25,
+            vec![],
+        ));
+        library.add_background(Background::new(
+            "Warrior".to_string(),
+            "".to_string(),
+            vec![],
+            vec![SkillTrainingChoice::predetermined(Skill::lore("Warfare"))],
+            Some(Feat::new(
+                "Intimidating Glare".to_string(),
+                "".to_string(),
+                FeatType::Skill,
+                1,
+            )),
+        ));
+        library
+    }
+
+    #[test]
+    fn every_fixture_version_loads() {
+        assert_eq!(FIXTURES.len() as u32, CURRENT_VERSION);
+        let library = library();
+
+        for fixture in FIXTURES.iter() {
+            let character = load_character(fixture, &library).unwrap();
+
+            assert_eq!(character.name(), "Bob");
+            assert_eq!(character.class().name(), "Fighter");
+            assert_eq!(character.level(), 2);
+            assert_eq!(character.hp().current(), 20);
+            assert_eq!(character.strength().value(), 14);
+            assert_eq!(
+                character.proficiency(&Skill::lore("Warfare")),
+                Proficiency::Trained
+            );
+            assert_eq!(character.feats()[0].name(), "Intimidating Glare");
+        }
+
+        let character = load_character(FIXTURES[0], &library).unwrap();
+        let entries = character.inventory().entries();
+        assert_eq!(entries.len(), 7);
+        assert_eq!(entries[2].item().weapon().unwrap().runes().striking(), 1);
+        assert_eq!(entries[3].item().armor().unwrap().strength(), Some(16));
+        assert_eq!(entries[5].item().consumable().unwrap().actions(), 1);
+        assert_eq!(entries[6].item().wand().unwrap().spell(), "Heal");
+        assert_eq!(character.wealth().total(), Price::gp(14) + Price::sp(3));
+        assert_eq!(character.transactions().len(), 2);
+        assert_eq!(character.formula_book().formulas()[0].name(), "Rope");
+        assert_eq!(character.crafting_projects()[0].days(), 3);
+        assert!(character.variant_rules().automatic_bonus_progression());
+        assert!(character.has_access("Intimidating Glare"));
+        let entry = character.spellcasting_entry("Ritualist").unwrap();
+        assert_eq!(entry.spells()[0].spell().name(), "Fear");
+        assert!(entry.prepared()[0].is_expended());
+    }
+
+    #[test]
+    fn save_roundtrip() {
+        let library = library();
+        let mut character = Character::new(
+            "Bob",
+            library.class("Fighter").unwrap(),
+            library.ancestry("Human").unwrap(),
+            &[],
+            library.background("Warrior").unwrap(),
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        character.level_up();
+        character.set_statistic_proficiency(Statistic::Fortitude, Proficiency::Expert);
+        character.add_modifier(Modifier::new(
+            "Heroism",
+            ModifierType::Status,
+            1,
+            Selector::All,
+        ));
+        let mut backpack = Item::new("Backpack", 0, Bulk::light());
+        backpack.set_container(Some(Container::new(Bulk::new(4), Bulk::new(2))));
+        let pack = character.buy(backpack, 1, ItemState::Worn).unwrap();
+        let mut rope = Item::new("Rope", 0, Bulk::light());
+        rope.set_price(Price::sp(5));
+        character.buy(rope, 1, ItemState::Stowed(pack)).unwrap();
+        character
+            .inventory_mut()
+            .add(Item::new("Anvil", 0, Bulk::new(8)), 1, ItemState::Held)
+            .unwrap();
+        let mut entry = SpellcastingEntry::new(
+            "Ritualist",
+            Tradition::Occult,
+            CastingType::Prepared,
+            AbilityScoreType::Wisdom,
+        );
+        let mut fear = Spell::new("Fear", 1, 2);
+        fear.add_tradition(Tradition::Occult);
+        entry.add_spell(fear).unwrap();
+        character.add_spellcasting(entry);
+        character.prepare_spell("Ritualist", "Fear", 1).unwrap();
+        let mut potion = Item::new("Minor Healing Potion", 1, Bulk::light());
+        potion.set_price(Price::gp(4));
+        character.formula_book_mut().add(potion);
+        character.set_proficiency(Skill::Crafting, Proficiency::Trained);
+        // this seed succeeds at the Crafting check
+        character
+            .start_crafting("Minor Healing Potion", 1, &mut SeededRng::new(2))
+            .unwrap();
+        character.spend_crafting_days(0, 1).unwrap();
+
+        let loaded = load_character(&save_character(&character), &library).unwrap();
+
+        assert_eq!(save_character(&loaded), save_character(&character));
+        assert_eq!(loaded.base_speed(), 25);
+        assert_eq!(loaded.speed(), 15);
+        assert_eq!(loaded.crafting_projects().len(), 1);
+        let project = &loaded.crafting_projects()[0];
+        assert!(project.check().is_none());
+        assert_eq!(project.degree(), character.crafting_projects()[0].degree());
+        assert_eq!(project.days(), SETUP_DAYS + 1);
+        assert_eq!(
+            project.remaining_cost(),
+            character.crafting_projects()[0].remaining_cost()
+        );
+        assert!(project.remaining_cost() < Price::sp(20));
+    }
+
+    #[test]
+    #[should_panic]
+    fn load_unknown_content_fails() {
+        let library = ContentLibrary::new();
+
+        load_character(FIXTURES[0], &library).unwrap();
+    }
+}
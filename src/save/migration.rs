@@ -0,0 +1,78 @@
+use crate::error::{Error, Result};
+use serde_json::Value;
+
+/// Upgrades a save by exactly one version.
+pub type Migration = fn(Value) -> Result<Value>;
+
+/// The version written by this crate.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// `MIGRATIONS[i]` upgrades a save from version `i + 1` to version `i + 2`. Whenever the save format changes, bump
+/// `CURRENT_VERSION`, append a migration here and add a fixture for the new version.
+pub(crate) const MIGRATIONS: &[Migration] = &[];
+
+/// Runs all migrations needed to bring `save` up to the newest version.
+pub(crate) fn migrate(save: Value, migrations: &[Migration]) -> Result<Value> {
+    let newest = migrations.len() as u32 + 1;
+    let version = save["version"]
+        .as_u64()
+        .ok_or_else(|| Error::InvalidFormat("save has no version".to_string()))?
+        as u32;
+    if version == 0 || version > newest {
+        return Err(Error::UnsupportedVersion { version, newest });
+    }
+
+    let mut save = save;
+    for (i, migration) in migrations.iter().enumerate().skip(version as usize - 1) {
+        save = migration(save)?;
+        save["version"] = Value::from(i as u32 + 2);
+    }
+    Ok(save)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename_title(mut save: Value) -> Result<Value> {
+        let character = save["character"].as_object_mut().unwrap();
+        let title = character.remove("title").unwrap();
+        character.insert("name".to_string(), title);
+        Ok(save)
+    }
+
+    fn add_description(mut save: Value) -> Result<Value> {
+        save["character"]["description"] = Value::from("");
+        Ok(save)
+    }
+
+    #[test]
+    fn migrations_run_in_order() {
+        let save = serde_json::json!({ "version": 1, "character": { "title": "Bob" } });
+
+        let save = migrate(save, &[rename_title, add_description]).unwrap();
+
+        assert_eq!(
+            save,
+            serde_json::json!({ "version": 3, "character": { "name": "Bob", "description": "" } })
+        );
+    }
+
+    #[test]
+    fn migrations_skip_older_versions() {
+        let save = serde_json::json!({ "version": 2, "character": { "name": "Bob" } });
+
+        let save = migrate(save, &[rename_title, add_description]).unwrap();
+
+        assert_eq!(save["character"]["name"], "Bob");
+        assert_eq!(save["version"], 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn migrate_rejects_newer_versions() {
+        let save = serde_json::json!({ "version": 2, "character": {} });
+
+        migrate(save, &[]).unwrap();
+    }
+}
@@ -0,0 +1,9 @@
+mod character;
+mod migration;
+
+pub use character::load_character;
+pub use character::load_character_file;
+pub use character::save_character;
+pub use character::save_character_file;
+pub use migration::Migration;
+pub use migration::CURRENT_VERSION;
@@ -0,0 +1,8 @@
+mod renderer;
+mod template;
+
+pub use renderer::render_html;
+pub use renderer::render_markdown;
+pub use renderer::SheetFormat;
+pub use renderer::SheetTemplate;
+pub use template::Template;
@@ -0,0 +1,512 @@
+use crate::character::{AbilityScoreType, Character, Proficiency, Skill, Statistic};
+use crate::combat::DamageFormula;
+use crate::error::Result;
+use crate::item::ItemState;
+use crate::sheet::Template;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SheetFormat {
+    Markdown,
+    Html,
+}
+
+impl SheetFormat {
+    /// Escapes text taken from the character so it can't break the surrounding markup.
+    pub fn escape(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            match (self, c) {
+                (SheetFormat::Html, '&') => result.push_str("&amp;"),
+                (SheetFormat::Html, '<') => result.push_str("&lt;"),
+                (SheetFormat::Html, '>') => result.push_str("&gt;"),
+                (SheetFormat::Html, '"') => result.push_str("&quot;"),
+                (SheetFormat::Html, '\'') => result.push_str("&#39;"),
+                (SheetFormat::Markdown, '\\' | '|' | '*' | '_' | '`' | '#' | '<' | '>') => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                (SheetFormat::Markdown, '\n') => result.push(' '),
+                _ => result.push(c),
+            }
+        }
+        result
+    }
+}
+
+const MARKDOWN_DOCUMENT: &str = "# {{name}}
+
+{{description}}
+
+| | |
+|---|---|
+| Ancestry | {{ancestry}} |
+| Background | {{background}} |
+| Class | {{class}} |
+| Level | {{level}} |
+| HP | {{hp}} / {{max_hp}} (temp {{temp_hp}}) |
+| AC | {{ac}} ({{armor}}) |
+| Speed | {{speed}} feet (base {{base_speed}} feet) |
+| Size | {{size}} |
+| Perception | {{perception}} |
+| Saves | Fortitude {{fortitude}}, Reflex {{reflex}}, Will {{will}} |
+| Wealth | {{wealth}} |
+
+## Ability Scores
+
+| Ability | Score | Modifier |
+|---|---|---|
+{{abilities}}
+## Skills
+
+| Skill | Proficiency | Modifier |
+|---|---|---|
+{{skills}}
+## Attacks
+
+| Weapon | Attack | Damage |
+|---|---|---|
+{{attacks}}
+## Spellcasting
+
+| Spellcasting | Tradition | Attack | DC |
+|---|---|---|---|
+{{spellcasting}}
+## Feats
+
+{{feats}}";
+const MARKDOWN_ABILITY_ROW: &str = "| {{ability}} | {{score}} | {{modifier}} |\n";
+const MARKDOWN_SKILL_ROW: &str = "| {{skill}} | {{proficiency}} | {{modifier}} |\n";
+const MARKDOWN_FEAT_ROW: &str = "- **{{feat}}** ({{type}} {{level}}) {{description}}\n";
+const MARKDOWN_ATTACK_ROW: &str = "| {{weapon}} | {{attack}} | {{damage}} |\n";
+const MARKDOWN_SPELLCASTING_ROW: &str =
+    "| {{spellcasting}} | {{tradition}} | {{spell_attack}} | {{spell_dc}} |\n";
+
+const HTML_DOCUMENT: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{{name}}</title>
+<style>
+body { font-family: sans-serif; max-width: 50em; margin: 2em auto; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #999; padding: 0.2em 0.6em; text-align: left; }
+</style>
+</head>
+<body>
+<h1>{{name}}</h1>
+<p>{{description}}</p>
+<table>
+<tr><th>Ancestry</th><td>{{ancestry}}</td></tr>
+<tr><th>Background</th><td>{{background}}</td></tr>
+<tr><th>Class</th><td>{{class}}</td></tr>
+<tr><th>Level</th><td>{{level}}</td></tr>
+<tr><th>HP</th><td>{{hp}} / {{max_hp}} (temp {{temp_hp}})</td></tr>
+<tr><th>AC</th><td>{{ac}} ({{armor}})</td></tr>
+<tr><th>Speed</th><td>{{speed}} feet (base {{base_speed}} feet)</td></tr>
+<tr><th>Size</th><td>{{size}}</td></tr>
+<tr><th>Perception</th><td>{{perception}}</td></tr>
+<tr><th>Saves</th><td>Fortitude {{fortitude}}, Reflex {{reflex}}, Will {{will}}</td></tr>
+<tr><th>Wealth</th><td>{{wealth}}</td></tr>
+</table>
+<h2>Ability Scores</h2>
+<table>
+<tr><th>Ability</th><th>Score</th><th>Modifier</th></tr>
+{{abilities}}</table>
+<h2>Skills</h2>
+<table>
+<tr><th>Skill</th><th>Proficiency</th><th>Modifier</th></tr>
+{{skills}}</table>
+<h2>Attacks</h2>
+<table>
+<tr><th>Weapon</th><th>Attack</th><th>Damage</th></tr>
+{{attacks}}</table>
+<h2>Spellcasting</h2>
+<table>
+<tr><th>Spellcasting</th><th>Tradition</th><th>Attack</th><th>DC</th></tr>
+{{spellcasting}}</table>
+<h2>Feats</h2>
+<ul>
+{{feats}}</ul>
+</body>
+</html>
+";
+const HTML_ABILITY_ROW: &str =
+    "<tr><td>{{ability}}</td><td>{{score}}</td><td>{{modifier}}</td></tr>\n";
+const HTML_SKILL_ROW: &str =
+    "<tr><td>{{skill}}</td><td>{{proficiency}}</td><td>{{modifier}}</td></tr>\n";
+const HTML_FEAT_ROW: &str =
+    "<li><strong>{{feat}}</strong> ({{type}} {{level}}) {{description}}</li>\n";
+const HTML_ATTACK_ROW: &str =
+    "<tr><td>{{weapon}}</td><td>{{attack}}</td><td>{{damage}}</td></tr>\n";
+const HTML_SPELLCASTING_ROW: &str =
+    "<tr><td>{{spellcasting}}</td><td>{{tradition}}</td><td>{{spell_attack}}</td><td>{{spell_dc}}</td></tr>\n";
+
+/// The layout of a character sheet.
+///
+/// `document` describes the whole sheet, while the row templates are repeated once per entry and inserted into the
+/// `{{abilities}}`, `{{skills}}`, `{{feats}}`, `{{attacks}}` and `{{spellcasting}}` placeholders of the document. The
+/// attack and spellcasting rows are empty unless set.
+#[derive(Debug, Clone)]
+pub struct SheetTemplate {
+    format: SheetFormat,
+    document: Template,
+    ability_row: Template,
+    skill_row: Template,
+    feat_row: Template,
+    attack_row: Template,
+    spellcasting_row: Template,
+}
+
+fn signed(value: i32) -> String {
+    format!("{:+}", value)
+}
+
+/// Shows damage like `1d8+4 slashing plus 1d6 fire`.
+fn damage(formula: &DamageFormula) -> String {
+    let mut text = format!("{}d{}", formula.dice(), formula.die_size());
+    if formula.modifier() != 0 {
+        text.push_str(&signed(formula.modifier()));
+    }
+    text.push(' ');
+    text.push_str(&formula.damage_type().name());
+    for part in formula.parts().iter() {
+        text.push_str(&format!(
+            " plus {} {}",
+            part.expression(),
+            part.damage_type().name()
+        ));
+    }
+    text
+}
+
+impl SheetTemplate {
+    pub fn new(
+        format: SheetFormat,
+        document: &str,
+        ability_row: &str,
+        skill_row: &str,
+        feat_row: &str,
+    ) -> Result<SheetTemplate> {
+        Ok(SheetTemplate {
+            format,
+            document: Template::parse(document)?,
+            ability_row: Template::parse(ability_row)?,
+            skill_row: Template::parse(skill_row)?,
+            feat_row: Template::parse(feat_row)?,
+            attack_row: Template::parse("")?,
+            spellcasting_row: Template::parse("")?,
+        })
+    }
+
+    /// Sets the row repeated for every weapon in the inventory.
+    pub fn set_attack_row(&mut self, attack_row: &str) -> Result<()> {
+        self.attack_row = Template::parse(attack_row)?;
+        Ok(())
+    }
+
+    /// Sets the row repeated for every spellcasting entry.
+    pub fn set_spellcasting_row(&mut self, spellcasting_row: &str) -> Result<()> {
+        self.spellcasting_row = Template::parse(spellcasting_row)?;
+        Ok(())
+    }
+
+    pub fn markdown() -> SheetTemplate {
+        let mut template = SheetTemplate::new(
+            SheetFormat::Markdown,
+            MARKDOWN_DOCUMENT,
+            MARKDOWN_ABILITY_ROW,
+            MARKDOWN_SKILL_ROW,
+            MARKDOWN_FEAT_ROW,
+        )
+        .expect("default markdown template is valid");
+        template
+            .set_attack_row(MARKDOWN_ATTACK_ROW)
+            .expect("default markdown template is valid");
+        template
+            .set_spellcasting_row(MARKDOWN_SPELLCASTING_ROW)
+            .expect("default markdown template is valid");
+        template
+    }
+
+    pub fn html() -> SheetTemplate {
+        let mut template = SheetTemplate::new(
+            SheetFormat::Html,
+            HTML_DOCUMENT,
+            HTML_ABILITY_ROW,
+            HTML_SKILL_ROW,
+            HTML_FEAT_ROW,
+        )
+        .expect("default html template is valid");
+        template
+            .set_attack_row(HTML_ATTACK_ROW)
+            .expect("default html template is valid");
+        template
+            .set_spellcasting_row(HTML_SPELLCASTING_ROW)
+            .expect("default html template is valid");
+        template
+    }
+
+    pub fn format(&self) -> SheetFormat {
+        self.format
+    }
+
+    pub fn render(&self, character: &Character) -> Result<String> {
+        let escape = |text: &str| self.format.escape(text);
+
+        let mut abilities = String::new();
+        for ability in AbilityScoreType::all().iter() {
+            let score = character.abliity_scores().get(*ability);
+            abilities.push_str(&self.ability_row.render(|name| match name {
+                "ability" => Some(format!("{:?}", ability)),
+                "abbreviation" => Some(ability.abbreviation().to_uppercase()),
+                "score" => Some(score.value().to_string()),
+                "modifier" => Some(signed(score.modifier())),
+                _ => None,
+            })?);
+        }
+
+        let mut skills = String::new();
+        let mut skill_list: Vec<_> = character
+            .skills()
+            .trained()
+            .into_iter()
+            .map(|(skill, _)| skill.clone())
+            .collect();
+        for skill in Skill::all().iter() {
+            if !skill_list.contains(skill) {
+                skill_list.push(skill.clone());
+            }
+        }
+        skill_list.sort();
+        for skill in skill_list.iter() {
+            let proficiency = character.proficiency(skill);
+            skills.push_str(&self.skill_row.render(|name| match name {
+                "skill" => Some(escape(&skill.name())),
+                "proficiency" => Some(format!("{:?}", proficiency)),
+                "trained" => Some((proficiency > Proficiency::Untrained).to_string()),
+                "modifier" => Some(signed(character.skill_modifier(skill))),
+                _ => None,
+            })?);
+        }
+
+        let mut feats = String::new();
+        for feat in character.feats().iter() {
+            feats.push_str(&self.feat_row.render(|name| match name {
+                "feat" => Some(escape(feat.name())),
+                "type" => Some(format!("{:?}", feat.feat_type())),
+                "level" => Some(feat.level().to_string()),
+                "description" => Some(escape(feat.description())),
+                _ => None,
+            })?);
+        }
+
+        let mut attacks = String::new();
+        for entry in character.inventory().entries().iter() {
+            let weapon = match entry.item().weapon() {
+                Some(weapon) => weapon,
+                None => continue,
+            };
+            attacks.push_str(&self.attack_row.render(|name| match name {
+                "weapon" => Some(escape(entry.item().name())),
+                "attack" => Some(signed(character.attack_breakdown(weapon, &[]).total())),
+                "damage" => Some(damage(&character.weapon_damage(weapon))),
+                _ => None,
+            })?);
+        }
+
+        let mut spellcasting = String::new();
+        for entry in character.spellcasting().iter() {
+            let attack = character.spell_attack(entry.name())?;
+            let dc = character.spell_dc(entry.name())?;
+            spellcasting.push_str(&self.spellcasting_row.render(|name| match name {
+                "spellcasting" => Some(escape(entry.name())),
+                "tradition" => Some(format!("{:?}", entry.tradition())),
+                "casting_type" => Some(format!("{:?}", entry.casting_type())),
+                "spell_attack" => Some(signed(attack)),
+                "spell_dc" => Some(dc.to_string()),
+                _ => None,
+            })?);
+        }
+
+        let armor = character
+            .inventory()
+            .entries()
+            .iter()
+            .find(|entry| entry.state() == ItemState::Worn && entry.item().armor().is_some())
+            .map_or("Unarmored", |entry| entry.item().name());
+
+        self.document.render(|name| {
+            let ability = AbilityScoreType::from_abbreviation(name.trim_end_matches("_mod"));
+            if let Some(ability) = ability {
+                let score = character.abliity_scores().get(ability);
+                return Some(if name.ends_with("_mod") {
+                    signed(score.modifier())
+                } else {
+                    score.value().to_string()
+                });
+            }
+            match name {
+                "name" => Some(escape(character.name())),
+                "description" => Some(escape(character.description())),
+                "ancestry" => Some(escape(character.ancestry().name())),
+                "background" => Some(escape(character.background().name())),
+                "class" => Some(escape(character.class().name())),
+                "level" => Some(character.level().to_string()),
+                "hp" => Some(character.hp().current().to_string()),
+                "max_hp" => Some(character.hp().max().to_string()),
+                "temp_hp" => Some(character.hp().temp().to_string()),
+                "speed" => Some(character.speed().to_string()),
+                "base_speed" => Some(character.base_speed().to_string()),
+                "ac" => Some(character.armor_class().to_string()),
+                "armor" => Some(escape(armor)),
+                "wealth" => Some(character.wealth().to_string()),
+                "size" => Some(format!("{:?}", character.size())),
+                "perception" => Some(signed(character.statistic_modifier(&Statistic::Perception))),
+                "fortitude" => Some(signed(character.statistic_modifier(&Statistic::Fortitude))),
+                "reflex" => Some(signed(character.statistic_modifier(&Statistic::Reflex))),
+                "will" => Some(signed(character.statistic_modifier(&Statistic::Will))),
+                "abilities" => Some(abilities.clone()),
+                "skills" => Some(skills.clone()),
+                "feats" => Some(feats.clone()),
+                "attacks" => Some(attacks.clone()),
+                "spellcasting" => Some(spellcasting.clone()),
+                _ => None,
+            }
+        })
+    }
+}
+
+pub fn render_markdown(character: &Character) -> String {
+    SheetTemplate::markdown()
+        .render(character)
+        .expect("default markdown template only uses known placeholders")
+}
+
+pub fn render_html(character: &Character) -> String {
+    SheetTemplate::html()
+        .render(character)
+        .expect("default html template only uses known placeholders")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Background, Class, Size};
+    use crate::combat::DamageType;
+    use crate::item::{Armor, ArmorCategory, Bulk, Item, Weapon, WeaponCategory, WeaponGroup};
+    use crate::spell::{CastingType, SpellcastingEntry, Tradition};
+
+    fn with_character<F: FnOnce(&mut Character)>(f: F) {
+        let class = Class::new("Fighter".to_string(), AbilityScoreType::Strength, 10);
+        let ancestry = Ancestry::new("Human".to_string(), 8, Size::Medium, 25, vec![]);
+        let background =
+            Background::new("Warrior".to_string(), "".to_string(), vec![], vec![], None);
+        let mut character = Character::new(
+            "Bob <the | Bold>",
+            &class,
+            &ancestry,
+            &[],
+            &background,
+            &[],
+            &[],
+            &[
+                AbilityScoreType::Strength,
+                AbilityScoreType::Dexterity,
+                AbilityScoreType::Constitution,
+                AbilityScoreType::Wisdom,
+            ],
+        )
+        .unwrap();
+        character.set_description("A fighter.".to_string());
+        f(&mut character);
+    }
+
+    #[test]
+    fn markdown_lists_statistics() {
+        with_character(|character| {
+            let sheet = render_markdown(character);
+
+            assert!(sheet.starts_with("# Bob \\<the \\| Bold\\>"));
+            assert!(sheet.contains("| Class | Fighter |"));
+            assert!(sheet.contains("| HP | 18 / 18 (temp 0) |"));
+            assert!(sheet.contains("| Strength | 14 | +2 |"));
+            assert!(sheet.contains("| Intelligence | 10 | +0 |"));
+            assert!(sheet.contains("| Athletics | Untrained | +2 |"));
+            assert!(sheet.contains("| Perception | +4 |"));
+            assert!(sheet.contains("| Saves | Fortitude +4, Reflex +4, Will +4 |"));
+        });
+    }
+
+    #[test]
+    fn sheets_list_defenses_attacks_and_spellcasting() {
+        with_character(|character| {
+            let mut leather = Item::new("Leather Armor", 0, Bulk::new(1));
+            leather.set_armor(Some(Armor::new(ArmorCategory::Light, 1, Some(4))));
+            let mut longsword = Item::new("Longsword", 0, Bulk::new(1));
+            longsword.set_weapon(Some(Weapon::new(
+                WeaponCategory::Martial,
+                WeaponGroup::Sword,
+                8,
+                DamageType::Slashing,
+            )));
+            let inventory = character.inventory_mut();
+            inventory.add(leather, 1, ItemState::Worn).unwrap();
+            inventory.add(longsword, 1, ItemState::Held).unwrap();
+            character.add_spellcasting(SpellcastingEntry::new(
+                "Ritualist",
+                Tradition::Occult,
+                CastingType::Prepared,
+                AbilityScoreType::Charisma,
+            ));
+
+            let sheet = render_markdown(character);
+
+            assert!(sheet.contains(&format!(
+                "| AC | {} (Leather Armor) |",
+                character.armor_class()
+            )));
+            assert!(sheet.contains("| Speed | 25 feet (base 25 feet) |"));
+            assert!(sheet.contains("| Wealth | 15 gp |"));
+            let weapon = character.inventory().entries()[1].item().weapon().unwrap();
+            assert!(sheet.contains(&format!(
+                "| Longsword | {:+} | 1d8+2 slashing |",
+                character.attack_breakdown(weapon, &[]).total()
+            )));
+            assert!(sheet.contains(&format!(
+                "| Ritualist | Occult | {:+} | {} |",
+                character.spell_attack("Ritualist").unwrap(),
+                character.spell_dc("Ritualist").unwrap()
+            )));
+            assert!(render_html(character).contains("<tr><td>Longsword</td>"));
+        });
+    }
+
+    #[test]
+    fn html_escapes_values() {
+        with_character(|character| {
+            let sheet = render_html(character);
+
+            assert!(sheet.contains("<h1>Bob &lt;the | Bold&gt;</h1>"));
+            assert!(sheet.contains("<tr><td>Dexterity</td><td>12</td><td>+1</td></tr>"));
+        });
+    }
+
+    #[test]
+    fn custom_template() {
+        with_character(|character| {
+            let template = SheetTemplate::new(
+                SheetFormat::Markdown,
+                "{{class}} {{level}}: STR {{str}} ({{str_mod}})",
+                "",
+                "",
+                "",
+            )
+            .unwrap();
+
+            assert_eq!(
+                template.render(character).unwrap(),
+                "Fighter 1: STR 14 (+2)"
+            );
+        });
+    }
+}
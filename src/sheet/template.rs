@@ -0,0 +1,99 @@
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// A text template with `{{placeholder}}` slots.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Template> {
+        let mut segments = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            let position = source.len() - rest.len() + start;
+            let end = rest[start..]
+                .find("}}")
+                .ok_or(Error::UnclosedPlaceholder { position })?;
+            let name = rest[start + 2..start + end].trim();
+            if name.is_empty() {
+                return Err(Error::EmptyPlaceholder { position });
+            }
+            segments.push(Segment::Placeholder(name.to_string()));
+            rest = &rest[start + end + 2..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+        Ok(Template { segments })
+    }
+
+    /// Lists the names of all placeholders in the order they appear.
+    pub fn placeholders(&self) -> Vec<&str> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Placeholder(name) => Some(name.as_str()),
+                Segment::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// Fills in the placeholders. Fails if `lookup` doesn't know one of them.
+    pub fn render<F>(&self, lookup: F) -> Result<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut result = String::new();
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Literal(text) => result.push_str(text),
+                Segment::Placeholder(name) => result.push_str(
+                    &lookup(name).ok_or_else(|| Error::UnknownPlaceholder(name.clone()))?,
+                ),
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_fills_placeholders() {
+        let template = Template::parse("Hello {{ name }}, you are level {{level}}!").unwrap();
+
+        let result = template.render(|name| match name {
+            "name" => Some("Bob".to_string()),
+            "level" => Some("3".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(result.unwrap(), "Hello Bob, you are level 3!");
+    }
+
+    #[test]
+    #[should_panic]
+    fn render_unknown_placeholder_fails() {
+        let template = Template::parse("{{unknown}}").unwrap();
+
+        template.render(|_| None).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_unclosed_placeholder_fails() {
+        Template::parse("Hello {{name").unwrap();
+    }
+}
@@ -0,0 +1,9 @@
+#[allow(clippy::module_inception)]
+mod spell;
+mod spellcasting;
+
+pub use spell::{Spell, Tradition};
+pub use spellcasting::{
+    cantrip_rank, spell_slots, CastingType, KnownSpell, PreparedSpell, SlotProgression, SpellCast,
+    SpellcastingEntry, MAX_PREPARED_CANTRIPS,
+};
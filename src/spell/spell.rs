@@ -0,0 +1,105 @@
+use crate::content::Rarity;
+use serde::{Deserialize, Serialize};
+
+/// The source of magic a spell draws from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Tradition {
+    Arcane,
+    Divine,
+    Occult,
+    Primal,
+}
+
+impl Tradition {
+    pub fn all() -> [Tradition; 4] {
+        [
+            Tradition::Arcane,
+            Tradition::Divine,
+            Tradition::Occult,
+            Tradition::Primal,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Spell {
+    name: String,
+    description: String,
+    /// The lowest rank it can be cast at, 0 for cantrips.
+    rank: u32,
+    /// The number of actions it takes to cast, 0 for reactions and free actions.
+    actions: u32,
+    traditions: Vec<Tradition>,
+    rarity: Rarity,
+    access: Option<String>,
+}
+
+impl Spell {
+    pub fn new(name: &str, rank: u32, actions: u32) -> Spell {
+        Spell {
+            name: name.to_string(),
+            description: "".to_string(),
+            rank,
+            actions,
+            traditions: vec![],
+            rarity: Rarity::Common,
+            access: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn set_description(&mut self, description: &str) {
+        self.description = description.to_string();
+    }
+
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    /// Cantrips can be cast any number of times and are always heightened to half the caster's level.
+    pub fn is_cantrip(&self) -> bool {
+        self.rank == 0
+    }
+
+    pub fn actions(&self) -> u32 {
+        self.actions
+    }
+
+    pub fn traditions(&self) -> &Vec<Tradition> {
+        &self.traditions
+    }
+
+    pub fn add_tradition(&mut self, tradition: Tradition) {
+        if !self.traditions.contains(&tradition) {
+            self.traditions.push(tradition);
+        }
+    }
+
+    pub fn has_tradition(&self, tradition: Tradition) -> bool {
+        self.traditions.contains(&tradition)
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        self.rarity
+    }
+
+    pub fn set_rarity(&mut self, rarity: Rarity) {
+        self.rarity = rarity;
+    }
+
+    /// What grants access if it isn't common.
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+
+    pub fn set_access(&mut self, access: Option<&str>) {
+        self.access = access.map(str::to_string);
+    }
+}
@@ -0,0 +1,516 @@
+use crate::character::{AbilityScoreType, Proficiency};
+use crate::error::{Error, Result};
+use crate::spell::{Spell, Tradition};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How many cantrips a prepared caster can prepare each day.
+pub const MAX_PREPARED_CANTRIPS: usize = 5;
+
+/// How a caster readies their spells.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum CastingType {
+    /// Picks a spell from the spellbook for each slot during daily preparations.
+    Prepared,
+    /// Casts any spell of the repertoire with a slot of its rank.
+    Spontaneous,
+}
+
+/// The spell slots of the given rank a full caster of the given level has. A new rank is gained at every odd level
+/// with one slot less than the lower ranks, and a single 10th-rank slot at level 19.
+pub fn spell_slots(casting_type: CastingType, level: u32, rank: u32) -> u32 {
+    let full = match casting_type {
+        CastingType::Prepared => 3,
+        CastingType::Spontaneous => 4,
+    };
+    match rank {
+        1..=9 if level > 2 * rank - 1 => full,
+        1..=9 if level == 2 * rank - 1 => full - 1,
+        10 if level >= 19 => 1,
+        _ => 0,
+    }
+}
+
+/// How many spell slots of each rank a caster has at each level.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SlotProgression {
+    /// The slots of full casters, see `spell_slots`.
+    #[default]
+    Full,
+    /// Slots by level and rank, e.g. for casters with fewer slots. The first row holds the slots of ranks 1 to 10 at
+    /// level 1, the second those at level 2 and so on. Missing levels and ranks have no slots.
+    Table(Vec<Vec<u32>>),
+}
+
+impl SlotProgression {
+    pub fn slots(&self, casting_type: CastingType, level: u32, rank: u32) -> u32 {
+        match self {
+            SlotProgression::Full => spell_slots(casting_type, level, rank),
+            SlotProgression::Table(table) => level
+                .checked_sub(1)
+                .and_then(|level| table.get(level as usize))
+                .zip(rank.checked_sub(1))
+                .and_then(|(slots, rank)| slots.get(rank as usize))
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Cantrips are heightened to half the caster's level, rounded up.
+pub fn cantrip_rank(level: u32) -> u32 {
+    level.div_ceil(2).clamp(1, 10)
+}
+
+/// A spell a prepared caster readied in one of their slots.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PreparedSpell {
+    spell: String,
+    rank: u32,
+    /// Cast since the last daily preparations.
+    expended: bool,
+}
+
+impl PreparedSpell {
+    pub fn spell(&self) -> &str {
+        &self.spell
+    }
+
+    /// The rank of the slot, 0 for cantrips.
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    pub fn is_expended(&self) -> bool {
+        self.expended
+    }
+}
+
+/// A spell in a spellbook or repertoire with the rank it's known at. Spellbooks know spells at their own rank, while
+/// a repertoire can know a spell at higher ranks to cast it heightened.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KnownSpell {
+    spell: Spell,
+    rank: u32,
+}
+
+impl KnownSpell {
+    pub fn spell(&self) -> &Spell {
+        &self.spell
+    }
+
+    /// The rank it's known at, 0 for cantrips.
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+}
+
+/// The result of casting a spell. Resolving its effects is up to the caller.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SpellCast {
+    spell: Spell,
+    rank: u32,
+    attack: i32,
+    dc: i32,
+}
+
+impl SpellCast {
+    pub(crate) fn new(spell: Spell, rank: u32, attack: i32, dc: i32) -> SpellCast {
+        SpellCast {
+            spell,
+            rank,
+            attack,
+            dc,
+        }
+    }
+
+    pub fn spell(&self) -> &Spell {
+        &self.spell
+    }
+
+    /// The rank it was cast at.
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    /// The spell attack modifier, for spells that need an attack roll.
+    pub fn attack(&self) -> i32 {
+        self.attack
+    }
+
+    /// The spell DC, for spells that call for a save.
+    pub fn dc(&self) -> i32 {
+        self.dc
+    }
+}
+
+/// One way a character casts spells, e.g. a wizard's arcane spells, with its spells and used slots.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SpellcastingEntry {
+    name: String,
+    tradition: Tradition,
+    casting_type: CastingType,
+    /// The ability used for spell attacks and DCs.
+    ability: AbilityScoreType,
+    proficiency: Proficiency,
+    slot_progression: SlotProgression,
+    /// The spellbook of a prepared caster or the repertoire of a spontaneous one.
+    spells: Vec<KnownSpell>,
+    prepared: Vec<PreparedSpell>,
+    /// Slots a spontaneous caster used since the last daily preparations, by rank.
+    used: BTreeMap<u32, u32>,
+}
+
+impl SpellcastingEntry {
+    /// A spellcasting entry trained in spell attacks and DCs.
+    pub fn new(
+        name: &str,
+        tradition: Tradition,
+        casting_type: CastingType,
+        ability: AbilityScoreType,
+    ) -> SpellcastingEntry {
+        SpellcastingEntry {
+            name: name.to_string(),
+            tradition,
+            casting_type,
+            ability,
+            proficiency: Proficiency::Trained,
+            slot_progression: SlotProgression::Full,
+            spells: vec![],
+            prepared: vec![],
+            used: BTreeMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tradition(&self) -> Tradition {
+        self.tradition
+    }
+
+    pub fn casting_type(&self) -> CastingType {
+        self.casting_type
+    }
+
+    pub fn ability(&self) -> AbilityScoreType {
+        self.ability
+    }
+
+    pub fn proficiency(&self) -> Proficiency {
+        self.proficiency
+    }
+
+    pub fn set_proficiency(&mut self, proficiency: Proficiency) {
+        self.proficiency = proficiency;
+    }
+
+    pub fn slot_progression(&self) -> &SlotProgression {
+        &self.slot_progression
+    }
+
+    pub fn set_slot_progression(&mut self, slot_progression: SlotProgression) {
+        self.slot_progression = slot_progression;
+    }
+
+    pub fn spells(&self) -> &Vec<KnownSpell> {
+        &self.spells
+    }
+
+    /// Adds a spell at its own rank to the spellbook or repertoire, replacing the spell if it's already known at that
+    /// rank. The spell has to be of the entry's tradition.
+    pub fn add_spell(&mut self, spell: Spell) -> Result<()> {
+        let rank = spell.rank();
+        self.add_spell_at(spell, rank)
+    }
+
+    /// Adds a spell to the repertoire at a rank above its own, so it can be cast heightened with slots of that rank.
+    /// Spellbooks only hold spells at their own rank.
+    pub fn add_spell_at(&mut self, spell: Spell, rank: u32) -> Result<()> {
+        if !spell.has_tradition(self.tradition) {
+            return Err(Error::SpellNotInTradition {
+                spell: spell.name().to_string(),
+                tradition: self.tradition,
+            });
+        }
+        if rank < spell.rank() || rank > 10 || (spell.is_cantrip() && rank > 0) {
+            return Err(Error::InvalidSpellRank {
+                spell: spell.name().to_string(),
+                rank,
+            });
+        }
+        if self.casting_type != CastingType::Spontaneous && rank != spell.rank() {
+            return Err(Error::NotSpontaneousCaster(self.name.clone()));
+        }
+        self.spells.retain(|known| {
+            !known.spell.name().eq_ignore_ascii_case(spell.name()) || known.rank != rank
+        });
+        self.spells.push(KnownSpell { spell, rank });
+        Ok(())
+    }
+
+    /// The spell with the given name, at whatever rank it's known.
+    pub fn spell(&self, name: &str) -> Option<&Spell> {
+        self.spells
+            .iter()
+            .map(KnownSpell::spell)
+            .find(|spell| spell.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the spell is known at the given rank. Cantrips are known at rank 0.
+    pub fn knows_at(&self, name: &str, rank: u32) -> bool {
+        self.spells
+            .iter()
+            .any(|known| known.spell.name().eq_ignore_ascii_case(name) && known.rank == rank)
+    }
+
+    pub fn prepared(&self) -> &Vec<PreparedSpell> {
+        &self.prepared
+    }
+
+    pub fn slots(&self, level: u32, rank: u32) -> u32 {
+        self.slot_progression.slots(self.casting_type, level, rank)
+    }
+
+    /// How many spells of the rank can still be cast today: unexpended prepared spells or unused slots.
+    pub fn remaining_slots(&self, level: u32, rank: u32) -> u32 {
+        match self.casting_type {
+            CastingType::Prepared => self
+                .prepared
+                .iter()
+                .filter(|prepared| prepared.rank == rank && !prepared.expended)
+                .count() as u32,
+            CastingType::Spontaneous => self
+                .slots(level, rank)
+                .saturating_sub(self.used.get(&rank).copied().unwrap_or(0)),
+        }
+    }
+
+    /// Forgets all prepared spells, to prepare new ones.
+    pub fn clear_prepared(&mut self) {
+        self.prepared.clear();
+    }
+
+    fn known_spell(&self, name: &str) -> Result<&Spell> {
+        self.spell(name)
+            .ok_or_else(|| Error::UnknownSpell(name.to_string()))
+    }
+
+    /// Prepares a spell from the spellbook in a slot of the given rank, which can be higher than the spell's own
+    /// rank to heighten it. Cantrips are prepared at rank 0.
+    pub(crate) fn prepare(&mut self, name: &str, rank: u32, level: u32) -> Result<()> {
+        if self.casting_type != CastingType::Prepared {
+            return Err(Error::NotPreparedCaster(self.name.clone()));
+        }
+        let spell = self.known_spell(name)?;
+        let name = spell.name().to_string();
+        let rank = if spell.is_cantrip() { 0 } else { rank };
+        if rank < spell.rank() || rank > 10 {
+            return Err(Error::InvalidSpellRank { spell: name, rank });
+        }
+        let prepared = self
+            .prepared
+            .iter()
+            .filter(|prepared| prepared.rank == rank)
+            .count();
+        let slots = if rank == 0 {
+            MAX_PREPARED_CANTRIPS
+        } else {
+            self.slots(level, rank) as usize
+        };
+        if prepared >= slots {
+            return Err(Error::NoSpellSlots { rank });
+        }
+        self.prepared.push(PreparedSpell {
+            spell: name,
+            rank,
+            expended: false,
+        });
+        Ok(())
+    }
+
+    /// Uses up a prepared spell or a slot and returns the spell with the rank it's cast at. Spontaneous casters can
+    /// only cast spells at ranks they know them at. Cantrips use nothing and are cast at `cantrip_rank`.
+    pub(crate) fn cast(&mut self, name: &str, rank: u32, level: u32) -> Result<(Spell, u32)> {
+        let spell = self.known_spell(name)?.clone();
+        let rank = if spell.is_cantrip() { 0 } else { rank };
+        match self.casting_type {
+            CastingType::Prepared => {
+                let prepared = self
+                    .prepared
+                    .iter_mut()
+                    .find(|prepared| {
+                        prepared.spell == spell.name()
+                            && prepared.rank == rank
+                            && !prepared.expended
+                    })
+                    .ok_or_else(|| Error::SpellNotPrepared {
+                        spell: spell.name().to_string(),
+                        rank,
+                    })?;
+                // cantrips stay prepared
+                prepared.expended = rank > 0;
+            }
+            CastingType::Spontaneous if rank > 0 => {
+                if !self.knows_at(spell.name(), rank) {
+                    return Err(Error::InvalidSpellRank {
+                        spell: spell.name().to_string(),
+                        rank,
+                    });
+                }
+                if self.remaining_slots(level, rank) == 0 {
+                    return Err(Error::NoSpellSlots { rank });
+                }
+                *self.used.entry(rank).or_insert(0) += 1;
+            }
+            CastingType::Spontaneous => {}
+        }
+        let rank = if spell.is_cantrip() {
+            cantrip_rank(level)
+        } else {
+            rank
+        };
+        Ok((spell, rank))
+    }
+
+    /// Regains all slots and prepared spells.
+    pub(crate) fn reset(&mut self) {
+        self.used.clear();
+        for prepared in &mut self.prepared {
+            prepared.expended = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spell(name: &str, rank: u32) -> Spell {
+        let mut spell = Spell::new(name, rank, 2);
+        spell.add_tradition(Tradition::Arcane);
+        spell
+    }
+
+    #[test]
+    fn slots_follow_the_progression() {
+        assert_eq!(spell_slots(CastingType::Prepared, 1, 1), 2);
+        assert_eq!(spell_slots(CastingType::Prepared, 2, 1), 3);
+        assert_eq!(spell_slots(CastingType::Spontaneous, 5, 3), 3);
+        assert_eq!(spell_slots(CastingType::Spontaneous, 6, 3), 4);
+        assert_eq!(spell_slots(CastingType::Prepared, 4, 3), 0);
+        assert_eq!(spell_slots(CastingType::Prepared, 19, 10), 1);
+        assert_eq!(cantrip_rank(5), 3);
+    }
+
+    #[test]
+    fn prepared_spells_are_expended() {
+        let mut entry = SpellcastingEntry::new(
+            "Wizard",
+            Tradition::Arcane,
+            CastingType::Prepared,
+            AbilityScoreType::Intelligence,
+        );
+        let mut heal = Spell::new("Heal", 1, 2);
+        heal.add_tradition(Tradition::Divine);
+        assert!(entry.add_spell(heal).is_err());
+        entry.add_spell(spell("Force Barrage", 1)).unwrap();
+        entry.add_spell(spell("Light", 0)).unwrap();
+
+        entry.prepare("Force Barrage", 1, 1).unwrap();
+        entry.prepare("force barrage", 1, 1).unwrap();
+        assert!(matches!(
+            entry.prepare("Force Barrage", 1, 1),
+            Err(Error::NoSpellSlots { rank: 1 })
+        ));
+        entry.prepare("Light", 1, 1).unwrap();
+
+        assert_eq!(entry.cast("Force Barrage", 1, 1).unwrap().1, 1);
+        assert_eq!(entry.remaining_slots(1, 1), 1);
+        entry.cast("Force Barrage", 1, 1).unwrap();
+        assert!(entry.cast("Force Barrage", 1, 1).is_err());
+        assert_eq!(entry.cast("Light", 0, 3).unwrap().1, 2);
+        entry.cast("Light", 0, 3).unwrap();
+
+        entry.reset();
+        assert_eq!(entry.remaining_slots(1, 1), 2);
+    }
+
+    #[test]
+    fn spontaneous_casters_use_slots() {
+        let mut entry = SpellcastingEntry::new(
+            "Sorcerer",
+            Tradition::Arcane,
+            CastingType::Spontaneous,
+            AbilityScoreType::Charisma,
+        );
+        entry.add_spell(spell("Force Barrage", 1)).unwrap();
+        assert!(matches!(
+            entry.prepare("Force Barrage", 1, 1),
+            Err(Error::NotPreparedCaster(_))
+        ));
+
+        for _ in 0..3 {
+            entry.cast("Force Barrage", 1, 1).unwrap();
+        }
+        assert!(matches!(
+            entry.cast("Force Barrage", 1, 1),
+            Err(Error::NoSpellSlots { rank: 1 })
+        ));
+        assert!(entry.cast("Fireball", 3, 5).is_err());
+        entry.reset();
+        assert_eq!(entry.remaining_slots(1, 1), 3);
+    }
+
+    #[test]
+    fn repertoires_know_heightened_spells() {
+        let mut entry = SpellcastingEntry::new(
+            "Sorcerer",
+            Tradition::Arcane,
+            CastingType::Spontaneous,
+            AbilityScoreType::Charisma,
+        );
+        entry.add_spell(spell("Force Barrage", 1)).unwrap();
+        entry.add_spell_at(spell("Force Barrage", 1), 3).unwrap();
+        assert_eq!(entry.spells().len(), 2);
+        assert!(matches!(
+            entry.add_spell_at(spell("Force Barrage", 1), 11),
+            Err(Error::InvalidSpellRank { rank: 11, .. })
+        ));
+
+        assert_eq!(entry.cast("Force Barrage", 3, 5).unwrap().1, 3);
+        entry.cast("Force Barrage", 1, 5).unwrap();
+        assert!(matches!(
+            entry.cast("Force Barrage", 2, 5),
+            Err(Error::InvalidSpellRank { rank: 2, .. })
+        ));
+
+        let mut wizard = SpellcastingEntry::new(
+            "Wizard",
+            Tradition::Arcane,
+            CastingType::Prepared,
+            AbilityScoreType::Intelligence,
+        );
+        assert!(matches!(
+            wizard.add_spell_at(spell("Force Barrage", 1), 3),
+            Err(Error::NotSpontaneousCaster(_))
+        ));
+    }
+
+    #[test]
+    fn slot_tables_replace_the_full_progression() {
+        let mut entry = SpellcastingEntry::new(
+            "Magus",
+            Tradition::Arcane,
+            CastingType::Prepared,
+            AbilityScoreType::Intelligence,
+        );
+        entry.set_slot_progression(SlotProgression::Table(vec![vec![1], vec![2]]));
+        assert_eq!(entry.slots(1, 1), 1);
+        assert_eq!(entry.slots(2, 1), 2);
+        assert_eq!(entry.slots(2, 2), 0);
+        assert_eq!(entry.slots(3, 1), 0);
+        assert_eq!(entry.slots(2, 0), 0);
+    }
+}